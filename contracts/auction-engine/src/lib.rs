@@ -4,14 +4,35 @@
 //! Events:
 //! - ("auction", "created"): [auction_id: u64, publisher: Address]
 //! - ("bid", "placed"): [auction_id: u64, bidder: Address, amount: i128]
-//! - ("auction", "settle"): [auction_id: u64, winner: Option<Address>, amount: Option<i128>]
+//! - ("bid", "commit"): [auction_id: u64, bidder: Address]
+//! - ("bid", "reveal"): [auction_id: u64, bidder: Address, amount: i128]
+//! - ("bid", "refund"): [auction_id: u64, bidder: Address, amount: i128]
+//! - ("slot", "won"): [auction_id: u64, slot_index: u32, bidder: Address, clearing_price: i128]
+//! - ("auction", "settle"): [auction_id: u64, winner: Option<Address>, clearing_price: Option<i128>]
 
 #![no_std]
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short,
-    token, Address, Env, String,
+    token, vec, xdr::ToXdr, Address, Bytes, BytesN, Env, IntoVal, Map, String, Symbol, Vec,
 };
 
+/// How bids are collected and what the winner pays.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum AuctionType {
+    /// Open ascending auction; the winner pays their own bid.
+    FirstPrice,
+    /// Open ascending auction; the winner pays max(second bid, reserve) + one increment.
+    SecondPrice,
+    /// Commit/reveal auction with second-price settlement (Vickrey).
+    SealedBid,
+    /// Open auction for `slot_count` placements cleared together by
+    /// generalized second price. Bids are not escrowed: each winner is
+    /// checked against its campaign budget, and the orchestrator pays the
+    /// clearing price to the publisher out of that budget.
+    Batch,
+}
+
 #[contracttype]
 #[derive(Clone, PartialEq)]
 pub enum AuctionStatus {
@@ -27,13 +48,17 @@ pub struct Auction {
     pub auction_id: u64,
     pub publisher: Address,
     pub impression_slot: String,
+    pub auction_type: AuctionType,
     pub floor_price: i128,
     pub reserve_price: i128,
     pub start_time: u64,
     pub end_time: u64,
+    pub reveal_end_time: u64, // equals end_time unless sealed
+    pub slot_count: u32,      // 1 unless batch
     pub status: AuctionStatus,
     pub winning_bid: Option<i128>,
     pub winner: Option<Address>,
+    pub clearing_price: Option<i128>,
     pub bid_count: u32,
 }
 
@@ -46,22 +71,51 @@ pub struct Bid {
     pub timestamp: u64,
 }
 
+#[contracttype]
+#[derive(Clone)]
+pub struct SlotWinner {
+    pub slot_index: u32,
+    pub bidder: Address,
+    pub campaign_id: u64,
+    pub bid_amount: i128,
+    pub clearing_price: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct BidCommitment {
+    pub commitment: BytesN<32>, // sha256(amount as 16 BE bytes || salt)
+    pub campaign_id: u64,
+    pub committed_at: u64,
+}
+
 #[contracttype]
 pub enum DataKey {
     Admin,
     TokenAddress,
     AuctionCounter,
+    BidIncrement,
+    RevealWindow,
+    CampaignOrchestrator,
     Auction(u64),
     BidCount(u64),
     Bid(u64, u32),       // auction_id, bid_index
     HighestBid(u64),
+    SecondHighestBid(u64),
     BidderBid(u64, Address),  // auction_id, bidder
+    BidCommit(u64, Address),  // auction_id, bidder
+    Escrow(u64, Address),     // auction_id, bidder -> locked funds
+    BatchWinners(u64),
 }
 
 const INSTANCE_LIFETIME_THRESHOLD: u32 = 17_280;
 const INSTANCE_BUMP_AMOUNT: u32 = 86_400;
 const PERSISTENT_LIFETIME_THRESHOLD: u32 = 17_280;
 const PERSISTENT_BUMP_AMOUNT: u32 = 86_400;
+const DEFAULT_BID_INCREMENT: i128 = 1;
+const DEFAULT_REVEAL_WINDOW: u64 = 3_600; // 1 hour
+const MAX_BATCH_SLOTS: u32 = 10;
+const MAX_BATCH_BIDS: u32 = 50;
 
 #[contract]
 pub struct AuctionEngineContract;
@@ -83,6 +137,7 @@ impl AuctionEngineContract {
         env: Env,
         publisher: Address,
         impression_slot: String,
+        auction_type: AuctionType,
         floor_price: i128,
         reserve_price: i128,
         duration_secs: u64,
@@ -90,35 +145,53 @@ impl AuctionEngineContract {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         publisher.require_auth();
 
-        let counter: u64 = env.storage().instance().get(&DataKey::AuctionCounter).unwrap_or(0);
-        let auction_id = counter + 1;
+        if auction_type == AuctionType::Batch {
+            panic!("use create_batch_auction");
+        }
 
-        let now = env.ledger().timestamp();
-        let auction = Auction {
-            auction_id,
-            publisher: publisher.clone(),
+        let auction = Self::_new_auction(
+            &env,
+            publisher,
             impression_slot,
+            auction_type,
             floor_price,
             reserve_price,
-            start_time: now,
-            end_time: now + duration_secs,
-            status: AuctionStatus::Open,
-            winning_bid: None,
-            winner: None,
-            bid_count: 0,
-        };
+            duration_secs,
+        );
+        Self::_store_new_auction(&env, &auction)
+    }
 
-        let _ttl_key = DataKey::Auction(auction_id);
-        env.storage().persistent().set(&_ttl_key, &auction);
-        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
-        env.storage().instance().set(&DataKey::AuctionCounter, &auction_id);
+    /// Sell `slot_count` placements of one page in a single auction.
+    pub fn create_batch_auction(
+        env: Env,
+        publisher: Address,
+        impression_slot: String,
+        slot_count: u32,
+        floor_price: i128,
+        reserve_price: i128,
+        duration_secs: u64,
+    ) -> u64 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        publisher.require_auth();
 
-        env.events().publish(
-            (symbol_short!("auction"), symbol_short!("created")),
-            (auction_id, publisher),
-        );
+        if slot_count == 0 || slot_count > MAX_BATCH_SLOTS {
+            panic!("invalid slot count");
+        }
+        if !env.storage().instance().has(&DataKey::CampaignOrchestrator) {
+            panic!("campaign orchestrator not set");
+        }
 
-        auction_id
+        let mut auction = Self::_new_auction(
+            &env,
+            publisher,
+            impression_slot,
+            AuctionType::Batch,
+            floor_price,
+            reserve_price,
+            duration_secs,
+        );
+        auction.slot_count = slot_count;
+        Self::_store_new_auction(&env, &auction)
     }
 
     pub fn place_bid(env: Env, bidder: Address, auction_id: u64, amount: i128, campaign_id: u64) {
@@ -131,6 +204,10 @@ impl AuctionEngineContract {
             .get(&DataKey::Auction(auction_id))
             .expect("auction not found");
 
+        if auction.auction_type == AuctionType::SealedBid {
+            panic!("sealed auction: use commit_bid");
+        }
+
         if auction.status != AuctionStatus::Open {
             panic!("auction not open");
         }
@@ -144,50 +221,163 @@ impl AuctionEngineContract {
             panic!("bid below floor price");
         }
 
-        // Check if higher than current best
-        let current_high: Option<i128> = env.storage().persistent().get(&DataKey::HighestBid(auction_id));
-        if let Some(high) = current_high {
-            if amount <= high {
-                panic!("bid too low");
+        if auction.auction_type == AuctionType::Batch {
+            // Every bid competes for a slot, so there is no minimum raise,
+            // but each bidder gets one bid per batch
+            if env.storage().persistent().has(&DataKey::BidderBid(auction_id, bidder.clone())) {
+                panic!("bid already placed");
+            }
+            if auction.bid_count >= MAX_BATCH_BIDS {
+                panic!("too many bids");
+            }
+        } else {
+            // Check if higher than current best
+            let current_high: Option<i128> = env.storage().persistent().get(&DataKey::HighestBid(auction_id));
+            if let Some(high) = current_high {
+                if amount <= high {
+                    panic!("bid too low");
+                }
             }
         }
 
-        let bid = Bid {
-            bidder: bidder.clone(),
-            amount,
+        // Lock the bid; a leader raising their own bid only tops up the difference.
+        // Batch bids are backed by the campaign budget instead.
+        if auction.auction_type != AuctionType::Batch {
+            let escrowed = Self::_escrow_of(&env, auction_id, &bidder);
+            Self::_token_client(&env).transfer(&bidder, &env.current_contract_address(), &(amount - escrowed));
+            Self::_set_escrow(&env, auction_id, &bidder, amount);
+        }
+
+        let outbid = auction.winner.clone();
+        Self::_record_bid(&env, &mut auction, &bidder, amount, campaign_id);
+
+        // Return the previous leader's funds as soon as they are outbid
+        if let Some(previous) = outbid {
+            if previous != bidder && auction.auction_type != AuctionType::Batch {
+                Self::_refund(&env, auction_id, &previous);
+            }
+        }
+
+        env.events().publish(
+            (symbol_short!("bid"), symbol_short!("placed")),
+            (auction_id, bidder, amount),
+        );
+    }
+
+    /// Sealed-bid phase: submit `sha256(auction_id as 8 big-endian bytes ||
+    /// bidder address XDR || amount as 16 big-endian bytes || salt)`, which
+    /// binds the commitment to this auction and bidder.
+    /// `deposit` is escrowed now and must cover the revealed amount; it may be
+    /// larger than the bid so the deposit does not leak it.
+    pub fn commit_bid(
+        env: Env,
+        bidder: Address,
+        auction_id: u64,
+        commitment: BytesN<32>,
+        campaign_id: u64,
+        deposit: i128,
+    ) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        bidder.require_auth();
+
+        let auction: Auction = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Auction(auction_id))
+            .expect("auction not found");
+
+        if auction.auction_type != AuctionType::SealedBid {
+            panic!("not a sealed auction");
+        }
+
+        if auction.status != AuctionStatus::Open {
+            panic!("auction not open");
+        }
+
+        let now = env.ledger().timestamp();
+        if now > auction.end_time {
+            panic!("auction ended");
+        }
+
+        let commit_key = DataKey::BidCommit(auction_id, bidder.clone());
+        if env.storage().persistent().has(&commit_key) {
+            panic!("bid already committed");
+        }
+        if deposit <= 0 {
+            panic!("invalid deposit");
+        }
+
+        Self::_token_client(&env).transfer(&bidder, &env.current_contract_address(), &deposit);
+        Self::_set_escrow(&env, auction_id, &bidder, deposit);
+
+        let commit = BidCommitment {
+            commitment,
             campaign_id,
-            timestamp: now,
+            committed_at: now,
         };
+        env.storage().persistent().set(&commit_key, &commit);
+        env.storage().persistent().extend_ttl(&commit_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
 
-        let bid_count: u32 = env.storage().persistent().get(&DataKey::BidCount(auction_id)).unwrap_or(0);
-        let _ttl_key = DataKey::Bid(auction_id, bid_count);
-        env.storage().persistent().set(&_ttl_key, &bid);
-        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
-        let _ttl_key = DataKey::BidCount(auction_id);
-        env.storage().persistent().set(&_ttl_key, &(bid_count + 1));
-        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
-        let _ttl_key = DataKey::BidderBid(auction_id, bidder.clone());
-        env.storage().persistent().set(&_ttl_key, &amount);
-        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
-        let _ttl_key = DataKey::HighestBid(auction_id);
-        env.storage().persistent().set(&_ttl_key, &amount);
-        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.events().publish(
+            (symbol_short!("bid"), symbol_short!("commit")),
+            (auction_id, bidder),
+        );
+    }
 
-        auction.bid_count += 1;
-        auction.winning_bid = Some(amount);
-        auction.winner = Some(bidder.clone());
-        let _ttl_key = DataKey::Auction(auction_id);
-        env.storage().persistent().set(&_ttl_key, &auction);
-        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    /// Reveal phase: open a previously committed sealed bid.
+    pub fn reveal_bid(env: Env, bidder: Address, auction_id: u64, amount: i128, salt: BytesN<32>) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        bidder.require_auth();
+
+        let mut auction: Auction = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Auction(auction_id))
+            .expect("auction not found");
+
+        if auction.status != AuctionStatus::Open {
+            panic!("auction not open");
+        }
+
+        let now = env.ledger().timestamp();
+        if now <= auction.end_time {
+            panic!("reveal phase not started");
+        }
+        if now > auction.reveal_end_time {
+            panic!("reveal phase ended");
+        }
+
+        let commit_key = DataKey::BidCommit(auction_id, bidder.clone());
+        let commit: BidCommitment = env
+            .storage()
+            .persistent()
+            .get(&commit_key)
+            .expect("no committed bid");
+
+        if Self::_bid_commitment(&env, auction_id, &bidder, amount, &salt) != commit.commitment {
+            panic!("commitment mismatch");
+        }
+
+        if amount < auction.floor_price {
+            panic!("bid below floor price");
+        }
+
+        if amount > Self::_escrow_of(&env, auction_id, &bidder) {
+            panic!("bid exceeds deposit");
+        }
+
+        env.storage().persistent().remove(&commit_key);
+        Self::_record_bid(&env, &mut auction, &bidder, amount, commit.campaign_id);
 
         env.events().publish(
-            (symbol_short!("bid"), symbol_short!("placed")),
+            (symbol_short!("bid"), symbol_short!("reveal")),
             (auction_id, bidder, amount),
         );
     }
 
     pub fn settle_auction(env: Env, caller: Address, auction_id: u64) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        caller.require_auth();
         let mut auction: Auction = env
             .storage()
             .persistent()
@@ -199,26 +389,19 @@ impl AuctionEngineContract {
             panic!("unauthorized");
         }
 
+        if auction.status != AuctionStatus::Open {
+            panic!("auction not open");
+        }
+
         let now = env.ledger().timestamp();
-        if now < auction.end_time && caller != admin {
+        if now < auction.reveal_end_time && caller != admin {
             panic!("auction still running");
         }
 
-        auction.status = if auction.winning_bid.is_some() {
-            let winning = auction.winning_bid.unwrap();
-            if winning >= auction.reserve_price {
-                // Transfer payment from winner to publisher
-                let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
-                let token_client = token::Client::new(&env, &token_addr);
-                if let Some(winner) = auction.winner.clone() {
-                    token_client.transfer(&winner, &auction.publisher, &winning);
-                }
-                AuctionStatus::Settled
-            } else {
-                AuctionStatus::Cancelled
-            }
+        auction.status = if auction.auction_type == AuctionType::Batch {
+            Self::_settle_batch(&env, &mut auction)
         } else {
-            AuctionStatus::Cancelled
+            Self::_settle_single(&env, &mut auction)
         };
 
         let _ttl_key = DataKey::Auction(auction_id);
@@ -227,10 +410,57 @@ impl AuctionEngineContract {
 
         env.events().publish(
             (symbol_short!("auction"), symbol_short!("settle")),
-            (auction_id, auction.winner, auction.winning_bid),
+            (auction_id, auction.winner, auction.clearing_price),
         );
     }
 
+    /// Pull a refund of escrowed funds. Only the current leader of an open
+    /// auction is locked; losing and unrevealed sealed bids can always withdraw.
+    pub fn withdraw_bid(env: Env, bidder: Address, auction_id: u64) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        bidder.require_auth();
+
+        let auction: Auction = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Auction(auction_id))
+            .expect("auction not found");
+
+        if auction.status == AuctionStatus::Open && auction.winner == Some(bidder.clone()) {
+            panic!("leading bid is locked");
+        }
+
+        let refunded = Self::_refund(&env, auction_id, &bidder);
+        if refunded == 0 {
+            panic!("nothing to withdraw");
+        }
+        refunded
+    }
+
+    pub fn set_campaign_orchestrator(env: Env, admin: Address, orchestrator: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::CampaignOrchestrator, &orchestrator);
+    }
+
+    pub fn set_bid_increment(env: Env, admin: Address, increment: i128) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        if increment < 0 {
+            panic!("invalid increment");
+        }
+        env.storage().instance().set(&DataKey::BidIncrement, &increment);
+    }
+
+    pub fn set_reveal_window(env: Env, admin: Address, window_secs: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        if window_secs == 0 {
+            panic!("invalid reveal window");
+        }
+        env.storage().instance().set(&DataKey::RevealWindow, &window_secs);
+    }
+
     pub fn get_auction(env: Env, auction_id: u64) -> Option<Auction> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage().persistent().get(&DataKey::Auction(auction_id))
@@ -250,6 +480,356 @@ impl AuctionEngineContract {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage().persistent().get(&DataKey::HighestBid(auction_id))
     }
+
+    pub fn get_second_highest_bid(env: Env, auction_id: u64) -> Option<i128> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::SecondHighestBid(auction_id))
+    }
+
+    pub fn get_batch_winners(env: Env, auction_id: u64) -> Vec<SlotWinner> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::BatchWinners(auction_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_escrow(env: Env, auction_id: u64, bidder: Address) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_escrow_of(&env, auction_id, &bidder)
+    }
+
+    pub fn get_bid_increment(env: Env) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::BidIncrement).unwrap_or(DEFAULT_BID_INCREMENT)
+    }
+
+    // ============================================================
+    // Internal Helpers
+    // ============================================================
+
+    fn _require_admin(env: &Env, admin: &Address) {
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if *admin != stored_admin {
+            panic!("unauthorized");
+        }
+    }
+
+    fn _new_auction(
+        env: &Env,
+        publisher: Address,
+        impression_slot: String,
+        auction_type: AuctionType,
+        floor_price: i128,
+        reserve_price: i128,
+        duration_secs: u64,
+    ) -> Auction {
+        let counter: u64 = env.storage().instance().get(&DataKey::AuctionCounter).unwrap_or(0);
+        let now = env.ledger().timestamp();
+        let end_time = now + duration_secs;
+        let reveal_end_time = if auction_type == AuctionType::SealedBid {
+            let window: u64 = env
+                .storage()
+                .instance()
+                .get(&DataKey::RevealWindow)
+                .unwrap_or(DEFAULT_REVEAL_WINDOW);
+            end_time + window
+        } else {
+            end_time
+        };
+
+        Auction {
+            auction_id: counter + 1,
+            publisher,
+            impression_slot,
+            auction_type,
+            floor_price,
+            reserve_price,
+            start_time: now,
+            end_time,
+            reveal_end_time,
+            slot_count: 1,
+            status: AuctionStatus::Open,
+            winning_bid: None,
+            winner: None,
+            clearing_price: None,
+            bid_count: 0,
+        }
+    }
+
+    fn _store_new_auction(env: &Env, auction: &Auction) -> u64 {
+        let auction_id = auction.auction_id;
+        let _ttl_key = DataKey::Auction(auction_id);
+        env.storage().persistent().set(&_ttl_key, auction);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.storage().instance().set(&DataKey::AuctionCounter, &auction_id);
+
+        env.events().publish(
+            (symbol_short!("auction"), symbol_short!("created")),
+            (auction_id, auction.publisher.clone()),
+        );
+
+        auction_id
+    }
+
+    fn _settle_single(env: &Env, auction: &mut Auction) -> AuctionStatus {
+        let auction_id = auction.auction_id;
+        match auction.winning_bid {
+            Some(winning) if winning >= auction.reserve_price => {
+                let price = Self::_clearing_price(env, auction, winning);
+                // Pay the publisher out of the winner's escrow and return any excess
+                if let Some(winner) = auction.winner.clone() {
+                    Self::_token_client(env).transfer(&env.current_contract_address(), &auction.publisher, &price);
+                    let escrowed = Self::_escrow_of(env, auction_id, &winner);
+                    Self::_set_escrow(env, auction_id, &winner, escrowed - price);
+                    Self::_refund(env, auction_id, &winner);
+                }
+                auction.clearing_price = Some(price);
+                AuctionStatus::Settled
+            }
+            _ => {
+                if let Some(winner) = auction.winner.clone() {
+                    Self::_refund(env, auction_id, &winner);
+                }
+                AuctionStatus::Cancelled
+            }
+        }
+    }
+
+    /// Generalized second price: bids are ranked by amount (earlier bid wins
+    /// ties), bids whose campaign cannot cover them are skipped, and the winner
+    /// of slot i pays max(next eligible bid, reserve) plus one increment out
+    /// of its campaign budget.
+    fn _settle_batch(env: &Env, auction: &mut Auction) -> AuctionStatus {
+        let auction_id = auction.auction_id;
+
+        let mut ranked: Vec<Bid> = Vec::new(env);
+        for i in 0..auction.bid_count {
+            let bid: Bid = env.storage().persistent().get(&DataKey::Bid(auction_id, i)).unwrap();
+            let mut pos = ranked.len();
+            for (j, other) in ranked.iter().enumerate() {
+                if bid.amount > other.amount {
+                    pos = j as u32;
+                    break;
+                }
+            }
+            ranked.insert(pos, bid);
+        }
+
+        // Keep one eligible bid beyond the last slot to price it
+        let mut eligible: Vec<Bid> = Vec::new(env);
+        let mut committed: Map<u64, i128> = Map::new(env);
+        for bid in ranked.iter() {
+            if eligible.len() > auction.slot_count || bid.amount < auction.reserve_price {
+                break;
+            }
+            let already = committed.get(bid.campaign_id).unwrap_or(0);
+            if Self::_spendable_budget(env, bid.campaign_id, &bid.bidder) - already < bid.amount {
+                continue;
+            }
+            if eligible.len() < auction.slot_count {
+                committed.set(bid.campaign_id, already + bid.amount);
+            }
+            eligible.push_back(bid);
+        }
+
+        let increment: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::BidIncrement)
+            .unwrap_or(DEFAULT_BID_INCREMENT);
+        let mut winners: Vec<SlotWinner> = Vec::new(env);
+        for slot_index in 0..eligible.len().min(auction.slot_count) {
+            let bid = eligible.get(slot_index).unwrap();
+            let next = eligible
+                .get(slot_index + 1)
+                .map(|b| b.amount)
+                .unwrap_or(auction.reserve_price);
+            let price = (next.max(auction.reserve_price) + increment).min(bid.amount);
+
+            Self::_charge_budget(env, bid.campaign_id, &bid.bidder, &auction.publisher, price);
+
+            env.events().publish(
+                (symbol_short!("slot"), symbol_short!("won")),
+                (auction_id, slot_index, bid.bidder.clone(), price),
+            );
+            winners.push_back(SlotWinner {
+                slot_index,
+                bidder: bid.bidder,
+                campaign_id: bid.campaign_id,
+                bid_amount: bid.amount,
+                clearing_price: price,
+            });
+        }
+
+        let _ttl_key = DataKey::BatchWinners(auction_id);
+        env.storage().persistent().set(&_ttl_key, &winners);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        match winners.first() {
+            Some(top) => {
+                auction.winner = Some(top.bidder);
+                auction.winning_bid = Some(top.bid_amount);
+                auction.clearing_price = Some(top.clearing_price);
+                AuctionStatus::Settled
+            }
+            None => {
+                auction.winner = None;
+                auction.winning_bid = None;
+                AuctionStatus::Cancelled
+            }
+        }
+    }
+
+    /// Budget the campaign-orchestrator still lets `advertiser` spend on `campaign_id`.
+    fn _spendable_budget(env: &Env, campaign_id: u64, advertiser: &Address) -> i128 {
+        let orchestrator: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::CampaignOrchestrator)
+            .expect("campaign orchestrator not set");
+        env.invoke_contract(
+            &orchestrator,
+            &Symbol::new(env, "get_spendable_budget"),
+            vec![env, campaign_id.into_val(env), advertiser.into_val(env)],
+        )
+    }
+
+    /// Pay `publisher` for a won slot out of the campaign's budget, so the
+    /// same budget cannot back bids in later auctions.
+    fn _charge_budget(env: &Env, campaign_id: u64, advertiser: &Address, publisher: &Address, amount: i128) {
+        let orchestrator: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::CampaignOrchestrator)
+            .expect("campaign orchestrator not set");
+        let _: () = env.invoke_contract(
+            &orchestrator,
+            &Symbol::new(env, "charge_auction_win"),
+            vec![
+                env,
+                env.current_contract_address().into_val(env),
+                campaign_id.into_val(env),
+                advertiser.into_val(env),
+                publisher.into_val(env),
+                amount.into_val(env),
+            ],
+        );
+    }
+
+    fn _token_client(env: &Env) -> token::Client<'_> {
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        token::Client::new(env, &token_addr)
+    }
+
+    fn _escrow_of(env: &Env, auction_id: u64, bidder: &Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Escrow(auction_id, bidder.clone()))
+            .unwrap_or(0)
+    }
+
+    fn _set_escrow(env: &Env, auction_id: u64, bidder: &Address, amount: i128) {
+        let _ttl_key = DataKey::Escrow(auction_id, bidder.clone());
+        env.storage().persistent().set(&_ttl_key, &amount);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    /// Returns a bidder's whole escrow to them and clears it.
+    fn _refund(env: &Env, auction_id: u64, bidder: &Address) -> i128 {
+        let amount = Self::_escrow_of(env, auction_id, bidder);
+        if amount > 0 {
+            env.storage().persistent().remove(&DataKey::Escrow(auction_id, bidder.clone()));
+            Self::_token_client(env).transfer(&env.current_contract_address(), bidder, &amount);
+            env.events().publish(
+                (symbol_short!("bid"), symbol_short!("refund")),
+                (auction_id, bidder.clone(), amount),
+            );
+        }
+        amount
+    }
+
+    fn _bid_commitment(env: &Env, auction_id: u64, bidder: &Address, amount: i128, salt: &BytesN<32>) -> BytesN<32> {
+        let mut data = Bytes::from_slice(env, &auction_id.to_be_bytes());
+        data.append(&bidder.clone().to_xdr(env));
+        data.extend_from_array(&amount.to_be_bytes());
+        data.append(&Bytes::from(salt.clone()));
+        env.crypto().sha256(&data).into()
+    }
+
+    /// Stores the bid and updates the highest / second-highest bookkeeping.
+    /// Reveals arrive in any order, so a bid may land below the current best.
+    fn _record_bid(env: &Env, auction: &mut Auction, bidder: &Address, amount: i128, campaign_id: u64) {
+        let auction_id = auction.auction_id;
+        let bid = Bid {
+            bidder: bidder.clone(),
+            amount,
+            campaign_id,
+            timestamp: env.ledger().timestamp(),
+        };
+
+        let bid_count: u32 = env.storage().persistent().get(&DataKey::BidCount(auction_id)).unwrap_or(0);
+        let _ttl_key = DataKey::Bid(auction_id, bid_count);
+        env.storage().persistent().set(&_ttl_key, &bid);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let _ttl_key = DataKey::BidCount(auction_id);
+        env.storage().persistent().set(&_ttl_key, &(bid_count + 1));
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let _ttl_key = DataKey::BidderBid(auction_id, bidder.clone());
+        env.storage().persistent().set(&_ttl_key, &amount);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        let current_high: Option<i128> = env.storage().persistent().get(&DataKey::HighestBid(auction_id));
+        let current_second: Option<i128> = env.storage().persistent().get(&DataKey::SecondHighestBid(auction_id));
+        let (new_high, new_second) = match current_high {
+            Some(high) if amount <= high => {
+                if current_second.is_none_or(|second| amount > second) {
+                    (None, Some(amount))
+                } else {
+                    (None, None)
+                }
+            }
+            _ => (Some(amount), current_high),
+        };
+
+        if let Some(high) = new_high {
+            let _ttl_key = DataKey::HighestBid(auction_id);
+            env.storage().persistent().set(&_ttl_key, &high);
+            env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+            auction.winning_bid = Some(high);
+            auction.winner = Some(bidder.clone());
+        }
+        if let Some(second) = new_second {
+            let _ttl_key = DataKey::SecondHighestBid(auction_id);
+            env.storage().persistent().set(&_ttl_key, &second);
+            env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        }
+
+        auction.bid_count += 1;
+        let _ttl_key = DataKey::Auction(auction_id);
+        env.storage().persistent().set(&_ttl_key, auction);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    /// First-price pays the winning bid. Second-price and sealed auctions pay
+    /// max(second-highest bid, reserve) plus one increment, capped at the winning bid.
+    fn _clearing_price(env: &Env, auction: &Auction, winning: i128) -> i128 {
+        if auction.auction_type == AuctionType::FirstPrice {
+            return winning;
+        }
+        let increment: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::BidIncrement)
+            .unwrap_or(DEFAULT_BID_INCREMENT);
+        let second: i128 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::SecondHighestBid(auction.auction_id))
+            .unwrap_or(auction.reserve_price);
+        (second.max(auction.reserve_price) + increment).min(winning)
+    }
 }
 
 mod test;
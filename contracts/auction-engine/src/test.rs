@@ -1,11 +1,52 @@
 #![cfg(test)]
 use super::*;
 use soroban_sdk::{
+    contract as soroban_contract, contractimpl as soroban_contractimpl,
     testutils::{Address as _, Ledger},
     token::{Client as TokenClient, StellarAssetClient},
-    Address, Env, String,
+    Address, BytesN, Env, String,
 };
 
+// ─── Mock campaign orchestrator ──────────────────────────────────────────────
+// Batch settlement asks the orchestrator how much budget each winning
+// campaign can still spend, and has it pay won slots out of that budget.
+
+#[soroban_contract]
+pub struct MockOrchestrator;
+
+#[soroban_contractimpl]
+impl MockOrchestrator {
+    pub fn set_token(env: Env, token: Address) {
+        env.storage().instance().set(&symbol_short!("token"), &token);
+    }
+
+    pub fn set_budget(env: Env, campaign_id: u64, amount: i128) {
+        env.storage().instance().set(&campaign_id, &amount);
+    }
+
+    /// Called by AuctionEngineContract::settle_auction via invoke_contract.
+    pub fn get_spendable_budget(env: Env, campaign_id: u64, _advertiser: Address) -> i128 {
+        env.storage().instance().get::<u64, i128>(&campaign_id).unwrap_or(0)
+    }
+
+    pub fn charge_auction_win(
+        env: Env,
+        _auction_engine: Address,
+        campaign_id: u64,
+        _advertiser: Address,
+        publisher: Address,
+        amount: i128,
+    ) {
+        let budget: i128 = env.storage().instance().get(&campaign_id).unwrap_or(0);
+        if budget < amount {
+            panic!("insufficient budget");
+        }
+        env.storage().instance().set(&campaign_id, &(budget - amount));
+        let token: Address = env.storage().instance().get(&symbol_short!("token")).unwrap();
+        TokenClient::new(&env, &token).transfer(&env.current_contract_address(), &publisher, &amount);
+    }
+}
+
 // ─── helpers ─────────────────────────────────────────────────────────────────
 
 fn deploy_token(env: &Env, admin: &Address) -> Address {
@@ -85,6 +126,7 @@ fn test_create_auction() {
     let auction_id = client.create_auction(
         &publisher,
         &slot(&env),
+        &AuctionType::FirstPrice,
         &1_000i128,   // floor
         &5_000i128,   // reserve
         &3600u64,     // 1 hour
@@ -106,12 +148,13 @@ fn test_create_auction() {
 fn test_place_bid() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _, _, _) = setup(&env);
+    let (client, _, _, token_addr) = setup(&env);
     let publisher = Address::generate(&env);
     let bidder = Address::generate(&env);
+    mint(&env, &token_addr, &bidder, 100_000);
 
     let auction_id = client.create_auction(
-        &publisher, &slot(&env), &1_000i128, &5_000i128, &3600u64,
+        &publisher, &slot(&env), &AuctionType::FirstPrice, &1_000i128, &5_000i128, &3600u64,
     );
 
     client.place_bid(&bidder, &auction_id, &2_000i128, &42u64);
@@ -129,13 +172,15 @@ fn test_place_bid() {
 fn test_multiple_bids_highest_wins() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _, _, _) = setup(&env);
+    let (client, _, _, token_addr) = setup(&env);
     let publisher = Address::generate(&env);
     let bidder1 = Address::generate(&env);
     let bidder2 = Address::generate(&env);
+    mint(&env, &token_addr, &bidder1, 100_000);
+    mint(&env, &token_addr, &bidder2, 100_000);
 
     let auction_id = client.create_auction(
-        &publisher, &slot(&env), &1_000i128, &5_000i128, &3600u64,
+        &publisher, &slot(&env), &AuctionType::FirstPrice, &1_000i128, &5_000i128, &3600u64,
     );
 
     client.place_bid(&bidder1, &auction_id, &2_000i128, &1u64);
@@ -152,12 +197,13 @@ fn test_multiple_bids_highest_wins() {
 fn test_bid_stored_by_index() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _, _, _) = setup(&env);
+    let (client, _, _, token_addr) = setup(&env);
     let publisher = Address::generate(&env);
     let bidder = Address::generate(&env);
+    mint(&env, &token_addr, &bidder, 100_000);
 
     let auction_id = client.create_auction(
-        &publisher, &slot(&env), &500i128, &2_000i128, &3600u64,
+        &publisher, &slot(&env), &AuctionType::FirstPrice, &500i128, &2_000i128, &3600u64,
     );
 
     client.place_bid(&bidder, &auction_id, &1_000i128, &99u64);
@@ -175,12 +221,13 @@ fn test_bid_stored_by_index() {
 fn test_bid_below_floor_rejected() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _, _, _) = setup(&env);
+    let (client, _, _, token_addr) = setup(&env);
     let publisher = Address::generate(&env);
     let bidder = Address::generate(&env);
+    mint(&env, &token_addr, &bidder, 100_000);
 
     let auction_id = client.create_auction(
-        &publisher, &slot(&env), &1_000i128, &5_000i128, &3600u64,
+        &publisher, &slot(&env), &AuctionType::FirstPrice, &1_000i128, &5_000i128, &3600u64,
     );
 
     client.place_bid(&bidder, &auction_id, &500i128, &1u64); // below 1_000
@@ -191,13 +238,15 @@ fn test_bid_below_floor_rejected() {
 fn test_bid_not_higher_than_current_rejected() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _, _, _) = setup(&env);
+    let (client, _, _, token_addr) = setup(&env);
     let publisher = Address::generate(&env);
     let bidder1 = Address::generate(&env);
     let bidder2 = Address::generate(&env);
+    mint(&env, &token_addr, &bidder1, 100_000);
+    mint(&env, &token_addr, &bidder2, 100_000);
 
     let auction_id = client.create_auction(
-        &publisher, &slot(&env), &1_000i128, &5_000i128, &3600u64,
+        &publisher, &slot(&env), &AuctionType::FirstPrice, &1_000i128, &5_000i128, &3600u64,
     );
 
     client.place_bid(&bidder1, &auction_id, &3_000i128, &1u64);
@@ -209,12 +258,13 @@ fn test_bid_not_higher_than_current_rejected() {
 fn test_bid_after_auction_ended() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _, _, _) = setup(&env);
+    let (client, _, _, token_addr) = setup(&env);
     let publisher = Address::generate(&env);
     let bidder = Address::generate(&env);
+    mint(&env, &token_addr, &bidder, 100_000);
 
     let auction_id = client.create_auction(
-        &publisher, &slot(&env), &1_000i128, &5_000i128, &100u64, // 100 second duration
+        &publisher, &slot(&env), &AuctionType::FirstPrice, &1_000i128, &5_000i128, &100u64, // 100 second duration
     );
 
     // advance past end_time
@@ -246,7 +296,7 @@ fn test_settle_auction_with_winner() {
     mint(&env, &token_addr, &bidder, 100_000);
 
     let auction_id = client.create_auction(
-        &publisher, &slot(&env), &1_000i128, &2_000i128, &100u64,
+        &publisher, &slot(&env), &AuctionType::FirstPrice, &1_000i128, &2_000i128, &100u64,
     );
 
     client.place_bid(&bidder, &auction_id, &3_000i128, &1u64); // above reserve
@@ -284,7 +334,7 @@ fn test_settle_auction_below_reserve_cancelled() {
     mint(&env, &token_addr, &bidder, 100_000);
 
     let auction_id = client.create_auction(
-        &publisher, &slot(&env),
+        &publisher, &slot(&env), &AuctionType::FirstPrice,
         &1_000i128,   // floor
         &10_000i128,  // reserve (high)
         &100u64,
@@ -315,7 +365,7 @@ fn test_settle_auction_no_bids_cancelled() {
     let publisher = Address::generate(&env);
 
     let auction_id = client.create_auction(
-        &publisher, &slot(&env), &1_000i128, &5_000i128, &100u64,
+        &publisher, &slot(&env), &AuctionType::FirstPrice, &1_000i128, &5_000i128, &100u64,
     );
 
     env.ledger().with_mut(|li| {
@@ -333,12 +383,13 @@ fn test_settle_auction_no_bids_cancelled() {
 fn test_settle_auction_still_running() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _, _, _) = setup(&env);
+    let (client, _, _, token_addr) = setup(&env);
     let publisher = Address::generate(&env);
     let bidder = Address::generate(&env);
+    mint(&env, &token_addr, &bidder, 100_000);
 
     let auction_id = client.create_auction(
-        &publisher, &slot(&env), &1_000i128, &5_000i128, &3600u64,
+        &publisher, &slot(&env), &AuctionType::FirstPrice, &1_000i128, &5_000i128, &3600u64,
     );
 
     client.place_bid(&bidder, &auction_id, &2_000i128, &1u64);
@@ -356,7 +407,7 @@ fn test_settle_auction_unauthorized() {
     let stranger = Address::generate(&env);
 
     let auction_id = client.create_auction(
-        &publisher, &slot(&env), &1_000i128, &5_000i128, &100u64,
+        &publisher, &slot(&env), &AuctionType::FirstPrice, &1_000i128, &5_000i128, &100u64,
     );
 
     env.ledger().with_mut(|li| {
@@ -386,7 +437,7 @@ fn test_admin_can_settle_before_end_time() {
     mint(&env, &token_addr, &bidder, 100_000);
 
     let auction_id = client.create_auction(
-        &publisher, &slot(&env), &1_000i128, &2_000i128, &9999u64,
+        &publisher, &slot(&env), &AuctionType::FirstPrice, &1_000i128, &2_000i128, &9999u64,
     );
 
     client.place_bid(&bidder, &auction_id, &5_000i128, &1u64);
@@ -410,3 +461,600 @@ fn test_get_auction_nonexistent_returns_none() {
     assert_eq!(client.get_bid_count(&999u64), 0);
     assert!(client.get_highest_bid(&999u64).is_none());
 }
+
+// ─── second-price ────────────────────────────────────────────────────────────
+
+#[test]
+fn test_second_price_winner_pays_second_bid_plus_increment() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    let (client, _, _, token_addr) = setup(&env);
+    let publisher = Address::generate(&env);
+    let bidder1 = Address::generate(&env);
+    let bidder2 = Address::generate(&env);
+    mint(&env, &token_addr, &bidder1, 100_000);
+    mint(&env, &token_addr, &bidder2, 100_000);
+
+    let auction_id = client.create_auction(
+        &publisher, &slot(&env), &AuctionType::SecondPrice, &1_000i128, &2_000i128, &100u64,
+    );
+
+    client.place_bid(&bidder1, &auction_id, &3_000i128, &1u64);
+    client.place_bid(&bidder2, &auction_id, &8_000i128, &2u64);
+    assert_eq!(client.get_second_highest_bid(&auction_id), Some(3_000));
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 200;
+    });
+    client.settle_auction(&publisher, &auction_id);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert!(matches!(auction.status, AuctionStatus::Settled));
+    assert_eq!(auction.winning_bid, Some(8_000));
+    assert_eq!(auction.clearing_price, Some(3_001));
+
+    let tc = TokenClient::new(&env, &token_addr);
+    assert_eq!(tc.balance(&publisher), 3_001);
+    assert_eq!(tc.balance(&bidder2), 96_999);
+}
+
+#[test]
+fn test_second_price_single_bid_pays_reserve_plus_increment() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    let (client, admin, _, token_addr) = setup(&env);
+    let publisher = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    mint(&env, &token_addr, &bidder, 100_000);
+
+    client.set_bid_increment(&admin, &10i128);
+    let auction_id = client.create_auction(
+        &publisher, &slot(&env), &AuctionType::SecondPrice, &1_000i128, &2_000i128, &100u64,
+    );
+    client.place_bid(&bidder, &auction_id, &5_000i128, &1u64);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 200;
+    });
+    client.settle_auction(&publisher, &auction_id);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.clearing_price, Some(2_010));
+    assert_eq!(TokenClient::new(&env, &token_addr).balance(&publisher), 2_010);
+}
+
+#[test]
+fn test_second_price_capped_at_winning_bid() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    let (client, admin, _, token_addr) = setup(&env);
+    let publisher = Address::generate(&env);
+    let bidder1 = Address::generate(&env);
+    let bidder2 = Address::generate(&env);
+    mint(&env, &token_addr, &bidder1, 100_000);
+    mint(&env, &token_addr, &bidder2, 100_000);
+
+    client.set_bid_increment(&admin, &500i128);
+    let auction_id = client.create_auction(
+        &publisher, &slot(&env), &AuctionType::SecondPrice, &1_000i128, &2_000i128, &100u64,
+    );
+    client.place_bid(&bidder1, &auction_id, &3_000i128, &1u64);
+    client.place_bid(&bidder2, &auction_id, &3_100i128, &2u64);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 200;
+    });
+    client.settle_auction(&publisher, &auction_id);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.clearing_price, Some(3_100));
+}
+
+#[test]
+#[should_panic(expected = "auction not open")]
+fn test_settle_twice_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, _) = setup(&env);
+    let publisher = Address::generate(&env);
+
+    let auction_id = client.create_auction(
+        &publisher, &slot(&env), &AuctionType::FirstPrice, &1_000i128, &5_000i128, &100u64,
+    );
+    env.ledger().with_mut(|li| {
+        li.timestamp = 200;
+    });
+    client.settle_auction(&publisher, &auction_id);
+    client.settle_auction(&publisher, &auction_id);
+}
+
+// ─── sealed-bid ──────────────────────────────────────────────────────────────
+
+fn commit_for(env: &Env, auction_id: u64, bidder: &Address, amount: i128, salt: &BytesN<32>) -> BytesN<32> {
+    AuctionEngineContract::_bid_commitment(env, auction_id, bidder, amount, salt)
+}
+
+#[test]
+fn test_sealed_bid_commit_reveal_settles_second_price() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+    let (client, _, _, token_addr) = setup(&env);
+    let publisher = Address::generate(&env);
+    let bidder1 = Address::generate(&env);
+    let bidder2 = Address::generate(&env);
+    mint(&env, &token_addr, &bidder1, 100_000);
+    mint(&env, &token_addr, &bidder2, 100_000);
+
+    let auction_id = client.create_auction(
+        &publisher, &slot(&env), &AuctionType::SealedBid, &1_000i128, &2_000i128, &100u64,
+    );
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.reveal_end_time, 100 + 3_600);
+
+    let salt1 = BytesN::from_array(&env, &[1u8; 32]);
+    let salt2 = BytesN::from_array(&env, &[2u8; 32]);
+    client.commit_bid(&bidder1, &auction_id, &commit_for(&env, auction_id, &bidder1, 9_000, &salt1), &1u64, &10_000i128);
+    client.commit_bid(&bidder2, &auction_id, &commit_for(&env, auction_id, &bidder2, 4_000, &salt2), &2u64, &10_000i128);
+
+    // Nothing is visible until the reveal phase
+    assert_eq!(client.get_bid_count(&auction_id), 0);
+    assert!(client.get_highest_bid(&auction_id).is_none());
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 200;
+    });
+    // Reveal order does not matter
+    client.reveal_bid(&bidder1, &auction_id, &9_000i128, &salt1);
+    client.reveal_bid(&bidder2, &auction_id, &4_000i128, &salt2);
+
+    assert_eq!(client.get_highest_bid(&auction_id), Some(9_000));
+    assert_eq!(client.get_second_highest_bid(&auction_id), Some(4_000));
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 100 + 3_600 + 1;
+    });
+    client.settle_auction(&publisher, &auction_id);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert!(matches!(auction.status, AuctionStatus::Settled));
+    assert_eq!(auction.winner, Some(bidder1.clone()));
+    assert_eq!(auction.clearing_price, Some(4_001));
+
+    // Winner's excess deposit is returned at settlement; the loser pulls theirs
+    let tc = TokenClient::new(&env, &token_addr);
+    assert_eq!(tc.balance(&bidder1), 95_999);
+    assert_eq!(tc.balance(&publisher), 4_001);
+    assert_eq!(client.get_escrow(&auction_id, &bidder2), 10_000);
+    assert_eq!(client.withdraw_bid(&bidder2, &auction_id), 10_000);
+    assert_eq!(tc.balance(&bidder2), 100_000);
+}
+
+#[test]
+#[should_panic(expected = "commitment mismatch")]
+fn test_sealed_bid_copied_commitment_cannot_be_revealed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, token_addr) = setup(&env);
+    let publisher = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    let shill = Address::generate(&env);
+    mint(&env, &token_addr, &bidder, 100_000);
+    mint(&env, &token_addr, &shill, 100_000);
+
+    let auction_id = client.create_auction(
+        &publisher, &slot(&env), &AuctionType::SealedBid, &1_000i128, &2_000i128, &100u64,
+    );
+    let salt = BytesN::from_array(&env, &[7u8; 32]);
+    let commitment = commit_for(&env, auction_id, &bidder, 3_000, &salt);
+    client.commit_bid(&bidder, &auction_id, &commitment, &1u64, &10_000i128);
+    client.commit_bid(&shill, &auction_id, &commitment, &2u64, &10_000i128);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 200;
+    });
+    client.reveal_bid(&bidder, &auction_id, &3_000i128, &salt);
+    client.reveal_bid(&shill, &auction_id, &3_000i128, &salt);
+}
+
+#[test]
+#[should_panic(expected = "commitment mismatch")]
+fn test_sealed_bid_reveal_wrong_amount_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, token_addr) = setup(&env);
+    let publisher = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    mint(&env, &token_addr, &bidder, 100_000);
+
+    let auction_id = client.create_auction(
+        &publisher, &slot(&env), &AuctionType::SealedBid, &1_000i128, &2_000i128, &100u64,
+    );
+    let salt = BytesN::from_array(&env, &[7u8; 32]);
+    client.commit_bid(&bidder, &auction_id, &commit_for(&env, auction_id, &bidder, 3_000, &salt), &1u64, &10_000i128);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 200;
+    });
+    client.reveal_bid(&bidder, &auction_id, &5_000i128, &salt);
+}
+
+#[test]
+#[should_panic(expected = "reveal phase not started")]
+fn test_sealed_bid_reveal_during_commit_phase_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, token_addr) = setup(&env);
+    let publisher = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    mint(&env, &token_addr, &bidder, 100_000);
+
+    let auction_id = client.create_auction(
+        &publisher, &slot(&env), &AuctionType::SealedBid, &1_000i128, &2_000i128, &100u64,
+    );
+    let salt = BytesN::from_array(&env, &[7u8; 32]);
+    client.commit_bid(&bidder, &auction_id, &commit_for(&env, auction_id, &bidder, 3_000, &salt), &1u64, &10_000i128);
+    client.reveal_bid(&bidder, &auction_id, &3_000i128, &salt);
+}
+
+#[test]
+#[should_panic(expected = "sealed auction: use commit_bid")]
+fn test_sealed_bid_rejects_open_bids() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, token_addr) = setup(&env);
+    let publisher = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    mint(&env, &token_addr, &bidder, 100_000);
+
+    let auction_id = client.create_auction(
+        &publisher, &slot(&env), &AuctionType::SealedBid, &1_000i128, &2_000i128, &100u64,
+    );
+    client.place_bid(&bidder, &auction_id, &3_000i128, &1u64);
+}
+
+#[test]
+#[should_panic(expected = "auction still running")]
+fn test_sealed_bid_settle_during_reveal_phase_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, _) = setup(&env);
+    let publisher = Address::generate(&env);
+
+    let auction_id = client.create_auction(
+        &publisher, &slot(&env), &AuctionType::SealedBid, &1_000i128, &2_000i128, &100u64,
+    );
+    env.ledger().with_mut(|li| {
+        li.timestamp = 200;
+    });
+    client.settle_auction(&publisher, &auction_id);
+}
+
+// ─── escrow ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_bid_is_escrowed_and_outbid_bidder_refunded() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, token_addr) = setup(&env);
+    let publisher = Address::generate(&env);
+    let bidder1 = Address::generate(&env);
+    let bidder2 = Address::generate(&env);
+    mint(&env, &token_addr, &bidder1, 100_000);
+    mint(&env, &token_addr, &bidder2, 100_000);
+    let tc = TokenClient::new(&env, &token_addr);
+
+    let auction_id = client.create_auction(
+        &publisher, &slot(&env), &AuctionType::FirstPrice, &1_000i128, &2_000i128, &3600u64,
+    );
+
+    client.place_bid(&bidder1, &auction_id, &3_000i128, &1u64);
+    assert_eq!(tc.balance(&bidder1), 97_000);
+    assert_eq!(tc.balance(&client.address), 3_000);
+    assert_eq!(client.get_escrow(&auction_id, &bidder1), 3_000);
+
+    client.place_bid(&bidder2, &auction_id, &4_000i128, &2u64);
+    assert_eq!(tc.balance(&bidder1), 100_000);
+    assert_eq!(client.get_escrow(&auction_id, &bidder1), 0);
+    assert_eq!(tc.balance(&client.address), 4_000);
+}
+
+#[test]
+fn test_leader_raising_bid_only_tops_up() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, token_addr) = setup(&env);
+    let publisher = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    mint(&env, &token_addr, &bidder, 100_000);
+
+    let auction_id = client.create_auction(
+        &publisher, &slot(&env), &AuctionType::FirstPrice, &1_000i128, &2_000i128, &3600u64,
+    );
+
+    client.place_bid(&bidder, &auction_id, &3_000i128, &1u64);
+    client.place_bid(&bidder, &auction_id, &5_000i128, &1u64);
+
+    assert_eq!(client.get_escrow(&auction_id, &bidder), 5_000);
+    assert_eq!(TokenClient::new(&env, &token_addr).balance(&bidder), 95_000);
+}
+
+#[test]
+fn test_settlement_pays_from_escrow_after_bidder_moves_funds() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, token_addr) = setup(&env);
+    let publisher = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    let elsewhere = Address::generate(&env);
+    mint(&env, &token_addr, &bidder, 3_000);
+    let tc = TokenClient::new(&env, &token_addr);
+
+    let auction_id = client.create_auction(
+        &publisher, &slot(&env), &AuctionType::FirstPrice, &1_000i128, &2_000i128, &100u64,
+    );
+    client.place_bid(&bidder, &auction_id, &3_000i128, &1u64);
+    assert_eq!(tc.balance(&bidder), 0);
+
+    // Moving the rest of the wallet cannot break settlement any more
+    mint(&env, &token_addr, &bidder, 500);
+    tc.transfer(&bidder, &elsewhere, &500);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 200;
+    });
+    client.settle_auction(&publisher, &auction_id);
+
+    assert_eq!(tc.balance(&publisher), 3_000);
+    assert_eq!(tc.balance(&client.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "leading bid is locked")]
+fn test_withdraw_leading_bid_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, token_addr) = setup(&env);
+    let publisher = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    mint(&env, &token_addr, &bidder, 100_000);
+
+    let auction_id = client.create_auction(
+        &publisher, &slot(&env), &AuctionType::FirstPrice, &1_000i128, &2_000i128, &3600u64,
+    );
+    client.place_bid(&bidder, &auction_id, &3_000i128, &1u64);
+    client.withdraw_bid(&bidder, &auction_id);
+}
+
+#[test]
+#[should_panic(expected = "nothing to withdraw")]
+fn test_withdraw_without_escrow_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, _) = setup(&env);
+    let publisher = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    let auction_id = client.create_auction(
+        &publisher, &slot(&env), &AuctionType::FirstPrice, &1_000i128, &2_000i128, &3600u64,
+    );
+    client.withdraw_bid(&stranger, &auction_id);
+}
+
+#[test]
+#[should_panic(expected = "bid exceeds deposit")]
+fn test_sealed_bid_reveal_above_deposit_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, token_addr) = setup(&env);
+    let publisher = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    mint(&env, &token_addr, &bidder, 100_000);
+
+    let auction_id = client.create_auction(
+        &publisher, &slot(&env), &AuctionType::SealedBid, &1_000i128, &2_000i128, &100u64,
+    );
+    let salt = BytesN::from_array(&env, &[3u8; 32]);
+    client.commit_bid(&bidder, &auction_id, &commit_for(&env, auction_id, &bidder, 20_000, &salt), &1u64, &10_000i128);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 200;
+    });
+    client.reveal_bid(&bidder, &auction_id, &20_000i128, &salt);
+}
+
+// ─── batch (GSP) ─────────────────────────────────────────────────────────────
+
+fn setup_batch(env: &Env) -> (AuctionEngineContractClient<'_>, MockOrchestratorClient<'_>, Address) {
+    let (client, admin, _, token_addr) = setup(env);
+    let orchestrator_id = env.register_contract(None, MockOrchestrator);
+    client.set_campaign_orchestrator(&admin, &orchestrator_id);
+    let orchestrator = MockOrchestratorClient::new(env, &orchestrator_id);
+    orchestrator.set_token(&token_addr);
+    mint(env, &token_addr, &orchestrator_id, 1_000_000);
+    (client, orchestrator, token_addr)
+}
+
+#[test]
+fn test_batch_auction_clears_k_winners_at_generalized_second_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, orchestrator, token_addr) = setup_batch(&env);
+    let publisher = Address::generate(&env);
+    let tc = TokenClient::new(&env, &token_addr);
+
+    let auction_id = client.create_batch_auction(
+        &publisher, &slot(&env), &3u32, &1_000i128, &2_000i128, &100u64,
+    );
+    let amounts = [5_000i128, 9_000, 3_000, 7_000];
+    let mut bidders = soroban_sdk::Vec::<Address>::new(&env);
+    for (i, amount) in amounts.iter().enumerate() {
+        let bidder = Address::generate(&env);
+        mint(&env, &token_addr, &bidder, 100_000);
+        orchestrator.set_budget(&(i as u64 + 1), &1_000_000i128);
+        client.place_bid(&bidder, &auction_id, amount, &(i as u64 + 1));
+        bidders.push_back(bidder);
+    }
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 200;
+    });
+    client.settle_auction(&publisher, &auction_id);
+
+    let winners = client.get_batch_winners(&auction_id);
+    assert_eq!(winners.len(), 3);
+    let w0 = winners.get(0).unwrap();
+    let w1 = winners.get(1).unwrap();
+    let w2 = winners.get(2).unwrap();
+    assert_eq!((w0.bidder.clone(), w0.bid_amount, w0.clearing_price), (bidders.get(1).unwrap(), 9_000, 7_001));
+    assert_eq!((w1.bidder.clone(), w1.bid_amount, w1.clearing_price), (bidders.get(3).unwrap(), 7_000, 5_001));
+    assert_eq!((w2.bidder.clone(), w2.bid_amount, w2.clearing_price), (bidders.get(0).unwrap(), 5_000, 3_001));
+
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert!(matches!(auction.status, AuctionStatus::Settled));
+    assert_eq!(auction.winner, Some(w0.bidder.clone()));
+
+    // Clearing prices are paid from the campaign budgets, not by the bidders
+    assert_eq!(tc.balance(&publisher), 7_001 + 5_001 + 3_001);
+    assert_eq!(tc.balance(&orchestrator.address), 1_000_000 - (7_001 + 5_001 + 3_001));
+    assert_eq!(orchestrator.get_spendable_budget(&2u64, &bidders.get(1).unwrap()), 1_000_000 - 7_001);
+    for bidder in bidders.iter() {
+        assert_eq!(tc.balance(&bidder), 100_000);
+    }
+    assert_eq!(tc.balance(&client.address), 0);
+}
+
+#[test]
+fn test_batch_auction_skips_bids_over_campaign_budget() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, orchestrator, token_addr) = setup_batch(&env);
+    let publisher = Address::generate(&env);
+    let rich = Address::generate(&env);
+    let paced_a = Address::generate(&env);
+    let paced_b = Address::generate(&env);
+    let broke = Address::generate(&env);
+    for bidder in [&rich, &paced_a, &paced_b, &broke] {
+        mint(&env, &token_addr, bidder, 100_000);
+    }
+
+    orchestrator.set_budget(&1u64, &1_000_000i128);
+    orchestrator.set_budget(&2u64, &6_000i128); // covers one of its two bids
+    orchestrator.set_budget(&3u64, &100i128);
+
+    let auction_id = client.create_batch_auction(
+        &publisher, &slot(&env), &2u32, &1_000i128, &2_000i128, &100u64,
+    );
+    client.place_bid(&broke, &auction_id, &10_000i128, &3u64);
+    client.place_bid(&paced_a, &auction_id, &5_000i128, &2u64);
+    client.place_bid(&paced_b, &auction_id, &4_000i128, &2u64);
+    client.place_bid(&rich, &auction_id, &3_000i128, &1u64);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 200;
+    });
+    client.settle_auction(&publisher, &auction_id);
+
+    let winners = client.get_batch_winners(&auction_id);
+    assert_eq!(winners.len(), 2);
+    assert_eq!(winners.get(0).unwrap().bidder, paced_a);
+    assert_eq!(winners.get(0).unwrap().clearing_price, 3_001);
+    assert_eq!(winners.get(1).unwrap().bidder, rich);
+    assert_eq!(winners.get(1).unwrap().clearing_price, 2_001);
+
+    let tc = TokenClient::new(&env, &token_addr);
+    assert_eq!(tc.balance(&broke), 100_000);
+    assert_eq!(tc.balance(&paced_b), 100_000);
+}
+
+#[test]
+fn test_batch_auction_wins_are_charged_to_campaign_budget() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, orchestrator, token_addr) = setup_batch(&env);
+    let publisher = Address::generate(&env);
+    let advertiser = Address::generate(&env);
+    mint(&env, &token_addr, &advertiser, 100_000);
+    orchestrator.set_budget(&1u64, &6_000i128);
+
+    let first = client.create_batch_auction(&publisher, &slot(&env), &1u32, &1_000i128, &2_000i128, &100u64);
+    let second = client.create_batch_auction(&publisher, &slot(&env), &1u32, &1_000i128, &2_000i128, &100u64);
+    client.place_bid(&advertiser, &first, &5_000i128, &1u64);
+    client.place_bid(&advertiser, &second, &5_000i128, &1u64);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 200;
+    });
+    client.settle_auction(&publisher, &first);
+    assert_eq!(orchestrator.get_spendable_budget(&1u64, &advertiser), 6_000 - 2_001);
+
+    // The remaining budget no longer covers the second bid
+    client.settle_auction(&publisher, &second);
+    assert!(matches!(client.get_auction(&second).unwrap().status, AuctionStatus::Cancelled));
+    let tc = TokenClient::new(&env, &token_addr);
+    assert_eq!(tc.balance(&advertiser), 100_000);
+    assert_eq!(tc.balance(&publisher), 2_001);
+}
+
+#[test]
+fn test_batch_auction_without_eligible_bids_cancelled() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, token_addr) = setup_batch(&env);
+    let publisher = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    mint(&env, &token_addr, &bidder, 100_000);
+
+    let auction_id = client.create_batch_auction(
+        &publisher, &slot(&env), &2u32, &1_000i128, &2_000i128, &100u64,
+    );
+    client.place_bid(&bidder, &auction_id, &3_000i128, &1u64); // no budget
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 200;
+    });
+    client.settle_auction(&publisher, &auction_id);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert!(matches!(auction.status, AuctionStatus::Cancelled));
+    assert!(auction.winner.is_none());
+    assert_eq!(TokenClient::new(&env, &token_addr).balance(&bidder), 100_000);
+}
+
+#[test]
+#[should_panic(expected = "bid already placed")]
+fn test_batch_auction_one_bid_per_bidder() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, token_addr) = setup_batch(&env);
+    let publisher = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    mint(&env, &token_addr, &bidder, 100_000);
+
+    let auction_id = client.create_batch_auction(
+        &publisher, &slot(&env), &2u32, &1_000i128, &2_000i128, &100u64,
+    );
+    client.place_bid(&bidder, &auction_id, &3_000i128, &1u64);
+    client.place_bid(&bidder, &auction_id, &4_000i128, &1u64);
+}
+
+#[test]
+#[should_panic(expected = "campaign orchestrator not set")]
+fn test_batch_auction_requires_orchestrator() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, _) = setup(&env);
+    let publisher = Address::generate(&env);
+
+    client.create_batch_auction(&publisher, &slot(&env), &2u32, &1_000i128, &2_000i128, &100u64);
+}
+
+#[test]
+#[should_panic(expected = "use create_batch_auction")]
+fn test_create_auction_rejects_batch_type() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, _) = setup(&env);
+    let publisher = Address::generate(&env);
+
+    client.create_auction(&publisher, &slot(&env), &AuctionType::Batch, &1_000i128, &2_000i128, &100u64);
+}
@@ -5,6 +5,9 @@
 //! - ("lifecycle", "transit"): [campaign_id: u64]
 //! - ("campaign", "pause"): [campaign_id: u64, actor: Address]
 //! - ("campaign", "resume"): [campaign_id: u64, actor: Address]
+//!
+//! The liquidity pool pauses campaigns whose loans default through
+//! `pause_for_default`.
 
 #![no_std]
 use soroban_sdk::{
@@ -57,6 +60,7 @@ pub struct StateTransition {
 pub enum DataKey {
     Admin,
     FraudContract,
+    LendingPool,
     LifecycleCounter,
     Lifecycle(u64),
     TransitionCount(u64),
@@ -111,6 +115,43 @@ impl CampaignLifecycleContract {
         );
     }
 
+    pub fn set_lending_pool(env: Env, admin: Address, pool: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        env.storage().instance().set(&DataKey::LendingPool, &pool);
+    }
+
+    /// Lending pool: pause a campaign whose loan defaulted. Campaigns that
+    /// are not running are left as they are.
+    pub fn pause_for_default(env: Env, pool: Address, campaign_id: u64, borrower: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        // `transition` requires the pool's auth
+        let stored_pool: Address = env.storage().instance().get(&DataKey::LendingPool).expect("lending pool not set");
+        if pool != stored_pool {
+            panic!("unauthorized lending pool");
+        }
+
+        let lifecycle: Option<CampaignLifecycle> = env.storage().persistent().get(&DataKey::Lifecycle(campaign_id));
+        // Only the defaulted borrower's own active campaign is paused
+        match lifecycle {
+            Some(lifecycle) if lifecycle.state == LifecycleState::Active && lifecycle.advertiser == borrower => {}
+            _ => return,
+        }
+
+        Self::transition(
+            env.clone(),
+            pool,
+            campaign_id,
+            LifecycleState::Paused,
+            String::from_str(&env, "paused for loan default"),
+        );
+    }
+
     pub fn register_campaign(
         env: Env,
         advertiser: Address,
@@ -171,16 +212,17 @@ impl CampaignLifecycleContract {
 
         let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
         let fraud_contract: Option<Address> = env.storage().instance().get(&DataKey::FraudContract);
-
-        // Only advertiser, admin or authorized fraud contract can transition
-        if actor != lifecycle.advertiser && actor != admin {
-            if let Some(fraud_addr) = fraud_contract {
-                if actor != fraud_addr {
-                    panic!("unauthorized");
-                }
-            } else {
-                panic!("unauthorized");
-            }
+        let lending_pool: Option<Address> = env.storage().instance().get(&DataKey::LendingPool);
+
+        // Only advertiser, admin, or the authorized fraud contract can transition;
+        // the lending pool may only pause
+        let pool_pausing = lending_pool.as_ref() == Some(&actor) && new_state == LifecycleState::Paused;
+        if actor != lifecycle.advertiser
+            && actor != admin
+            && fraud_contract.as_ref() != Some(&actor)
+            && !pool_pausing
+        {
+            panic!("unauthorized");
         }
 
         // Validate state transition
@@ -292,6 +334,14 @@ impl CampaignLifecycleContract {
             .get(&DataKey::Lifecycle(campaign_id))
     }
 
+    pub fn get_advertiser(env: Env, campaign_id: u64) -> Option<Address> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get::<_, CampaignLifecycle>(&DataKey::Lifecycle(campaign_id))
+            .map(|lifecycle| lifecycle.advertiser)
+    }
+
     pub fn get_transition(env: Env, campaign_id: u64, index: u32) -> Option<StateTransition> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage()
@@ -357,3 +357,70 @@ fn test_transition_recorded() {
     assert!(matches!(t.to_state, LifecycleState::PendingReview));
     assert_eq!(t.actor, advertiser);
 }
+
+// ─── pause_for_default ───────────────────────────────────────────────────────
+
+#[test]
+fn test_pause_for_default() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let advertiser = Address::generate(&env);
+    let pool = Address::generate(&env);
+
+    client.set_lending_pool(&admin, &pool);
+    client.register_campaign(&advertiser, &1u64, &10_000u32);
+    client.transition(&advertiser, &1u64, &LifecycleState::PendingReview, &make_reason(&env));
+    client.transition(&admin, &1u64, &LifecycleState::Active, &make_reason(&env));
+
+    client.pause_for_default(&pool, &1u64, &advertiser);
+    assert!(client.get_lifecycle(&1u64).unwrap().state == LifecycleState::Paused);
+
+    // Already paused: left alone
+    client.pause_for_default(&pool, &1u64, &advertiser);
+    assert_eq!(client.get_lifecycle(&1u64).unwrap().pause_count, 1);
+}
+
+#[test]
+#[should_panic(expected = "unauthorized lending pool")]
+fn test_pause_for_default_wrong_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    client.set_lending_pool(&admin, &Address::generate(&env));
+    client.pause_for_default(&Address::generate(&env), &1u64, &Address::generate(&env));
+}
+
+#[test]
+fn test_pause_for_default_skips_other_advertisers_campaign() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let advertiser = Address::generate(&env);
+    let pool = Address::generate(&env);
+
+    client.set_lending_pool(&admin, &pool);
+    client.register_campaign(&advertiser, &1u64, &10_000u32);
+    client.transition(&advertiser, &1u64, &LifecycleState::PendingReview, &make_reason(&env));
+    client.transition(&admin, &1u64, &LifecycleState::Active, &make_reason(&env));
+
+    client.pause_for_default(&pool, &1u64, &Address::generate(&env));
+    assert!(client.get_lifecycle(&1u64).unwrap().state == LifecycleState::Active);
+}
+
+#[test]
+#[should_panic(expected = "unauthorized")]
+fn test_lending_pool_can_only_pause() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let advertiser = Address::generate(&env);
+    let pool = Address::generate(&env);
+
+    client.set_lending_pool(&admin, &pool);
+    client.register_campaign(&advertiser, &1u64, &10_000u32);
+    client.transition(&advertiser, &1u64, &LifecycleState::PendingReview, &make_reason(&env));
+    client.transition(&admin, &1u64, &LifecycleState::Active, &make_reason(&env));
+
+    client.transition(&pool, &1u64, &LifecycleState::Cancelled, &make_reason(&env));
+}
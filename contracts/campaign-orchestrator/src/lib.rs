@@ -1,11 +1,17 @@
 //! PulsarTrack - Campaign Orchestrator (Soroban)
 //! Advanced decentralized advertising campaign orchestration on Stellar.
+//!
+//! Events:
+//! - ("batch", "submitted"): [batch_id: u64, campaign_id: u64, view_count: u64]
+//! - ("batch", "challenge"): [batch_id: u64, leaf: BytesN<32>]
+//! - ("batch", "settled"): [batch_id: u64, paid: i128]
 
 
 #![no_std]
 use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
     contract, contractimpl, contracttype, symbol_short,
-    token, Address, Env, String,
+    token, vec, xdr::ToXdr, Address, Bytes, BytesN, Env, IntoVal, String, Symbol, Vec,
 };
 
 // ============================================================
@@ -74,6 +80,62 @@ pub struct AdvertiserStats {
     pub join_ledger: u32,
 }
 
+/// Receipt signed off-chain by the viewer's SDK key for one view. The
+/// viewer signs its digest (see `get_receipt_digest`); the digest together
+/// with the signature (see `get_receipt_leaf`) is a leaf of a batch's
+/// Merkle tree.
+#[contracttype]
+#[derive(Clone)]
+pub struct ViewReceipt {
+    pub campaign_id: u64,
+    pub publisher: Address,
+    pub viewer_key: BytesN<32>,
+    pub nonce: u64,
+    pub timestamp: u64,
+    pub signature: BytesN<64>,
+}
+
+/// A receipt and its Merkle path in a batch
+#[contracttype]
+#[derive(Clone)]
+pub struct ViewProof {
+    pub batch_id: u64,
+    pub receipt: ViewReceipt,
+    pub proof: Vec<BytesN<32>>,
+}
+
+/// State of a challenged leaf. `Unanswered` signature challenges count as
+/// rejected until the publisher presents a valid signature.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum LeafStatus {
+    Rejected,
+    Unanswered,
+    Verified,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum BatchStatus {
+    Pending,
+    Settled,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ViewBatch {
+    pub campaign_id: u64,
+    pub publisher: Address,
+    pub merkle_root: BytesN<32>,
+    pub view_count: u64,
+    pub payout: i128,
+    pub cost_per_view: i128,
+    pub rejected_views: u64,
+    pub submitted_at: u64,
+    pub challenge_deadline: u64,
+    pub status: BatchStatus,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct CampaignMetrics {
@@ -99,6 +161,17 @@ pub enum DataKey {
     Publisher(Address),
     AdvertiserStats(Address),
     DailyViews(u64, u64),
+    FraudContract,
+    ChallengeWindow,
+    BatchCounter,
+    ViewBatch(u64),
+    ChallengedLeaf(u64, BytesN<32>),
+    RevenueSettlement,
+    SubscriptionManager,
+    LendingPool,
+    PerformanceOracle,
+    OracleFeeBps,
+    AuctionEngine,
 }
 
 // ============================================================
@@ -110,6 +183,9 @@ const INSTANCE_BUMP_AMOUNT: u32 = 86_400;
 const PERSISTENT_LIFETIME_THRESHOLD: u32 = 120_960;
 const PERSISTENT_BUMP_AMOUNT: u32 = 1_051_200;
 
+const DEFAULT_CHALLENGE_WINDOW_SECS: u64 = 86_400;
+const ANSWER_WINDOW_SECS: u64 = 43_200;
+
 #[contract]
 pub struct CampaignOrchestratorContract;
 
@@ -179,6 +255,14 @@ impl CampaignOrchestratorContract {
             panic!("invalid duration");
         }
 
+        if let Some(manager) = env.storage().instance().get::<_, Address>(&DataKey::SubscriptionManager) {
+            env.invoke_contract::<()>(
+                &manager,
+                &Symbol::new(&env, "consume_campaign"),
+                vec![&env, env.current_contract_address().into_val(&env), advertiser.into_val(&env)],
+            );
+        }
+
         let counter: u64 = env
             .storage()
             .instance()
@@ -298,15 +382,11 @@ impl CampaignOrchestratorContract {
         if daily_views >= campaign.daily_view_limit {
             panic!("daily view limit reached");
         }
+        Self::_consume_impressions(&env, &campaign.advertiser, 1);
+        Self::_consume_publisher(&env, &campaign.advertiser, &publisher);
 
         // Transfer payment to publisher
-        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
-        let token_client = token::Client::new(&env, &token_addr);
-        token_client.transfer(
-            &env.current_contract_address(),
-            &publisher,
-            &campaign.cost_per_view,
-        );
+        Self::_pay_publisher(&env, campaign_id, &publisher, campaign.cost_per_view);
 
         // Update campaign
         campaign.remaining_budget -= campaign.cost_per_view;
@@ -337,6 +417,318 @@ impl CampaignOrchestratorContract {
         );
     }
 
+    /// Commit a batch of ed25519-signed `ViewReceipt`s collected off-chain.
+    /// `merkle_root` covers the receipt leaves (`get_receipt_leaf`, a
+    /// sorted-pair sha256 tree); `payout` must equal
+    /// `view_count * cost_per_view` and is reserved from the budget until
+    /// the batch is finalized. Fraud-prevention may challenge individual
+    /// receipts until the challenge window closes.
+    pub fn settle_view_batch(
+        env: Env,
+        publisher: Address,
+        campaign_id: u64,
+        merkle_root: BytesN<32>,
+        view_count: u64,
+        payout: i128,
+    ) -> u64 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        publisher.require_auth();
+
+        let mut campaign: Campaign = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Campaign(campaign_id))
+            .expect("campaign not found");
+
+        let publisher_data: VerifiedPublisher = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Publisher(publisher.clone()))
+            .expect("publisher not verified");
+        if !publisher_data.verified {
+            panic!("publisher not verified");
+        }
+
+        match campaign.status {
+            CampaignStatus::Active => {}
+            _ => panic!("campaign not active"),
+        }
+        if env.ledger().sequence() > campaign.end_ledger {
+            panic!("campaign expired");
+        }
+
+        if view_count == 0 {
+            panic!("empty batch");
+        }
+        if payout != campaign.cost_per_view * view_count as i128 {
+            panic!("payout mismatch");
+        }
+        if campaign.remaining_budget < payout {
+            panic!("insufficient budget");
+        }
+
+        let current_day = env.ledger().timestamp() / 86_400;
+        let daily_key = DataKey::DailyViews(campaign_id, current_day);
+        let daily_views: u64 = env.storage().temporary().get(&daily_key).unwrap_or(0);
+        if daily_views + view_count > campaign.daily_view_limit {
+            panic!("daily view limit reached");
+        }
+        Self::_consume_impressions(&env, &campaign.advertiser, view_count);
+        Self::_consume_publisher(&env, &campaign.advertiser, &publisher);
+        env.storage()
+            .temporary()
+            .set(&daily_key, &(daily_views + view_count));
+
+        // Reserve the payout so the budget cannot be committed twice
+        campaign.remaining_budget -= payout;
+        campaign.last_updated = env.ledger().timestamp();
+        let _ttl_key = DataKey::Campaign(campaign_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &campaign);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        let window: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ChallengeWindow)
+            .unwrap_or(DEFAULT_CHALLENGE_WINDOW_SECS);
+        let now = env.ledger().timestamp();
+        let batch_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::BatchCounter)
+            .unwrap_or(0u64)
+            + 1;
+        let batch = ViewBatch {
+            campaign_id,
+            publisher,
+            merkle_root,
+            view_count,
+            payout,
+            cost_per_view: campaign.cost_per_view,
+            rejected_views: 0,
+            submitted_at: now,
+            challenge_deadline: now + window,
+            status: BatchStatus::Pending,
+        };
+
+        let _ttl_key = DataKey::ViewBatch(batch_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &batch);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.storage()
+            .instance()
+            .set(&DataKey::BatchCounter, &batch_id);
+
+        env.events().publish(
+            (symbol_short!("batch"), symbol_short!("submitted")),
+            (batch_id, campaign_id, view_count),
+        );
+
+        batch_id
+    }
+
+    /// Fraud contract: dispute one receipt of a pending batch during its
+    /// challenge window. The receipt is rejected at once if it belongs to
+    /// another campaign or publisher, or if `duplicate_of` proves the same
+    /// view (campaign, viewer key and nonce) committed at another leaf that
+    /// still stands. Otherwise the challenge disputes the receipt's
+    /// signature: the view counts as rejected unless the publisher answers
+    /// with `answer_challenge`, and the window stays open for at least
+    /// `ANSWER_WINDOW_SECS` to let it.
+    pub fn challenge_view(env: Env, fraud_contract: Address, challenged: ViewProof, duplicate_of: Option<ViewProof>) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        fraud_contract.require_auth();
+        let stored_fraud: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::FraudContract)
+            .expect("fraud contract not set");
+        if fraud_contract != stored_fraud {
+            panic!("unauthorized fraud contract");
+        }
+
+        let batch_id = challenged.batch_id;
+        let mut batch: ViewBatch = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ViewBatch(batch_id))
+            .expect("batch not found");
+        if batch.status != BatchStatus::Pending {
+            panic!("batch not pending");
+        }
+        let now = env.ledger().timestamp();
+        if now >= batch.challenge_deadline {
+            panic!("challenge window closed");
+        }
+        let leaf = Self::_receipt_leaf(&env, &challenged.receipt);
+        if !Self::_verify_merkle_proof(&env, &batch.merkle_root, &leaf, &challenged.proof) {
+            panic!("invalid merkle proof");
+        }
+
+        let leaf_key = DataKey::ChallengedLeaf(batch_id, leaf.clone());
+        let previous: Option<LeafStatus> = env.storage().persistent().get(&leaf_key);
+        if matches!(previous, Some(LeafStatus::Rejected) | Some(LeafStatus::Unanswered)) {
+            panic!("leaf already challenged");
+        }
+        if batch.rejected_views >= batch.view_count {
+            panic!("all views rejected");
+        }
+
+        let receipt = &challenged.receipt;
+        let status = if receipt.campaign_id != batch.campaign_id || receipt.publisher != batch.publisher {
+            LeafStatus::Rejected
+        } else if let Some(original) = duplicate_of {
+            if !Self::_is_duplicate(&env, &challenged, &original) {
+                panic!("not a duplicate");
+            }
+            LeafStatus::Rejected
+        } else {
+            if previous == Some(LeafStatus::Verified) {
+                panic!("signature already verified");
+            }
+            batch.challenge_deadline = batch.challenge_deadline.max(now + ANSWER_WINDOW_SECS);
+            LeafStatus::Unanswered
+        };
+        env.storage().persistent().set(&leaf_key, &status);
+        env.storage()
+            .persistent()
+            .extend_ttl(&leaf_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        batch.rejected_views += 1;
+        let _ttl_key = DataKey::ViewBatch(batch_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &batch);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("batch"), symbol_short!("challenge")),
+            (batch_id, leaf),
+        );
+    }
+
+    /// Publisher: clear a signature challenge by presenting the challenged
+    /// receipt; fails unless its signature verifies. The view is paid again.
+    pub fn answer_challenge(env: Env, publisher: Address, batch_id: u64, receipt: ViewReceipt) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        publisher.require_auth();
+
+        let mut batch: ViewBatch = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ViewBatch(batch_id))
+            .expect("batch not found");
+        if batch.publisher != publisher {
+            panic!("unauthorized");
+        }
+        if batch.status != BatchStatus::Pending {
+            panic!("batch not pending");
+        }
+        if env.ledger().timestamp() >= batch.challenge_deadline {
+            panic!("challenge window closed");
+        }
+
+        let leaf = Self::get_receipt_leaf(env.clone(), receipt);
+        let leaf_key = DataKey::ChallengedLeaf(batch_id, leaf);
+        if env.storage().persistent().get::<_, LeafStatus>(&leaf_key) != Some(LeafStatus::Unanswered) {
+            panic!("no open challenge");
+        }
+        env.storage().persistent().set(&leaf_key, &LeafStatus::Verified);
+        env.storage()
+            .persistent()
+            .extend_ttl(&leaf_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        batch.rejected_views -= 1;
+        let _ttl_key = DataKey::ViewBatch(batch_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &batch);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    /// Pay out a batch once its challenge window has closed. Rejected views
+    /// go back to the campaign budget, or to the advertiser once the
+    /// campaign has ended. Callable by anyone.
+    pub fn finalize_view_batch(env: Env, batch_id: u64) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let mut batch: ViewBatch = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ViewBatch(batch_id))
+            .expect("batch not found");
+        if batch.status != BatchStatus::Pending {
+            panic!("batch not pending");
+        }
+        if env.ledger().timestamp() < batch.challenge_deadline {
+            panic!("challenge window open");
+        }
+
+        let accepted = batch.view_count - batch.rejected_views;
+        let paid = batch.cost_per_view * accepted as i128;
+        let returned = batch.payout - paid;
+
+        let mut campaign: Campaign = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Campaign(batch.campaign_id))
+            .expect("campaign not found");
+        let running = matches!(campaign.status, CampaignStatus::Active | CampaignStatus::Paused);
+        if running {
+            campaign.remaining_budget += returned;
+        }
+        campaign.current_views += accepted;
+        campaign.last_updated = env.ledger().timestamp();
+        if running && campaign.current_views >= campaign.target_views {
+            campaign.status = CampaignStatus::Completed;
+        }
+        let _ttl_key = DataKey::Campaign(batch.campaign_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &campaign);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        if paid > 0 {
+            Self::_pay_publisher(&env, batch.campaign_id, &batch.publisher, paid);
+            Self::_update_publisher_earnings(&env, &batch.publisher, paid);
+        }
+        if batch.rejected_views > 0 {
+            Self::_release_impressions(&env, &campaign.advertiser, batch.rejected_views, batch.submitted_at);
+        }
+        if !running && returned > 0 {
+            let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+            token::Client::new(&env, &token_addr).transfer(&env.current_contract_address(), &campaign.advertiser, &returned);
+        }
+
+        batch.status = BatchStatus::Settled;
+        let _ttl_key = DataKey::ViewBatch(batch_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &batch);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("batch"), symbol_short!("settled")),
+            (batch_id, paid),
+        );
+
+        paid
+    }
+
     /// Pause a campaign (advertiser only)
     pub fn pause_campaign(env: Env, advertiser: Address, campaign_id: u64) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
@@ -434,6 +826,52 @@ impl CampaignOrchestratorContract {
         );
     }
 
+    /// Lending pool: seize the unspent budget of a defaulted borrower's
+    /// campaign and cancel it. Returns the amount seized.
+    pub fn claim_for_default(env: Env, pool: Address, campaign_id: u64, borrower: Address) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        pool.require_auth();
+        let stored: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::LendingPool)
+            .expect("lending pool not set");
+        if pool != stored {
+            panic!("unauthorized lending pool");
+        }
+
+        let mut campaign: Campaign = match env.storage().persistent().get(&DataKey::Campaign(campaign_id)) {
+            Some(campaign) => campaign,
+            None => return 0,
+        };
+        if campaign.advertiser != borrower {
+            return 0;
+        }
+
+        let seized = campaign.remaining_budget;
+        campaign.remaining_budget = 0;
+        campaign.status = CampaignStatus::Cancelled;
+        campaign.last_updated = env.ledger().timestamp();
+        let _ttl_key = DataKey::Campaign(campaign_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &campaign);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        if seized > 0 {
+            let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+            token::Client::new(&env, &token_addr).transfer(&env.current_contract_address(), &pool, &seized);
+        }
+
+        env.events().publish(
+            (symbol_short!("campaign"), symbol_short!("seized")),
+            (campaign_id, seized),
+        );
+        seized
+    }
+
     /// Admin: verify a publisher
     pub fn verify_publisher(env: Env, admin: Address, publisher: Address, initial_score: u32) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
@@ -465,6 +903,154 @@ impl CampaignOrchestratorContract {
         );
     }
 
+    /// Admin: set the fraud-prevention contract allowed to challenge batches
+    pub fn set_fraud_contract(env: Env, admin: Address, fraud_contract: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::FraudContract, &fraud_contract);
+    }
+
+    /// Admin: route publisher payouts through revenue-settlement. Payouts are
+    /// transferred to it and credited through its `record_revenue` instead
+    /// of being paid to the publisher directly.
+    pub fn set_revenue_settlement(env: Env, admin: Address, settlement: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::RevenueSettlement, &settlement);
+    }
+
+    /// Admin: enforce plan quotas through subscription-manager. New
+    /// campaigns, recorded views and the publishers they run on are metered
+    /// against the advertiser's plan and rejected once its allowance for the
+    /// period is used up; impressions of batched views that are later
+    /// rejected are released when the batch is finalized.
+    pub fn set_subscription_manager(env: Env, admin: Address, manager: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::SubscriptionManager, &manager);
+    }
+
+    /// Admin: route `fee_bps` of the platform fee charged on every publisher
+    /// payout into the performance oracle's reward pool for the campaign's
+    /// current epoch
+    pub fn set_performance_oracle(env: Env, admin: Address, oracle: Address, fee_bps: u32) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        if fee_bps > 10_000 {
+            panic!("invalid fee");
+        }
+        env.storage().instance().set(&DataKey::PerformanceOracle, &oracle);
+        env.storage().instance().set(&DataKey::OracleFeeBps, &fee_bps);
+    }
+
+    /// Admin: let the auction engine pay won batch-auction slots out of
+    /// campaign budgets
+    pub fn set_auction_engine(env: Env, admin: Address, engine: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::AuctionEngine, &engine);
+    }
+
+    /// Auction engine: pay `publisher` for a batch-auction slot the
+    /// advertiser won, out of the campaign's budget.
+    pub fn charge_auction_win(
+        env: Env,
+        auction_engine: Address,
+        campaign_id: u64,
+        advertiser: Address,
+        publisher: Address,
+        amount: i128,
+    ) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        auction_engine.require_auth();
+        let stored: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::AuctionEngine)
+            .expect("auction engine not set");
+        if auction_engine != stored {
+            panic!("unauthorized auction engine");
+        }
+        if amount <= 0 {
+            panic!("invalid amount");
+        }
+        if Self::get_spendable_budget(env.clone(), campaign_id, advertiser.clone()) < amount {
+            panic!("insufficient budget");
+        }
+
+        let mut campaign: Campaign = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Campaign(campaign_id))
+            .unwrap();
+        campaign.remaining_budget -= amount;
+        campaign.last_updated = env.ledger().timestamp();
+        let _ttl_key = DataKey::Campaign(campaign_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &campaign);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        token::Client::new(&env, &token_addr).transfer(&env.current_contract_address(), &publisher, &amount);
+    }
+
+    /// Admin: let the liquidity pool claim defaulted budgets
+    pub fn set_lending_pool(env: Env, admin: Address, pool: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::LendingPool, &pool);
+    }
+
+    /// Admin: set how long batches stay open to challenges
+    pub fn set_challenge_window(env: Env, admin: Address, window_secs: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::ChallengeWindow, &window_secs);
+    }
+
     /// Admin: set platform fee
     pub fn set_platform_fee(env: Env, admin: Address, fee_pct: u32) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
@@ -492,6 +1078,60 @@ impl CampaignOrchestratorContract {
             .get(&DataKey::Campaign(campaign_id))
     }
 
+    /// Budget an active, unexpired campaign can still commit, or 0 if
+    /// `advertiser` does not own it. Used by auction-engine for pacing.
+    pub fn get_spendable_budget(env: Env, campaign_id: u64, advertiser: Address) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let campaign: Campaign = match env
+            .storage()
+            .persistent()
+            .get(&DataKey::Campaign(campaign_id))
+        {
+            Some(c) => c,
+            None => return 0,
+        };
+
+        if campaign.advertiser != advertiser || env.ledger().sequence() > campaign.end_ledger {
+            return 0;
+        }
+        match campaign.status {
+            CampaignStatus::Active => campaign.remaining_budget,
+            _ => 0,
+        }
+    }
+
+    pub fn get_view_batch(env: Env, batch_id: u64) -> Option<ViewBatch> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::ViewBatch(batch_id))
+    }
+
+    /// Leaf of a receipt after checking the viewer's signature over its
+    /// digest; this is what a batch's Merkle tree commits to.
+    pub fn get_receipt_leaf(env: Env, receipt: ViewReceipt) -> BytesN<32> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let digest = Self::_receipt_digest(&env, &receipt);
+        env.crypto().ed25519_verify(
+            &receipt.viewer_key,
+            &Bytes::from_array(&env, &digest.to_array()),
+            &receipt.signature,
+        );
+        Self::_receipt_leaf(&env, &receipt)
+    }
+
+    /// Digest of a receipt after checking the viewer's signature over it
+    pub fn get_receipt_digest(env: Env, receipt: ViewReceipt) -> BytesN<32> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let digest = Self::_receipt_digest(&env, &receipt);
+        env.crypto().ed25519_verify(
+            &receipt.viewer_key,
+            &Bytes::from_array(&env, &digest.to_array()),
+            &receipt.signature,
+        );
+        digest
+    }
+
     pub fn get_campaign_metrics(env: Env, campaign_id: u64) -> Option<CampaignMetrics> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         let campaign: Campaign = env
@@ -569,6 +1209,197 @@ impl CampaignOrchestratorContract {
         env.storage().persistent().extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
     }
 
+    /// sha256(campaign_id || publisher || viewer_key || nonce || timestamp);
+    /// the message the viewer signs.
+    fn _receipt_digest(env: &Env, receipt: &ViewReceipt) -> BytesN<32> {
+        let mut data = Bytes::from_array(env, &receipt.campaign_id.to_be_bytes());
+        data.append(&receipt.publisher.clone().to_xdr(env));
+        data.extend_from_array(&receipt.viewer_key.to_array());
+        data.extend_from_array(&receipt.nonce.to_be_bytes());
+        data.extend_from_array(&receipt.timestamp.to_be_bytes());
+        env.crypto().sha256(&data).into()
+    }
+
+    /// sha256(digest || signature); the Merkle leaf of a receipt
+    fn _receipt_leaf(env: &Env, receipt: &ViewReceipt) -> BytesN<32> {
+        let mut data = Bytes::from_array(env, &Self::_receipt_digest(env, receipt).to_array());
+        data.extend_from_array(&receipt.signature.to_array());
+        env.crypto().sha256(&data).into()
+    }
+
+    /// Whether `original` is the same view as `challenged` committed at
+    /// another position, in this or an earlier batch, and not itself rejected
+    fn _is_duplicate(env: &Env, challenged: &ViewProof, original: &ViewProof) -> bool {
+        let (a, b) = (&challenged.receipt, &original.receipt);
+        if a.campaign_id != b.campaign_id || a.viewer_key != b.viewer_key || a.nonce != b.nonce {
+            return false;
+        }
+        if original.batch_id > challenged.batch_id {
+            return false;
+        }
+        let batch: ViewBatch = match env.storage().persistent().get(&DataKey::ViewBatch(original.batch_id)) {
+            Some(batch) => batch,
+            None => return false,
+        };
+        let leaf = Self::_receipt_leaf(env, b);
+        if !Self::_verify_merkle_proof(env, &batch.merkle_root, &leaf, &original.proof) {
+            return false;
+        }
+        if original.batch_id == challenged.batch_id
+            && leaf == Self::_receipt_leaf(env, a)
+            && original.proof == challenged.proof
+        {
+            return false;
+        }
+        let status: Option<LeafStatus> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::ChallengedLeaf(original.batch_id, leaf));
+        !matches!(status, Some(LeafStatus::Rejected) | Some(LeafStatus::Unanswered))
+    }
+
+    /// Verify a Merkle path where each parent is sha256 of its two children
+    /// in ascending byte order.
+    fn _verify_merkle_proof(
+        env: &Env,
+        root: &BytesN<32>,
+        leaf: &BytesN<32>,
+        proof: &Vec<BytesN<32>>,
+    ) -> bool {
+        let mut node = leaf.clone();
+        for sibling in proof.iter() {
+            let (a, b) = if node.to_array() <= sibling.to_array() {
+                (node, sibling)
+            } else {
+                (sibling, node)
+            };
+            let mut data = Bytes::from_array(env, &a.to_array());
+            data.extend_from_array(&b.to_array());
+            node = env.crypto().sha256(&data).into();
+        }
+        node == *root
+    }
+
+    fn _pay_publisher(env: &Env, campaign_id: u64, publisher: &Address, amount: i128) {
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        let token_client = token::Client::new(env, &token_addr);
+
+        // Part of the campaign's platform fee on this payout funds attesters
+        // of the campaign's current epoch; the publisher is paid in full
+        if let Some(oracle) = env.storage().instance().get::<_, Address>(&DataKey::PerformanceOracle) {
+            let fee_bps: u32 = env.storage().instance().get(&DataKey::OracleFeeBps).unwrap_or(0);
+            let campaign: Campaign = env.storage().persistent().get(&DataKey::Campaign(campaign_id)).unwrap();
+            let reward = if campaign.budget > 0 {
+                campaign.platform_fee * amount * fee_bps as i128 / (campaign.budget * 10_000)
+            } else {
+                0
+            };
+            if reward > 0 {
+                let epoch: u64 = env.invoke_contract(&oracle, &Symbol::new(env, "get_current_epoch"), vec![env]);
+                Self::_authorize_pull(env, &token_addr, &oracle, reward);
+                let _: () = env.invoke_contract(
+                    &oracle,
+                    &Symbol::new(env, "fund_rewards"),
+                    vec![
+                        env,
+                        env.current_contract_address().into_val(env),
+                        campaign_id.into_val(env),
+                        epoch.into_val(env),
+                        reward.into_val(env),
+                    ],
+                );
+            }
+        }
+
+        match env.storage().instance().get::<_, Address>(&DataKey::RevenueSettlement) {
+            Some(settlement) => {
+                // record_revenue pulls the amount from this contract
+                Self::_authorize_pull(env, &token_addr, &settlement, amount);
+                let _: u64 = env.invoke_contract(
+                    &settlement,
+                    &Symbol::new(env, "record_revenue"),
+                    vec![
+                        env,
+                        env.current_contract_address().into_val(env),
+                        campaign_id.into_val(env),
+                        amount.into_val(env),
+                        publisher.into_val(env),
+                    ],
+                );
+            }
+            None => token_client.transfer(&env.current_contract_address(), publisher, &amount),
+        }
+    }
+
+    /// Authorize the next contract call to move `amount` of `token_addr` from
+    /// this contract to `to`
+    fn _authorize_pull(env: &Env, token_addr: &Address, to: &Address, amount: i128) {
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token_addr.clone(),
+                    fn_name: symbol_short!("transfer"),
+                    args: vec![
+                        env,
+                        env.current_contract_address().into_val(env),
+                        to.into_val(env),
+                        amount.into_val(env),
+                    ],
+                },
+                sub_invocations: Vec::new(env),
+            }),
+        ]);
+    }
+
+    fn _consume_impressions(env: &Env, advertiser: &Address, count: u64) {
+        if let Some(manager) = env.storage().instance().get::<_, Address>(&DataKey::SubscriptionManager) {
+            env.invoke_contract::<()>(
+                &manager,
+                &Symbol::new(env, "consume_impressions"),
+                vec![
+                    env,
+                    env.current_contract_address().into_val(env),
+                    advertiser.into_val(env),
+                    count.into_val(env),
+                ],
+            );
+        }
+    }
+
+    fn _consume_publisher(env: &Env, advertiser: &Address, publisher: &Address) {
+        if let Some(manager) = env.storage().instance().get::<_, Address>(&DataKey::SubscriptionManager) {
+            env.invoke_contract::<()>(
+                &manager,
+                &Symbol::new(env, "consume_publisher"),
+                vec![
+                    env,
+                    env.current_contract_address().into_val(env),
+                    advertiser.into_val(env),
+                    publisher.into_val(env),
+                ],
+            );
+        }
+    }
+
+    /// Hand back impressions metered when a batch was committed for views
+    /// that were rejected
+    fn _release_impressions(env: &Env, advertiser: &Address, count: u64, metered_at: u64) {
+        if let Some(manager) = env.storage().instance().get::<_, Address>(&DataKey::SubscriptionManager) {
+            env.invoke_contract::<()>(
+                &manager,
+                &Symbol::new(env, "release_impressions"),
+                vec![
+                    env,
+                    env.current_contract_address().into_val(env),
+                    advertiser.into_val(env),
+                    count.into_val(env),
+                    metered_at.into_val(env),
+                ],
+            );
+        }
+    }
+
     fn _update_publisher_earnings(env: &Env, publisher: &Address, earning: i128) {
         let key = DataKey::Publisher(publisher.clone());
         if let Some(mut pub_data) = env
@@ -1,6 +1,12 @@
 #![cfg(test)]
 use super::*;
-use soroban_sdk::{testutils::Address as _, token::StellarAssetClient, Address, Env, String};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    contract as soroban_contract, contractimpl as soroban_contractimpl,
+    testutils::{Address as _, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    vec, Address, Bytes, BytesN, Env, String, Vec,
+};
 
 fn deploy_token(env: &Env, admin: &Address) -> Address {
     env.register_stellar_asset_contract_v2(admin.clone()).address()
@@ -9,6 +15,99 @@ fn mint(env: &Env, token: &Address, to: &Address, amount: i128) {
     StellarAssetClient::new(env, token).mint(to, &amount);
 }
 
+#[soroban_contract]
+pub struct MockSettlement;
+
+#[soroban_contractimpl]
+impl MockSettlement {
+    pub fn init(env: Env, token: Address) {
+        env.storage().instance().set(&symbol_short!("token"), &token);
+    }
+
+    /// Pulls the revenue from the source, like the real settlement contract
+    pub fn record_revenue(env: Env, source: Address, campaign_id: u64, amount: i128, publisher: Address) -> u64 {
+        source.require_auth();
+        let token: Address = env.storage().instance().get(&symbol_short!("token")).unwrap();
+        TokenClient::new(&env, &token).transfer(&source, &env.current_contract_address(), &amount);
+        env.storage().instance().set(&symbol_short!("last"), &(campaign_id, amount, publisher));
+        1
+    }
+
+    pub fn last(env: Env) -> Option<(u64, i128, Address)> {
+        env.storage().instance().get(&symbol_short!("last"))
+    }
+}
+
+/// Performance-oracle stand-in in epoch 5 that pulls funded rewards
+#[soroban_contract]
+pub struct MockOracle;
+
+#[soroban_contractimpl]
+impl MockOracle {
+    pub fn set_token(env: Env, token: Address) {
+        env.storage().instance().set(&symbol_short!("token"), &token);
+    }
+
+    pub fn get_current_epoch(_env: Env) -> u64 {
+        5
+    }
+
+    pub fn fund_rewards(env: Env, funder: Address, campaign_id: u64, epoch: u64, amount: i128) {
+        funder.require_auth();
+        let token: Address = env.storage().instance().get(&symbol_short!("token")).unwrap();
+        TokenClient::new(&env, &token).transfer(&funder, &env.current_contract_address(), &amount);
+        env.storage().instance().set(&symbol_short!("funded"), &(campaign_id, epoch, amount));
+    }
+
+    pub fn funded(env: Env) -> Option<(u64, u64, i128)> {
+        env.storage().instance().get(&symbol_short!("funded"))
+    }
+}
+
+/// Subscription-manager stand-in allowing two campaigns, five impressions
+/// and one publisher
+#[soroban_contract]
+pub struct MockQuota;
+
+#[soroban_contractimpl]
+impl MockQuota {
+    pub fn consume_campaign(env: Env, reporter: Address, _subscriber: Address) {
+        reporter.require_auth();
+        let used: u32 = env.storage().instance().get(&symbol_short!("camps")).unwrap_or(0);
+        if used >= 2 {
+            panic!("campaign quota exceeded");
+        }
+        env.storage().instance().set(&symbol_short!("camps"), &(used + 1));
+    }
+
+    pub fn consume_impressions(env: Env, reporter: Address, _subscriber: Address, count: u64) {
+        reporter.require_auth();
+        let used: u64 = env.storage().instance().get(&symbol_short!("imps")).unwrap_or(0);
+        if used + count > 5 {
+            panic!("impression quota exceeded");
+        }
+        env.storage().instance().set(&symbol_short!("imps"), &(used + count));
+    }
+
+    pub fn release_impressions(env: Env, reporter: Address, _subscriber: Address, count: u64, _metered_at: u64) {
+        reporter.require_auth();
+        let used: u64 = env.storage().instance().get(&symbol_short!("imps")).unwrap_or(0);
+        env.storage().instance().set(&symbol_short!("imps"), &(used - count));
+    }
+
+    pub fn consume_publisher(env: Env, reporter: Address, _subscriber: Address, publisher: Address) {
+        reporter.require_auth();
+        match env.storage().instance().get::<_, Address>(&symbol_short!("pub")) {
+            Some(first) if first != publisher => panic!("publisher quota exceeded"),
+            _ => env.storage().instance().set(&symbol_short!("pub"), &publisher),
+        }
+    }
+
+    pub fn impressions(env: Env) -> u64 {
+        env.storage().instance().get(&symbol_short!("imps")).unwrap_or(0)
+    }
+}
+
 fn setup(env: &Env) -> (CampaignOrchestratorContractClient, Address, Address, Address) {
     let admin = Address::generate(env);
     let token_admin = Address::generate(env);
@@ -91,3 +190,549 @@ fn test_get_campaign_nonexistent() {
     let (c, _, _, _) = setup(&env);
     assert!(c.get_campaign(&999u64).is_none());
 }
+
+#[test]
+fn test_get_spendable_budget() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, _, token) = setup(&env);
+    let advertiser = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    mint(&env, &token, &advertiser, 5_000_000);
+    let id = c.create_campaign(&advertiser, &1u32, &1_000_000i128, &100i128, &1000u32, &10_000u64, &5_000u64, &true);
+    assert_eq!(c.get_spendable_budget(&id, &advertiser), 1_000_000);
+    assert_eq!(c.get_spendable_budget(&id, &stranger), 0);
+    assert_eq!(c.get_spendable_budget(&99u64, &advertiser), 0);
+    c.pause_campaign(&advertiser, &id);
+    assert_eq!(c.get_spendable_budget(&id, &advertiser), 0);
+}
+
+#[test]
+fn test_charge_auction_win_pays_publisher_from_budget() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, _, token) = setup(&env);
+    let engine = Address::generate(&env);
+    c.set_auction_engine(&admin, &engine);
+    let advertiser = Address::generate(&env);
+    let publisher_a = Address::generate(&env);
+    let publisher_b = Address::generate(&env);
+    mint(&env, &token, &advertiser, 5_000_000);
+    let id = c.create_campaign(&advertiser, &1u32, &1_000_000i128, &100i128, &1000u32, &10_000u64, &5_000u64, &true);
+    let tc = TokenClient::new(&env, &token);
+    let advertiser_before = tc.balance(&advertiser);
+    let held_before = tc.balance(&c.address);
+
+    // two slots of one batch settlement
+    c.charge_auction_win(&engine, &id, &advertiser, &publisher_a, &250_000i128);
+    c.charge_auction_win(&engine, &id, &advertiser, &publisher_b, &100_000i128);
+
+    assert_eq!(c.get_spendable_budget(&id, &advertiser), 650_000);
+    assert_eq!(tc.balance(&advertiser), advertiser_before);
+    assert_eq!(tc.balance(&publisher_a), 250_000);
+    assert_eq!(tc.balance(&publisher_b), 100_000);
+    assert_eq!(tc.balance(&c.address), held_before - 350_000);
+    assert_eq!(
+        tc.balance(&advertiser) + tc.balance(&publisher_a) + tc.balance(&publisher_b) + tc.balance(&c.address),
+        advertiser_before + held_before
+    );
+}
+
+#[test]
+#[should_panic(expected = "insufficient budget")]
+fn test_charge_auction_win_over_budget() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, _, token) = setup(&env);
+    let engine = Address::generate(&env);
+    c.set_auction_engine(&admin, &engine);
+    let advertiser = Address::generate(&env);
+    mint(&env, &token, &advertiser, 5_000_000);
+    let id = c.create_campaign(&advertiser, &1u32, &1_000_000i128, &100i128, &1000u32, &10_000u64, &5_000u64, &true);
+    c.charge_auction_win(&engine, &id, &advertiser, &Address::generate(&env), &1_000_001i128);
+}
+
+#[test]
+#[should_panic(expected = "unauthorized auction engine")]
+fn test_charge_auction_win_by_stranger() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, _, _) = setup(&env);
+    c.set_auction_engine(&admin, &Address::generate(&env));
+    c.charge_auction_win(&Address::generate(&env), &1u64, &Address::generate(&env), &Address::generate(&env), &1i128);
+}
+
+#[test]
+fn test_record_view_routes_payout_through_settlement() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, token, id) = setup_batch(&env);
+    let settlement = env.register_contract(None, MockSettlement);
+    MockSettlementClient::new(&env, &settlement).init(&token);
+    c.set_revenue_settlement(&admin, &settlement);
+
+    c.record_view(&id, &publisher);
+
+    let tc = TokenClient::new(&env, &token);
+    assert_eq!(tc.balance(&publisher), 0);
+    assert_eq!(tc.balance(&settlement), 100);
+    assert_eq!(MockSettlementClient::new(&env, &settlement).last(), Some((id, 100, publisher)));
+}
+
+#[test]
+fn test_finalized_batch_routes_payout_through_settlement() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, token, id) = setup_batch(&env);
+    let settlement = env.register_contract(None, MockSettlement);
+    MockSettlementClient::new(&env, &settlement).init(&token);
+    c.set_revenue_settlement(&admin, &settlement);
+    let (_, root, _) = receipt_tree(&env, id, &publisher);
+
+    let batch_id = c.settle_view_batch(&publisher, &id, &root, &4u64, &400i128);
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    c.finalize_view_batch(&batch_id);
+
+    assert_eq!(TokenClient::new(&env, &token).balance(&settlement), 400);
+    assert_eq!(MockSettlementClient::new(&env, &settlement).last(), Some((id, 400, publisher)));
+}
+
+#[test]
+fn test_payout_funds_oracle_reward_pool() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, token, id) = setup_batch(&env);
+    let oracle = env.register_contract(None, MockOracle);
+    MockOracleClient::new(&env, &oracle).set_token(&token);
+    c.set_performance_oracle(&admin, &oracle, &10_000u32);
+    let held_before = TokenClient::new(&env, &token).balance(&c.address);
+
+    c.record_view(&id, &publisher);
+
+    // the 2% platform fee on a 100 payout is 2; the publisher is paid in full
+    let tc = TokenClient::new(&env, &token);
+    assert_eq!(tc.balance(&publisher), 100);
+    assert_eq!(tc.balance(&oracle), 2);
+    assert_eq!(tc.balance(&c.address), held_before - 102);
+    assert_eq!(MockOracleClient::new(&env, &oracle).funded(), Some((id, 5, 2)));
+}
+
+#[test]
+fn test_oracle_reward_does_not_reduce_settled_payout() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, token, id) = setup_batch(&env);
+    let oracle = env.register_contract(None, MockOracle);
+    MockOracleClient::new(&env, &oracle).set_token(&token);
+    c.set_performance_oracle(&admin, &oracle, &5_000u32);
+    let settlement = env.register_contract(None, MockSettlement);
+    MockSettlementClient::new(&env, &settlement).init(&token);
+    c.set_revenue_settlement(&admin, &settlement);
+
+    c.record_view(&id, &publisher);
+
+    assert_eq!(TokenClient::new(&env, &token).balance(&oracle), 1);
+    assert_eq!(MockSettlementClient::new(&env, &settlement).last(), Some((id, 100, publisher)));
+}
+
+// ─── subscription quotas ─────────────────────────────────────────────────────
+
+#[test]
+#[should_panic(expected = "campaign quota exceeded")]
+fn test_create_campaign_over_quota() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, _, token) = setup(&env);
+    let quota = env.register_contract(None, MockQuota);
+    c.set_subscription_manager(&admin, &quota);
+    let advertiser = Address::generate(&env);
+    mint(&env, &token, &advertiser, 5_000_000);
+    for _ in 0..3 {
+        c.create_campaign(&advertiser, &1u32, &1_000_000i128, &100i128, &1000u32, &10_000u64, &5_000u64, &true);
+    }
+}
+
+#[test]
+#[should_panic(expected = "impression quota exceeded")]
+fn test_record_view_over_quota() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, _, id) = setup_batch(&env);
+    let quota = env.register_contract(None, MockQuota);
+    c.set_subscription_manager(&admin, &quota);
+    for _ in 0..6 {
+        c.record_view(&id, &publisher);
+    }
+}
+
+#[test]
+fn test_view_batch_meters_impressions() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, _, id) = setup_batch(&env);
+    let quota = env.register_contract(None, MockQuota);
+    c.set_subscription_manager(&admin, &quota);
+    let (_, root, _) = receipt_tree(&env, id, &publisher);
+
+    c.settle_view_batch(&publisher, &id, &root, &4u64, &400i128);
+    assert_eq!(MockQuotaClient::new(&env, &quota).impressions(), 4);
+}
+
+#[test]
+fn test_rejected_batch_views_release_impressions() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, _, id) = setup_batch(&env);
+    let quota = env.register_contract(None, MockQuota);
+    c.set_subscription_manager(&admin, &quota);
+    let fraud = Address::generate(&env);
+    c.set_fraud_contract(&admin, &fraud);
+    let (receipts, root, proof0) = receipt_tree(&env, id, &publisher);
+
+    let batch_id = c.settle_view_batch(&publisher, &id, &root, &4u64, &400i128);
+    c.challenge_view(&fraud, &view_proof(batch_id, receipts.get(0).unwrap(), &proof0), &None);
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    c.finalize_view_batch(&batch_id);
+    assert_eq!(MockQuotaClient::new(&env, &quota).impressions(), 3);
+}
+
+#[test]
+#[should_panic(expected = "publisher quota exceeded")]
+fn test_record_view_meters_publishers() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, _, id) = setup_batch(&env);
+    let quota = env.register_contract(None, MockQuota);
+    c.set_subscription_manager(&admin, &quota);
+    let second = Address::generate(&env);
+    c.verify_publisher(&admin, &second, &80u32);
+
+    c.record_view(&id, &publisher);
+    c.record_view(&id, &publisher);
+    c.record_view(&id, &second);
+}
+
+// ─── batch settlement ────────────────────────────────────────────────────────
+
+/// Campaign with cost_per_view=100 and a verified publisher.
+fn setup_batch(env: &Env) -> (CampaignOrchestratorContractClient<'_>, Address, Address, Address, u64) {
+    let (c, admin, _, token) = setup(env);
+    let advertiser = Address::generate(env);
+    let publisher = Address::generate(env);
+    mint(env, &token, &advertiser, 5_000_000);
+    let id = c.create_campaign(&advertiser, &1u32, &1_000_000i128, &100i128, &1000u32, &10_000u64, &5_000u64, &true);
+    c.verify_publisher(&admin, &publisher, &80u32);
+    (c, admin, publisher, token, id)
+}
+
+fn signed_receipt(env: &Env, campaign_id: u64, publisher: &Address, nonce: u64) -> (ViewReceipt, SigningKey) {
+    let key = SigningKey::from_bytes(&[nonce as u8 + 1; 32]);
+    let mut receipt = ViewReceipt {
+        campaign_id,
+        publisher: publisher.clone(),
+        viewer_key: BytesN::from_array(env, &key.verifying_key().to_bytes()),
+        nonce,
+        timestamp: env.ledger().timestamp(),
+        signature: BytesN::from_array(env, &[0u8; 64]),
+    };
+    let digest = CampaignOrchestratorContract::_receipt_digest(env, &receipt);
+    receipt.signature = BytesN::from_array(env, &key.sign(&digest.to_array()).to_bytes());
+    (receipt, key)
+}
+
+fn hash_pair(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (lo, hi) = if a.to_array() <= b.to_array() { (a, b) } else { (b, a) };
+    let mut data = Bytes::from_array(env, &lo.to_array());
+    data.extend_from_array(&hi.to_array());
+    env.crypto().sha256(&data).into()
+}
+
+/// Four signed receipts, their Merkle root and the proof for receipt 0.
+fn receipt_tree(env: &Env, campaign_id: u64, publisher: &Address) -> (Vec<ViewReceipt>, BytesN<32>, Vec<BytesN<32>>) {
+    let mut receipts = Vec::new(env);
+    for nonce in 0..4u64 {
+        receipts.push_back(signed_receipt(env, campaign_id, publisher, nonce).0);
+    }
+    let (root, proof0) = tree_of(env, &receipts);
+    (receipts, root, proof0)
+}
+
+/// Merkle root of four receipts and the proof for receipt 0. Leaves are
+/// built without checking signatures so tests can commit bad receipts.
+fn tree_of(env: &Env, receipts: &Vec<ViewReceipt>) -> (BytesN<32>, Vec<BytesN<32>>) {
+    let leaf = |i: u32| CampaignOrchestratorContract::_receipt_leaf(env, &receipts.get(i).unwrap());
+    let l01 = hash_pair(env, &leaf(0), &leaf(1));
+    let l23 = hash_pair(env, &leaf(2), &leaf(3));
+    (hash_pair(env, &l01, &l23), vec![env, leaf(1), l23])
+}
+
+fn view_proof(batch_id: u64, receipt: ViewReceipt, proof: &Vec<BytesN<32>>) -> ViewProof {
+    ViewProof { batch_id, receipt, proof: proof.clone() }
+}
+
+#[test]
+fn test_settle_view_batch_pays_after_window() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, publisher, token, id) = setup_batch(&env);
+    let (_, root, _) = receipt_tree(&env, id, &publisher);
+
+    let batch_id = c.settle_view_batch(&publisher, &id, &root, &4u64, &400i128);
+    assert_eq!(c.get_campaign(&id).unwrap().remaining_budget, 1_000_000 - 400);
+    assert_eq!(TokenClient::new(&env, &token).balance(&publisher), 0);
+
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    assert_eq!(c.finalize_view_batch(&batch_id), 400);
+
+    assert_eq!(TokenClient::new(&env, &token).balance(&publisher), 400);
+    let batch = c.get_view_batch(&batch_id).unwrap();
+    assert_eq!(batch.status, BatchStatus::Settled);
+    let campaign = c.get_campaign(&id).unwrap();
+    assert_eq!(campaign.current_views, 4);
+    assert_eq!(c.get_publisher_metrics(&publisher).unwrap().total_earnings, 400);
+}
+
+#[test]
+fn test_challenged_leaf_is_not_paid() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, token, id) = setup_batch(&env);
+    let fraud = Address::generate(&env);
+    c.set_fraud_contract(&admin, &fraud);
+    let (receipts, root, proof0) = receipt_tree(&env, id, &publisher);
+
+    let batch_id = c.settle_view_batch(&publisher, &id, &root, &4u64, &400i128);
+    c.challenge_view(&fraud, &view_proof(batch_id, receipts.get(0).unwrap(), &proof0), &None);
+
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    assert_eq!(c.finalize_view_batch(&batch_id), 300);
+
+    assert_eq!(TokenClient::new(&env, &token).balance(&publisher), 300);
+    let campaign = c.get_campaign(&id).unwrap();
+    assert_eq!(campaign.remaining_budget, 1_000_000 - 300);
+    assert_eq!(campaign.current_views, 3);
+}
+
+#[test]
+#[should_panic(expected = "leaf already challenged")]
+fn test_leaf_cannot_be_challenged_twice() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, _, id) = setup_batch(&env);
+    let fraud = Address::generate(&env);
+    c.set_fraud_contract(&admin, &fraud);
+    let (receipts, root, proof0) = receipt_tree(&env, id, &publisher);
+
+    let batch_id = c.settle_view_batch(&publisher, &id, &root, &4u64, &400i128);
+    c.challenge_view(&fraud, &view_proof(batch_id, receipts.get(0).unwrap(), &proof0), &None);
+    c.challenge_view(&fraud, &view_proof(batch_id, receipts.get(0).unwrap(), &proof0), &None);
+}
+
+#[test]
+#[should_panic(expected = "invalid merkle proof")]
+fn test_challenge_with_leaf_outside_batch() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, _, id) = setup_batch(&env);
+    let fraud = Address::generate(&env);
+    c.set_fraud_contract(&admin, &fraud);
+    let (_, root, proof0) = receipt_tree(&env, id, &publisher);
+
+    let batch_id = c.settle_view_batch(&publisher, &id, &root, &4u64, &400i128);
+    let (outside, _) = signed_receipt(&env, id, &publisher, 9);
+    c.challenge_view(&fraud, &view_proof(batch_id, outside, &proof0), &None);
+}
+
+#[test]
+#[should_panic(expected = "challenge window closed")]
+fn test_challenge_after_window() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, _, id) = setup_batch(&env);
+    let fraud = Address::generate(&env);
+    c.set_fraud_contract(&admin, &fraud);
+    let (receipts, root, proof0) = receipt_tree(&env, id, &publisher);
+
+    let batch_id = c.settle_view_batch(&publisher, &id, &root, &4u64, &400i128);
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    c.challenge_view(&fraud, &view_proof(batch_id, receipts.get(0).unwrap(), &proof0), &None);
+}
+
+#[test]
+fn test_answered_signature_challenge_is_paid() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, token, id) = setup_batch(&env);
+    let fraud = Address::generate(&env);
+    c.set_fraud_contract(&admin, &fraud);
+    let (receipts, root, proof0) = receipt_tree(&env, id, &publisher);
+
+    let batch_id = c.settle_view_batch(&publisher, &id, &root, &4u64, &400i128);
+    c.challenge_view(&fraud, &view_proof(batch_id, receipts.get(0).unwrap(), &proof0), &None);
+    assert_eq!(c.get_view_batch(&batch_id).unwrap().rejected_views, 1);
+    c.answer_challenge(&publisher, &batch_id, &receipts.get(0).unwrap());
+
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    assert_eq!(c.finalize_view_batch(&batch_id), 400);
+    assert_eq!(TokenClient::new(&env, &token).balance(&publisher), 400);
+}
+
+/// Batch whose first receipt carries a forged signature; returns the batch
+/// id and the forged receipt with its proof
+fn forged_batch(env: &Env, c: &CampaignOrchestratorContractClient, id: u64, publisher: &Address) -> (u64, ViewProof) {
+    let (mut receipts, _, _) = receipt_tree(env, id, publisher);
+    let mut forged = receipts.get(0).unwrap();
+    forged.signature = BytesN::from_array(env, &[7u8; 64]);
+    receipts.set(0, forged.clone());
+    let (root, proof0) = tree_of(env, &receipts);
+    let batch_id = c.settle_view_batch(publisher, &id, &root, &4u64, &400i128);
+    (batch_id, view_proof(batch_id, forged, &proof0))
+}
+
+#[test]
+fn test_unanswered_bad_signature_is_not_paid() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, token, id) = setup_batch(&env);
+    let fraud = Address::generate(&env);
+    c.set_fraud_contract(&admin, &fraud);
+    let (batch_id, forged) = forged_batch(&env, &c, id, &publisher);
+
+    c.challenge_view(&fraud, &forged, &None);
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    assert_eq!(c.finalize_view_batch(&batch_id), 300);
+    assert_eq!(TokenClient::new(&env, &token).balance(&publisher), 300);
+}
+
+#[test]
+#[should_panic]
+fn test_bad_signature_cannot_be_answered() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, _, id) = setup_batch(&env);
+    let fraud = Address::generate(&env);
+    c.set_fraud_contract(&admin, &fraud);
+    let (batch_id, forged) = forged_batch(&env, &c, id, &publisher);
+
+    c.challenge_view(&fraud, &forged, &None);
+    c.answer_challenge(&publisher, &batch_id, &forged.receipt);
+}
+
+#[test]
+fn test_challenge_extends_window_for_answer() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, _, id) = setup_batch(&env);
+    let fraud = Address::generate(&env);
+    c.set_fraud_contract(&admin, &fraud);
+    let (receipts, root, proof0) = receipt_tree(&env, id, &publisher);
+
+    let batch_id = c.settle_view_batch(&publisher, &id, &root, &4u64, &400i128);
+    env.ledger().with_mut(|li| li.timestamp += 86_000);
+    c.challenge_view(&fraud, &view_proof(batch_id, receipts.get(0).unwrap(), &proof0), &None);
+    assert_eq!(c.get_view_batch(&batch_id).unwrap().challenge_deadline, 86_000 + 43_200);
+}
+
+#[test]
+fn test_duplicate_across_batches_is_rejected() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, _, id) = setup_batch(&env);
+    let fraud = Address::generate(&env);
+    c.set_fraud_contract(&admin, &fraud);
+    let (receipts, root, proof0) = receipt_tree(&env, id, &publisher);
+
+    let first = c.settle_view_batch(&publisher, &id, &root, &4u64, &400i128);
+    let second = c.settle_view_batch(&publisher, &id, &root, &4u64, &400i128);
+    let original = view_proof(first, receipts.get(0).unwrap(), &proof0);
+    c.challenge_view(&fraud, &view_proof(second, receipts.get(0).unwrap(), &proof0), &Some(original));
+
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    assert_eq!(c.finalize_view_batch(&first), 400);
+    assert_eq!(c.finalize_view_batch(&second), 300);
+}
+
+#[test]
+#[should_panic(expected = "not a duplicate")]
+fn test_leaf_is_not_its_own_duplicate() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, _, id) = setup_batch(&env);
+    let fraud = Address::generate(&env);
+    c.set_fraud_contract(&admin, &fraud);
+    let (receipts, root, proof0) = receipt_tree(&env, id, &publisher);
+
+    let batch_id = c.settle_view_batch(&publisher, &id, &root, &4u64, &400i128);
+    let challenged = view_proof(batch_id, receipts.get(0).unwrap(), &proof0);
+    c.challenge_view(&fraud, &challenged, &Some(challenged.clone()));
+}
+
+#[test]
+#[should_panic(expected = "no open challenge")]
+fn test_receipt_for_other_campaign_is_rejected_outright() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, _, id) = setup_batch(&env);
+    let fraud = Address::generate(&env);
+    c.set_fraud_contract(&admin, &fraud);
+    let (mut receipts, _, _) = receipt_tree(&env, id, &publisher);
+    let (foreign, _) = signed_receipt(&env, id + 1, &publisher, 0);
+    receipts.set(0, foreign.clone());
+    let (root, proof0) = tree_of(&env, &receipts);
+
+    let batch_id = c.settle_view_batch(&publisher, &id, &root, &4u64, &400i128);
+    c.challenge_view(&fraud, &view_proof(batch_id, foreign.clone(), &proof0), &None);
+    assert_eq!(c.get_view_batch(&batch_id).unwrap().rejected_views, 1);
+    c.answer_challenge(&publisher, &batch_id, &foreign);
+}
+
+#[test]
+fn test_finalize_after_cancel_refunds_advertiser() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, token, id) = setup_batch(&env);
+    let fraud = Address::generate(&env);
+    c.set_fraud_contract(&admin, &fraud);
+    let advertiser = c.get_campaign(&id).unwrap().advertiser;
+    let (receipts, root, proof0) = receipt_tree(&env, id, &publisher);
+
+    let batch_id = c.settle_view_batch(&publisher, &id, &root, &4u64, &400i128);
+    c.challenge_view(&fraud, &view_proof(batch_id, receipts.get(0).unwrap(), &proof0), &None);
+    let tc = TokenClient::new(&env, &token);
+    let before = tc.balance(&advertiser);
+    c.cancel_campaign(&advertiser, &id);
+    assert_eq!(tc.balance(&advertiser), before + 1_000_000 - 400);
+
+    env.ledger().with_mut(|li| li.timestamp += 86_400);
+    assert_eq!(c.finalize_view_batch(&batch_id), 300);
+    assert_eq!(tc.balance(&advertiser), before + 1_000_000 - 300);
+    let campaign = c.get_campaign(&id).unwrap();
+    assert!(matches!(campaign.status, CampaignStatus::Cancelled));
+    assert_eq!(campaign.remaining_budget, 0);
+}
+
+#[test]
+#[should_panic(expected = "challenge window open")]
+fn test_finalize_during_window() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, publisher, _, id) = setup_batch(&env);
+    let (_, root, _) = receipt_tree(&env, id, &publisher);
+
+    let batch_id = c.settle_view_batch(&publisher, &id, &root, &4u64, &400i128);
+    c.finalize_view_batch(&batch_id);
+}
+
+#[test]
+#[should_panic(expected = "payout mismatch")]
+fn test_settle_view_batch_payout_mismatch() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, publisher, _, id) = setup_batch(&env);
+    c.settle_view_batch(&publisher, &id, &BytesN::from_array(&env, &[1u8; 32]), &4u64, &500i128);
+}
+
+#[test]
+#[should_panic]
+fn test_receipt_with_bad_signature_rejected() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, publisher, _, id) = setup_batch(&env);
+    let (mut receipt, _) = signed_receipt(&env, id, &publisher, 0);
+    receipt.nonce = 7; // signature no longer matches
+    c.get_receipt_digest(&receipt);
+}
+
+// ─── loan defaults ───────────────────────────────────────────────────────────
+
+#[test]
+fn test_claim_for_default_seizes_remaining_budget() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, publisher, token, id) = setup_batch(&env);
+    let pool = Address::generate(&env);
+    c.set_lending_pool(&admin, &pool);
+    c.record_view(&id, &publisher);
+
+    let advertiser = c.get_campaign(&id).unwrap().advertiser;
+    let remaining = c.get_campaign(&id).unwrap().remaining_budget;
+    assert_eq!(c.claim_for_default(&pool, &id, &advertiser), remaining);
+    assert_eq!(TokenClient::new(&env, &token).balance(&pool), remaining);
+
+    let campaign = c.get_campaign(&id).unwrap();
+    assert_eq!(campaign.remaining_budget, 0);
+    assert!(matches!(campaign.status, CampaignStatus::Cancelled));
+
+    // Another borrower's default cannot touch this campaign
+    assert_eq!(c.claim_for_default(&pool, &id, &Address::generate(&env)), 0);
+}
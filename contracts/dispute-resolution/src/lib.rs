@@ -1,10 +1,54 @@
 //! PulsarTrack - Dispute Resolution (Soroban)
 //! On-chain dispute resolution for PulsarTrack ecosystem participants on Stellar.
+//!
+//! Filing a dispute escrows the filing fee in this contract and, when an
+//! escrow vault is configured, freezes the open escrow of the campaign held
+//! between the two parties, with `claim_amount` of it in dispute. The
+//! arbitrator's ruling is executed against that amount only: the claimant is
+//! awarded up to `claim_amount` (or a basis-point share of it for a split),
+//! the rest of the disputed funds go back to the other party, and any other
+//! locked funds stay in the escrow. The
+//! filing fee is returned to a winning claimant and forfeited to the admin
+//! when the claimant loses; splits return the claimant's share of the fee.
+//!
+//! A claimant may point a newly filed dispute at the respondent's
+//! performance-oracle attestation for the campaign in a given epoch. A ruling
+//! for the claimant slashes that attester in the oracle, scaled by the
+//! claimant's share, with the proceeds going to that epoch's reward pool.
+//!
+//! Assigning an arbitrator opens the evidence timeline. Both parties may
+//! add to an ordered evidence log until `evidence_deadline`; a party with
+//! nothing on record by then is in default and anyone may call
+//! `default_judgment`. Parties on record may then rebut until
+//! `rebuttal_deadline`, after which the arbitrator can rule.
+//!
+//! A ruling is only executed once its appeal window has passed. Either party
+//! may appeal within the window by posting a bond; the appeal is heard by a
+//! randomly drawn panel of staked arbitrators whose majority ruling is final.
+//! The appellant commits to a seed when appealing and the other party may
+//! add a seed of its own; the panel is drawn from both once the appellant
+//! reveals, so neither side can predict it in advance. An appellant who does
+//! not reveal in time forfeits the bond and the original ruling stands.
+//! Panel members who vote against the majority (or not at all) forfeit
+//! `slash_bps` of their stake.
+//!
+//! Events:
+//! - ("dispute", "filed"): [dispute_id: u64, claimant: Address]
+//! - ("dispute", "resolved"): dispute_id: u64
+//! - ("dispute", "evidence"): [dispute_id: u64, party: Address, index: u32]
+//! - ("dispute", "default"): [dispute_id: u64, outcome: DisputeOutcome]
+//! - ("dispute", "appealed"): [dispute_id: u64, appellant: Address]
+//! - ("dispute", "seeded"): [dispute_id: u64, party: Address]
+//! - ("dispute", "panel"): [dispute_id: u64, panel: Vec<Address>]
+//! - ("dispute", "voted"): [dispute_id: u64, arbitrator: Address]
+//! - ("dispute", "ruling"): dispute_id: u64
+//! - ("dispute", "closed"): dispute_id: u64
+//! - ("arbiter", "slashed"): [dispute_id: u64, arbitrator: Address, amount: i128]
 
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short,
-    token, Address, Env, String,
+    contract, contractimpl, contracttype, symbol_short, token, vec, Address, Bytes, BytesN, Env,
+    IntoVal, String, Symbol, Vec,
 };
 
 #[contracttype]
@@ -25,10 +69,37 @@ pub enum DisputeOutcome {
     Pending,
     Claimant,
     Respondent,
-    Split,
+    Split(u32), // claimant's share in basis points
     NoAction,
 }
 
+#[contracttype]
+#[derive(Clone, PartialEq)]
+pub enum EvidenceKind {
+    Document,
+    Analytics,
+    Media,
+    Statement,
+    Other,
+}
+
+#[contracttype]
+#[derive(Clone, PartialEq)]
+pub enum EvidencePhase {
+    Submission,
+    Rebuttal,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Evidence {
+    pub party: Address,
+    pub hash: String, // IPFS hash
+    pub kind: EvidenceKind,
+    pub phase: EvidencePhase,
+    pub submitted_at: u64,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct Dispute {
@@ -46,6 +117,36 @@ pub struct Dispute {
     pub filed_at: u64,
     pub resolved_at: Option<u64>,
     pub arbitrator: Option<Address>,
+    pub escrow_id: Option<u64>,
+    pub filing_fee: i128,
+    pub appeal_deadline: u64,
+    pub evidence_deadline: u64,
+    pub rebuttal_deadline: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct AppealConfig {
+    pub appeal_window: u64,
+    pub appeal_bond: i128,
+    pub panel_size: u32,
+    pub voting_period: u64,
+    pub min_stake: i128,
+    pub slash_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Appeal {
+    pub appellant: Address,
+    pub bond: i128,
+    pub original_outcome: DisputeOutcome,
+    pub seed_hash: BytesN<32>,          // sha256 of the appellant's panel seed
+    pub counter_seed: Option<BytesN<32>>,
+    pub seed_deadline: u64,             // last moment the other party may add its seed
+    pub panel: Vec<Address>,            // empty until drawn
+    pub votes_cast: u32,
+    pub voting_deadline: u64,
 }
 
 #[contracttype]
@@ -55,14 +156,35 @@ pub enum DataKey {
     DisputeCounter,
     FilingFee,
     TokenAddress,
+    EscrowVault,
+    PerformanceOracle,
+    AppealConfig,
+    EvidenceWindow,
+    RebuttalWindow,
     Dispute(u64),
+    EvidenceCount(u64),
+    Evidence(u64, u32),
+    OnRecord(u64, Address),
+    Appeal(u64),
+    PanelVote(u64, Address),
+    ArbitratorStake(Address),
+    PanelSeats(Address), // open panels the arbitrator sits on
     ArbitratorApproved(Address),
+    AttestationTarget(u64), // dispute_id -> epoch of the disputed attestation
 }
 
 const INSTANCE_LIFETIME_THRESHOLD: u32 = 17_280;
 const INSTANCE_BUMP_AMOUNT: u32 = 86_400;
 const PERSISTENT_LIFETIME_THRESHOLD: u32 = 34_560;
 const PERSISTENT_BUMP_AMOUNT: u32 = 259_200;
+const BPS_DENOMINATOR: i128 = 10_000;
+const DEFAULT_APPEAL_WINDOW: u64 = 259_200; // 3 days
+const DEFAULT_VOTING_PERIOD: u64 = 604_800; // 7 days
+const DEFAULT_PANEL_SIZE: u32 = 3;
+const DEFAULT_SLASH_BPS: u32 = 1_000;
+const DEFAULT_EVIDENCE_WINDOW: u64 = 432_000; // 5 days
+const DEFAULT_REBUTTAL_WINDOW: u64 = 172_800; // 2 days
+const PANEL_SEED_WINDOW: u64 = 86_400; // 1 day
 
 #[contract]
 pub struct DisputeResolutionContract;
@@ -88,11 +210,68 @@ impl DisputeResolutionContract {
         if admin != stored_admin {
             panic!("unauthorized");
         }
+        let mut pool: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::ArbitratorPool)
+            .unwrap_or(Vec::new(&env));
+        if !pool.contains(&arbitrator) {
+            pool.push_back(arbitrator.clone());
+            env.storage().instance().set(&DataKey::ArbitratorPool, &pool);
+        }
+
         let _ttl_key = DataKey::ArbitratorApproved(arbitrator);
         env.storage().persistent().set(&_ttl_key, &true);
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
     }
 
+    pub fn set_escrow_vault(env: Env, admin: Address, escrow_vault: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        env.storage().instance().set(&DataKey::EscrowVault, &escrow_vault);
+    }
+
+    pub fn set_performance_oracle(env: Env, admin: Address, oracle: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        env.storage().instance().set(&DataKey::PerformanceOracle, &oracle);
+    }
+
+    /// Claimant: mark a freshly filed dispute as contesting the respondent's
+    /// oracle attestation for the dispute's campaign in `epoch`. A ruling for
+    /// the claimant then slashes that attester.
+    pub fn target_attestation(env: Env, claimant: Address, dispute_id: u64, epoch: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        claimant.require_auth();
+
+        let dispute: Dispute = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Dispute(dispute_id))
+            .expect("dispute not found");
+        if dispute.claimant != claimant {
+            panic!("unauthorized");
+        }
+        if dispute.status != DisputeStatus::Filed {
+            panic!("dispute already under review");
+        }
+        if !env.storage().instance().has(&DataKey::PerformanceOracle) {
+            panic!("oracle not configured");
+        }
+
+        let _ttl_key = DataKey::AttestationTarget(dispute_id);
+        env.storage().persistent().set(&_ttl_key, &epoch);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
     pub fn file_dispute(
         env: Env,
         claimant: Address,
@@ -105,18 +284,41 @@ impl DisputeResolutionContract {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         claimant.require_auth();
 
-        // Collect filing fee
+        if claim_amount <= 0 {
+            panic!("invalid claim amount");
+        }
+        if claimant == respondent {
+            panic!("cannot dispute self");
+        }
+
+        // Hold the filing fee until the ruling
         let fee: i128 = env.storage().instance().get(&DataKey::FilingFee).unwrap_or(0);
         if fee > 0 {
             let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
-            let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
             let token_client = token::Client::new(&env, &token_addr);
-            token_client.transfer(&claimant, &admin, &fee);
+            token_client.transfer(&claimant, &env.current_contract_address(), &fee);
         }
 
         let counter: u64 = env.storage().instance().get(&DataKey::DisputeCounter).unwrap_or(0);
         let dispute_id = counter + 1;
 
+        // Freeze the campaign escrow between the parties, if there is one
+        let escrow_id: Option<u64> = match env.storage().instance().get::<_, Address>(&DataKey::EscrowVault) {
+            Some(vault) => env.invoke_contract(
+                &vault,
+                &Symbol::new(&env, "freeze_for_dispute"),
+                vec![
+                    &env,
+                    env.current_contract_address().into_val(&env),
+                    campaign_id.into_val(&env),
+                    claimant.into_val(&env),
+                    respondent.into_val(&env),
+                    claim_amount.into_val(&env),
+                ],
+            ),
+            None => None,
+        };
+
         let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
         let dispute = Dispute {
             dispute_id,
@@ -133,6 +335,11 @@ impl DisputeResolutionContract {
             filed_at: env.ledger().timestamp(),
             resolved_at: None,
             arbitrator: None,
+            escrow_id,
+            filing_fee: fee,
+            appeal_deadline: 0,
+            evidence_deadline: 0,
+            rebuttal_deadline: 0,
         };
 
         let _ttl_key = DataKey::Dispute(dispute_id);
@@ -140,6 +347,10 @@ impl DisputeResolutionContract {
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
         env.storage().instance().set(&DataKey::DisputeCounter, &dispute_id);
 
+        if !dispute.evidence_hash.is_empty() {
+            Self::_log_evidence(&env, dispute_id, &dispute.claimant, dispute.evidence_hash.clone(), EvidenceKind::Document, EvidencePhase::Submission);
+        }
+
         env.events().publish(
             (symbol_short!("dispute"), symbol_short!("filed")),
             (dispute_id, claimant),
@@ -171,9 +382,23 @@ impl DisputeResolutionContract {
             .persistent()
             .get(&DataKey::Dispute(dispute_id))
             .expect("dispute not found");
+        if dispute.status != DisputeStatus::Filed
+            && dispute.status != DisputeStatus::AwaitingEvidence
+            && dispute.status != DisputeStatus::UnderReview
+        {
+            panic!("dispute already resolved");
+        }
 
+        // The evidence timeline starts with the first assignment only
+        if dispute.status == DisputeStatus::Filed {
+            let now = env.ledger().timestamp();
+            let evidence_window: u64 = env.storage().instance().get(&DataKey::EvidenceWindow).unwrap_or(DEFAULT_EVIDENCE_WINDOW);
+            let rebuttal_window: u64 = env.storage().instance().get(&DataKey::RebuttalWindow).unwrap_or(DEFAULT_REBUTTAL_WINDOW);
+            dispute.evidence_deadline = now + evidence_window;
+            dispute.rebuttal_deadline = dispute.evidence_deadline + rebuttal_window;
+            dispute.status = DisputeStatus::AwaitingEvidence;
+        }
         dispute.arbitrator = Some(arbitrator);
-        dispute.status = DisputeStatus::UnderReview;
         let _ttl_key = DataKey::Dispute(dispute_id);
         env.storage().persistent().set(&_ttl_key, &dispute);
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
@@ -202,11 +427,26 @@ impl DisputeResolutionContract {
         } else {
             panic!("not assigned arbitrator");
         }
+        if dispute.status != DisputeStatus::AwaitingEvidence && dispute.status != DisputeStatus::UnderReview {
+            panic!("dispute already resolved");
+        }
+        Self::_claimant_bps(&outcome);
+
+        let now = env.ledger().timestamp();
+        if now <= dispute.rebuttal_deadline {
+            panic!("evidence window open");
+        }
+        if !Self::_on_record(&env, dispute_id, &dispute.claimant)
+            || !Self::_on_record(&env, dispute_id, &dispute.respondent)
+        {
+            panic!("party in default");
+        }
 
         dispute.outcome = outcome;
         dispute.resolution_notes = notes;
         dispute.status = DisputeStatus::Resolved;
-        dispute.resolved_at = Some(env.ledger().timestamp());
+        dispute.resolved_at = Some(now);
+        dispute.appeal_deadline = now + Self::get_appeal_config(env.clone()).appeal_window;
 
         let _ttl_key = DataKey::Dispute(dispute_id);
         env.storage().persistent().set(&_ttl_key, &dispute);
@@ -218,6 +458,442 @@ impl DisputeResolutionContract {
         );
     }
 
+    // ============================================================
+    // Evidence
+    // ============================================================
+
+    pub fn set_evidence_windows(env: Env, admin: Address, evidence_window: u64, rebuttal_window: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        env.storage().instance().set(&DataKey::EvidenceWindow, &evidence_window);
+        env.storage().instance().set(&DataKey::RebuttalWindow, &rebuttal_window);
+    }
+
+    /// Append to the dispute's evidence log. Open to both parties until the
+    /// evidence deadline; during the rebuttal phase only parties already on
+    /// record may add to it.
+    pub fn submit_evidence(
+        env: Env,
+        party: Address,
+        dispute_id: u64,
+        hash: String,
+        kind: EvidenceKind,
+    ) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        party.require_auth();
+
+        let dispute: Dispute = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Dispute(dispute_id))
+            .expect("dispute not found");
+        if party != dispute.claimant && party != dispute.respondent {
+            panic!("not a party");
+        }
+        if hash.is_empty() {
+            panic!("invalid evidence hash");
+        }
+
+        let now = env.ledger().timestamp();
+        let phase = match dispute.status {
+            DisputeStatus::Filed => EvidencePhase::Submission,
+            DisputeStatus::AwaitingEvidence if now <= dispute.evidence_deadline => EvidencePhase::Submission,
+            DisputeStatus::AwaitingEvidence if now <= dispute.rebuttal_deadline => {
+                if !Self::_on_record(&env, dispute_id, &party) {
+                    panic!("party in default");
+                }
+                EvidencePhase::Rebuttal
+            }
+            _ => panic!("evidence window closed"),
+        };
+
+        Self::_log_evidence(&env, dispute_id, &party, hash, kind, phase)
+    }
+
+    /// Permissionless once the evidence deadline has passed: rules against
+    /// whichever party has nothing on record (no action if neither does).
+    pub fn default_judgment(env: Env, dispute_id: u64) -> DisputeOutcome {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        let mut dispute: Dispute = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Dispute(dispute_id))
+            .expect("dispute not found");
+        if dispute.status != DisputeStatus::AwaitingEvidence {
+            panic!("dispute not awaiting evidence");
+        }
+        let now = env.ledger().timestamp();
+        if now <= dispute.evidence_deadline {
+            panic!("evidence window open");
+        }
+
+        let claimant_on_record = Self::_on_record(&env, dispute_id, &dispute.claimant);
+        let respondent_on_record = Self::_on_record(&env, dispute_id, &dispute.respondent);
+        let outcome = match (claimant_on_record, respondent_on_record) {
+            (true, false) => DisputeOutcome::Claimant,
+            (false, true) => DisputeOutcome::Respondent,
+            (false, false) => DisputeOutcome::NoAction,
+            (true, true) => panic!("no party in default"),
+        };
+
+        dispute.outcome = outcome.clone();
+        dispute.resolution_notes = String::from_str(&env, "default judgment");
+        dispute.status = DisputeStatus::Resolved;
+        dispute.resolved_at = Some(now);
+        dispute.appeal_deadline = now + Self::get_appeal_config(env.clone()).appeal_window;
+
+        let _ttl_key = DataKey::Dispute(dispute_id);
+        env.storage().persistent().set(&_ttl_key, &dispute);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("default")),
+            (dispute_id, outcome.clone()),
+        );
+        outcome
+    }
+
+    // ============================================================
+    // Appeals
+    // ============================================================
+
+    pub fn set_appeal_config(env: Env, admin: Address, config: AppealConfig) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        if config.panel_size == 0 || config.panel_size.is_multiple_of(2) {
+            panic!("panel size must be odd");
+        }
+        if config.appeal_bond < 0 || config.min_stake < 0 || config.slash_bps > BPS_DENOMINATOR as u32 {
+            panic!("invalid config");
+        }
+        env.storage().instance().set(&DataKey::AppealConfig, &config);
+    }
+
+    pub fn stake_arbitrator(env: Env, arbitrator: Address, amount: i128) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        arbitrator.require_auth();
+        if amount <= 0 {
+            panic!("invalid amount");
+        }
+
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        let token_client = token::Client::new(&env, &token_addr);
+        token_client.transfer(&arbitrator, &env.current_contract_address(), &amount);
+
+        let stake = Self::get_arbitrator_stake(env.clone(), arbitrator.clone());
+        let _ttl_key = DataKey::ArbitratorStake(arbitrator);
+        env.storage().persistent().set(&_ttl_key, &(stake + amount));
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    pub fn unstake_arbitrator(env: Env, arbitrator: Address, amount: i128) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        arbitrator.require_auth();
+
+        let seats: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::PanelSeats(arbitrator.clone()))
+            .unwrap_or(0);
+        if seats > 0 {
+            panic!("stake locked by open panel");
+        }
+        let stake = Self::get_arbitrator_stake(env.clone(), arbitrator.clone());
+        if amount <= 0 || amount > stake {
+            panic!("insufficient stake");
+        }
+
+        let _ttl_key = DataKey::ArbitratorStake(arbitrator.clone());
+        env.storage().persistent().set(&_ttl_key, &(stake - amount));
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        let token_client = token::Client::new(&env, &token_addr);
+        token_client.transfer(&env.current_contract_address(), &arbitrator, &amount);
+    }
+
+    /// A party contests the ruling before `appeal_deadline` by posting the
+    /// appeal bond and committing to `seed_hash`, the sha256 of a secret seed
+    /// that is revealed later in `draw_panel`.
+    pub fn appeal_dispute(env: Env, appellant: Address, dispute_id: u64, seed_hash: BytesN<32>) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        appellant.require_auth();
+
+        let mut dispute: Dispute = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Dispute(dispute_id))
+            .expect("dispute not found");
+
+        if appellant != dispute.claimant && appellant != dispute.respondent {
+            panic!("not a party");
+        }
+        if dispute.status != DisputeStatus::Resolved {
+            panic!("dispute not appealable");
+        }
+        let now = env.ledger().timestamp();
+        if now > dispute.appeal_deadline {
+            panic!("appeal window closed");
+        }
+
+        let config = Self::get_appeal_config(env.clone());
+        if Self::_eligible_panelists(&env, &dispute, &config).len() < config.panel_size {
+            panic!("insufficient arbitrators");
+        }
+
+        if config.appeal_bond > 0 {
+            let token_client = token::Client::new(&env, &dispute.token);
+            token_client.transfer(&appellant, &env.current_contract_address(), &config.appeal_bond);
+        }
+
+        let appeal = Appeal {
+            appellant: appellant.clone(),
+            bond: config.appeal_bond,
+            original_outcome: dispute.outcome.clone(),
+            seed_hash,
+            counter_seed: None,
+            seed_deadline: now + PANEL_SEED_WINDOW,
+            panel: Vec::new(&env),
+            votes_cast: 0,
+            voting_deadline: 0,
+        };
+        let _ttl_key = DataKey::Appeal(dispute_id);
+        env.storage().persistent().set(&_ttl_key, &appeal);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        dispute.status = DisputeStatus::Appealed;
+        let _ttl_key = DataKey::Dispute(dispute_id);
+        env.storage().persistent().set(&_ttl_key, &dispute);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("appealed")),
+            (dispute_id, appellant),
+        );
+    }
+
+    /// The other party adds its own seed to the panel draw before
+    /// `seed_deadline`. It is mixed with the appellant's committed seed, so
+    /// the appellant cannot steer the draw by choosing a seed.
+    pub fn contribute_seed(env: Env, party: Address, dispute_id: u64, seed: BytesN<32>) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        party.require_auth();
+
+        let dispute: Dispute = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Dispute(dispute_id))
+            .expect("dispute not found");
+        let mut appeal: Appeal = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Appeal(dispute_id))
+            .expect("appeal not found");
+
+        let opponent = if appeal.appellant == dispute.claimant {
+            &dispute.respondent
+        } else {
+            &dispute.claimant
+        };
+        if &party != opponent {
+            panic!("not the opposing party");
+        }
+        if env.ledger().timestamp() > appeal.seed_deadline {
+            panic!("seed window closed");
+        }
+        if appeal.counter_seed.is_some() {
+            panic!("seed already contributed");
+        }
+
+        appeal.counter_seed = Some(seed);
+        let _ttl_key = DataKey::Appeal(dispute_id);
+        env.storage().persistent().set(&_ttl_key, &appeal);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("seeded")),
+            (dispute_id, party),
+        );
+    }
+
+    /// The appellant reveals its seed and the panel is drawn from staked,
+    /// approved arbitrators other than the original arbitrator and the
+    /// parties. Allowed once the other party has contributed its seed or
+    /// `seed_deadline` has passed, and until `PANEL_SEED_WINDOW` after that.
+    pub fn draw_panel(env: Env, appellant: Address, dispute_id: u64, seed: BytesN<32>) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        appellant.require_auth();
+
+        let dispute: Dispute = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Dispute(dispute_id))
+            .expect("dispute not found");
+        let mut appeal: Appeal = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Appeal(dispute_id))
+            .expect("appeal not found");
+
+        if appellant != appeal.appellant {
+            panic!("not the appellant");
+        }
+        if !appeal.panel.is_empty() {
+            panic!("panel already drawn");
+        }
+        let now = env.ledger().timestamp();
+        if appeal.counter_seed.is_none() && now <= appeal.seed_deadline {
+            panic!("seed window open");
+        }
+        if now > appeal.seed_deadline + PANEL_SEED_WINDOW {
+            panic!("reveal window closed");
+        }
+        let revealed: BytesN<32> = env.crypto().sha256(&Bytes::from(seed.clone())).into();
+        if revealed != appeal.seed_hash {
+            panic!("seed mismatch");
+        }
+
+        let config = Self::get_appeal_config(env.clone());
+        let mut eligible = Self::_eligible_panelists(&env, &dispute, &config);
+        if eligible.len() < config.panel_size {
+            panic!("insufficient arbitrators");
+        }
+        let mut material = Bytes::from(seed);
+        material.append(&Bytes::from(appeal.counter_seed.clone().unwrap_or(BytesN::from_array(&env, &[0; 32]))));
+        let draw_seed: BytesN<32> = env.crypto().sha256(&material).into();
+        env.prng().seed(draw_seed.into());
+        env.prng().shuffle(&mut eligible);
+        let panel = eligible.slice(0..config.panel_size);
+
+        for member in panel.iter() {
+            let seats: u32 = env
+                .storage()
+                .persistent()
+                .get(&DataKey::PanelSeats(member.clone()))
+                .unwrap_or(0);
+            let _ttl_key = DataKey::PanelSeats(member);
+            env.storage().persistent().set(&_ttl_key, &(seats + 1));
+            env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        }
+
+        appeal.panel = panel.clone();
+        appeal.voting_deadline = now + config.voting_period;
+        let _ttl_key = DataKey::Appeal(dispute_id);
+        env.storage().persistent().set(&_ttl_key, &appeal);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("panel")),
+            (dispute_id, panel),
+        );
+    }
+
+    pub fn cast_panel_vote(env: Env, arbitrator: Address, dispute_id: u64, outcome: DisputeOutcome) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        arbitrator.require_auth();
+
+        let mut dispute: Dispute = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Dispute(dispute_id))
+            .expect("dispute not found");
+        if dispute.status != DisputeStatus::Appealed && dispute.status != DisputeStatus::Deliberating {
+            panic!("dispute not under appeal");
+        }
+
+        let mut appeal: Appeal = env.storage().persistent().get(&DataKey::Appeal(dispute_id)).unwrap();
+        if !appeal.panel.contains(&arbitrator) {
+            panic!("not on panel");
+        }
+        if env.ledger().timestamp() > appeal.voting_deadline {
+            panic!("voting closed");
+        }
+        let vote_key = DataKey::PanelVote(dispute_id, arbitrator.clone());
+        if env.storage().persistent().has(&vote_key) {
+            panic!("already voted");
+        }
+        Self::_claimant_bps(&outcome);
+
+        env.storage().persistent().set(&vote_key, &outcome);
+        env.storage().persistent().extend_ttl(&vote_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        appeal.votes_cast += 1;
+        let _ttl_key = DataKey::Appeal(dispute_id);
+        env.storage().persistent().set(&_ttl_key, &appeal);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("voted")),
+            (dispute_id, arbitrator),
+        );
+
+        if appeal.votes_cast == appeal.panel.len() {
+            Self::_rule_on_appeal(&env, &mut dispute, &appeal);
+        } else {
+            dispute.status = DisputeStatus::Deliberating;
+        }
+        let _ttl_key = DataKey::Dispute(dispute_id);
+        env.storage().persistent().set(&_ttl_key, &dispute);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    /// Permissionless. Executes an unappealed ruling once the appeal window
+    /// has passed, or rules on an appeal once its voting period has ended.
+    pub fn finalize_dispute(env: Env, dispute_id: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        let mut dispute: Dispute = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Dispute(dispute_id))
+            .expect("dispute not found");
+        let now = env.ledger().timestamp();
+
+        match dispute.status {
+            DisputeStatus::Resolved => {
+                if now <= dispute.appeal_deadline {
+                    panic!("appeal window open");
+                }
+                Self::_execute_outcome(&env, &dispute);
+                dispute.status = DisputeStatus::Closed;
+                env.events().publish(
+                    (symbol_short!("dispute"), symbol_short!("closed")),
+                    dispute_id,
+                );
+            }
+            DisputeStatus::Appealed | DisputeStatus::Deliberating => {
+                let appeal: Appeal = env.storage().persistent().get(&DataKey::Appeal(dispute_id)).unwrap();
+                // an undrawn panel lapses once the reveal window has passed,
+                // leaving the original ruling in place
+                if appeal.panel.is_empty() && now <= appeal.seed_deadline + PANEL_SEED_WINDOW {
+                    panic!("panel not drawn");
+                }
+                if now <= appeal.voting_deadline {
+                    panic!("voting open");
+                }
+                Self::_rule_on_appeal(&env, &mut dispute, &appeal);
+            }
+            _ => panic!("dispute not final"),
+        }
+
+        let _ttl_key = DataKey::Dispute(dispute_id);
+        env.storage().persistent().set(&_ttl_key, &dispute);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    // ============================================================
+    // Read-only
+    // ============================================================
+
     pub fn get_dispute(env: Env, dispute_id: u64) -> Option<Dispute> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage().persistent().get(&DataKey::Dispute(dispute_id))
@@ -227,6 +903,282 @@ impl DisputeResolutionContract {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage().instance().get(&DataKey::DisputeCounter).unwrap_or(0)
     }
+
+    pub fn get_evidence_count(env: Env, dispute_id: u64) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::EvidenceCount(dispute_id)).unwrap_or(0)
+    }
+
+    pub fn get_evidence(env: Env, dispute_id: u64, index: u32) -> Option<Evidence> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::Evidence(dispute_id, index))
+    }
+
+    pub fn get_evidence_log(env: Env, dispute_id: u64) -> Vec<Evidence> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let count: u32 = env.storage().persistent().get(&DataKey::EvidenceCount(dispute_id)).unwrap_or(0);
+        let mut log = Vec::new(&env);
+        for index in 0..count {
+            if let Some(entry) = env.storage().persistent().get(&DataKey::Evidence(dispute_id, index)) {
+                log.push_back(entry);
+            }
+        }
+        log
+    }
+
+    pub fn get_appeal(env: Env, dispute_id: u64) -> Option<Appeal> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::Appeal(dispute_id))
+    }
+
+    pub fn get_panel_vote(env: Env, dispute_id: u64, arbitrator: Address) -> Option<DisputeOutcome> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::PanelVote(dispute_id, arbitrator))
+    }
+
+    pub fn get_arbitrator_stake(env: Env, arbitrator: Address) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::ArbitratorStake(arbitrator)).unwrap_or(0)
+    }
+
+    pub fn get_appeal_config(env: Env) -> AppealConfig {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::AppealConfig).unwrap_or(AppealConfig {
+            appeal_window: DEFAULT_APPEAL_WINDOW,
+            appeal_bond: 0,
+            panel_size: DEFAULT_PANEL_SIZE,
+            voting_period: DEFAULT_VOTING_PERIOD,
+            min_stake: 0,
+            slash_bps: DEFAULT_SLASH_BPS,
+        })
+    }
+
+    // ============================================================
+    // Internal
+    // ============================================================
+
+    fn _on_record(env: &Env, dispute_id: u64, party: &Address) -> bool {
+        env.storage().persistent().has(&DataKey::OnRecord(dispute_id, party.clone()))
+    }
+
+    fn _log_evidence(
+        env: &Env,
+        dispute_id: u64,
+        party: &Address,
+        hash: String,
+        kind: EvidenceKind,
+        phase: EvidencePhase,
+    ) -> u32 {
+        let index: u32 = env.storage().persistent().get(&DataKey::EvidenceCount(dispute_id)).unwrap_or(0);
+        let entry = Evidence {
+            party: party.clone(),
+            hash,
+            kind,
+            phase,
+            submitted_at: env.ledger().timestamp(),
+        };
+
+        let _ttl_key = DataKey::Evidence(dispute_id, index);
+        env.storage().persistent().set(&_ttl_key, &entry);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let _ttl_key = DataKey::EvidenceCount(dispute_id);
+        env.storage().persistent().set(&_ttl_key, &(index + 1));
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let _ttl_key = DataKey::OnRecord(dispute_id, party.clone());
+        env.storage().persistent().set(&_ttl_key, &true);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("evidence")),
+            (dispute_id, party.clone(), index),
+        );
+        index
+    }
+
+    /// Claimant's share of the claim and filing fee for an outcome
+    fn _claimant_bps(outcome: &DisputeOutcome) -> i128 {
+        match outcome {
+            DisputeOutcome::Pending => panic!("invalid outcome"),
+            DisputeOutcome::Claimant => BPS_DENOMINATOR,
+            DisputeOutcome::Respondent => 0,
+            DisputeOutcome::Split(bps) => {
+                if *bps > BPS_DENOMINATOR as u32 {
+                    panic!("invalid split");
+                }
+                *bps as i128
+            }
+            DisputeOutcome::NoAction => BPS_DENOMINATOR,
+        }
+    }
+
+    /// Tally the panel: an outcome backed by a majority of the panel becomes
+    /// final and every member who did not vote for it is slashed. Without a
+    /// majority the original ruling stands. The bond is returned only if the
+    /// appeal changed the outcome.
+    fn _rule_on_appeal(env: &Env, dispute: &mut Dispute, appeal: &Appeal) {
+        let dispute_id = dispute.dispute_id;
+        let mut majority: Option<DisputeOutcome> = None;
+        for member in appeal.panel.iter() {
+            let vote: Option<DisputeOutcome> =
+                env.storage().persistent().get(&DataKey::PanelVote(dispute_id, member));
+            let Some(vote) = vote else { continue };
+            let mut support = 0u32;
+            for other in appeal.panel.iter() {
+                let other_vote: Option<DisputeOutcome> =
+                    env.storage().persistent().get(&DataKey::PanelVote(dispute_id, other));
+                if other_vote.as_ref() == Some(&vote) {
+                    support += 1;
+                }
+            }
+            if support * 2 > appeal.panel.len() {
+                majority = Some(vote);
+                break;
+            }
+        }
+
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        let token_client = token::Client::new(env, &dispute.token);
+        let config = Self::get_appeal_config(env.clone());
+
+        for member in appeal.panel.iter() {
+            let seats: u32 = env
+                .storage()
+                .persistent()
+                .get(&DataKey::PanelSeats(member.clone()))
+                .unwrap_or(0);
+            env.storage().persistent().set(&DataKey::PanelSeats(member.clone()), &seats.saturating_sub(1));
+
+            let Some(ref ruling) = majority else { continue };
+            let vote: Option<DisputeOutcome> =
+                env.storage().persistent().get(&DataKey::PanelVote(dispute_id, member.clone()));
+            if vote.as_ref() == Some(ruling) {
+                continue;
+            }
+            let stake = Self::get_arbitrator_stake(env.clone(), member.clone());
+            let slashed = stake * config.slash_bps as i128 / BPS_DENOMINATOR;
+            if slashed > 0 {
+                let _ttl_key = DataKey::ArbitratorStake(member.clone());
+                env.storage().persistent().set(&_ttl_key, &(stake - slashed));
+                env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+                token_client.transfer(&env.current_contract_address(), &admin, &slashed);
+                env.events().publish(
+                    (symbol_short!("arbiter"), symbol_short!("slashed")),
+                    (dispute_id, member, slashed),
+                );
+            }
+        }
+
+        let ruling = majority.unwrap_or(appeal.original_outcome.clone());
+        if appeal.bond > 0 {
+            let bond_to = if ruling != appeal.original_outcome {
+                appeal.appellant.clone()
+            } else {
+                admin
+            };
+            token_client.transfer(&env.current_contract_address(), &bond_to, &appeal.bond);
+        }
+
+        dispute.outcome = ruling;
+        dispute.resolved_at = Some(env.ledger().timestamp());
+        Self::_execute_outcome(env, dispute);
+        dispute.status = DisputeStatus::Closed;
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("ruling")),
+            dispute_id,
+        );
+    }
+
+    /// Staked, approved arbitrators other than the original arbitrator and the parties
+    fn _eligible_panelists(env: &Env, dispute: &Dispute, config: &AppealConfig) -> Vec<Address> {
+        let pool: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::ArbitratorPool)
+            .unwrap_or(Vec::new(env));
+        let mut eligible: Vec<Address> = Vec::new(env);
+        for candidate in pool.iter() {
+            let approved: bool = env
+                .storage()
+                .persistent()
+                .get(&DataKey::ArbitratorApproved(candidate.clone()))
+                .unwrap_or(false);
+            if !approved
+                || dispute.arbitrator.as_ref() == Some(&candidate)
+                || candidate == dispute.claimant
+                || candidate == dispute.respondent
+                || Self::get_arbitrator_stake(env.clone(), candidate.clone()) < config.min_stake
+            {
+                continue;
+            }
+            eligible.push_back(candidate);
+        }
+        eligible
+    }
+
+    /// Settle the frozen escrow and the filing fee according to the outcome
+    fn _execute_outcome(env: &Env, dispute: &Dispute) {
+        let claimant_bps = Self::_claimant_bps(&dispute.outcome);
+
+        if let Some(escrow_id) = dispute.escrow_id {
+            let vault: Address = env.storage().instance().get(&DataKey::EscrowVault).unwrap();
+            if dispute.outcome == DisputeOutcome::NoAction {
+                let _: () = env.invoke_contract(
+                    &vault,
+                    &Symbol::new(env, "release_dispute_hold"),
+                    vec![env, env.current_contract_address().into_val(env), escrow_id.into_val(env)],
+                );
+            } else {
+                let award = dispute.claim_amount * claimant_bps / BPS_DENOMINATOR;
+                let _: () = env.invoke_contract(
+                    &vault,
+                    &Symbol::new(env, "settle_dispute"),
+                    vec![
+                        env,
+                        env.current_contract_address().into_val(env),
+                        escrow_id.into_val(env),
+                        dispute.claimant.into_val(env),
+                        award.into_val(env),
+                    ],
+                );
+            }
+        }
+
+        // Slash the attester whose attestation the claimant contested, only
+        // when the ruling went (at least partly) for the claimant
+        let for_claimant = matches!(dispute.outcome, DisputeOutcome::Claimant | DisputeOutcome::Split(_));
+        if for_claimant && claimant_bps > 0 {
+            if let Some(epoch) = env.storage().persistent().get::<_, u64>(&DataKey::AttestationTarget(dispute.dispute_id)) {
+                let oracle: Address = env.storage().instance().get(&DataKey::PerformanceOracle).unwrap();
+                let _: i128 = env.invoke_contract(
+                    &oracle,
+                    &Symbol::new(env, "slash_by_ruling"),
+                    vec![
+                        env,
+                        env.current_contract_address().into_val(env),
+                        dispute.campaign_id.into_val(env),
+                        epoch.into_val(env),
+                        dispute.respondent.into_val(env),
+                        (claimant_bps as u32).into_val(env),
+                    ],
+                );
+            }
+        }
+
+        // Return the claimant's share of the fee; the rest is forfeited
+        if dispute.filing_fee > 0 {
+            let token_client = token::Client::new(env, &dispute.token);
+            let refund = dispute.filing_fee * claimant_bps / BPS_DENOMINATOR;
+            let forfeit = dispute.filing_fee - refund;
+            if refund > 0 {
+                token_client.transfer(&env.current_contract_address(), &dispute.claimant, &refund);
+            }
+            if forfeit > 0 {
+                let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+                token_client.transfer(&env.current_contract_address(), &admin, &forfeit);
+            }
+        }
+    }
 }
 
 mod test;
@@ -1,11 +1,77 @@
 #![cfg(test)]
 use super::*;
 use soroban_sdk::{
+    contract as soroban_contract, contractimpl as soroban_contractimpl,
     testutils::{Address as _, Ledger},
-    token::StellarAssetClient,
-    Address, Env, String,
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Bytes, BytesN, Env, String,
 };
 
+// ─── mock escrow vault ───────────────────────────────────────────────────────
+
+#[soroban_contract]
+pub struct MockVault;
+
+#[soroban_contractimpl]
+impl MockVault {
+    pub fn freeze_for_dispute(
+        env: Env,
+        _dispute_contract: Address,
+        campaign_id: u64,
+        _claimant: Address,
+        _respondent: Address,
+        _amount: i128,
+    ) -> Option<u64> {
+        // campaign 1 has escrow 42; anything else has none
+        if campaign_id == 1 {
+            env.storage().instance().set(&symbol_short!("frozen"), &42u64);
+            Some(42)
+        } else {
+            None
+        }
+    }
+
+    pub fn settle_dispute(env: Env, _dispute_contract: Address, escrow_id: u64, party: Address, party_amount: i128) {
+        env.storage().instance().set(&symbol_short!("settled"), &(escrow_id, party, party_amount));
+    }
+
+    pub fn release_dispute_hold(env: Env, _dispute_contract: Address, escrow_id: u64) {
+        env.storage().instance().set(&symbol_short!("released"), &escrow_id);
+    }
+
+    pub fn settled(env: Env) -> Option<(u64, Address, i128)> {
+        env.storage().instance().get(&symbol_short!("settled"))
+    }
+
+    pub fn released(env: Env) -> Option<u64> {
+        env.storage().instance().get(&symbol_short!("released"))
+    }
+}
+
+// ─── mock performance oracle ─────────────────────────────────────────────────
+
+#[soroban_contract]
+pub struct MockOracle;
+
+#[soroban_contractimpl]
+impl MockOracle {
+    pub fn slash_by_ruling(
+        env: Env,
+        _dispute_contract: Address,
+        campaign_id: u64,
+        epoch: u64,
+        attester: Address,
+        ruling_bps: u32,
+    ) -> i128 {
+        env.storage().instance().set(&symbol_short!("slashed"), &(campaign_id, epoch, attester, ruling_bps));
+        0
+    }
+
+    pub fn slashed(env: Env) -> Option<(u64, u64, Address, u32)> {
+        env.storage().instance().get(&symbol_short!("slashed"))
+    }
+}
+
 // ─── helpers ─────────────────────────────────────────────────────────────────
 
 fn deploy_token(env: &Env, admin: &Address) -> Address {
@@ -29,6 +95,44 @@ fn setup(env: &Env) -> (DisputeResolutionContractClient, Address, Address, Addre
     (client, admin, token_admin, token_addr)
 }
 
+fn setup_vault(env: &Env, client: &DisputeResolutionContractClient, admin: &Address) -> MockVaultClient<'static> {
+    let vault_id = env.register_contract(None, MockVault);
+    client.set_escrow_vault(admin, &vault_id);
+    MockVaultClient::new(env, &vault_id)
+}
+
+/// Files a dispute on campaign 1 and assigns an arbitrator; returns (id, claimant, arbitrator)
+fn assigned_dispute(
+    env: &Env,
+    client: &DisputeResolutionContractClient,
+    admin: &Address,
+    token_addr: &Address,
+) -> (u64, Address, Address) {
+    let claimant = Address::generate(env);
+    let respondent = Address::generate(env);
+    let arbitrator = Address::generate(env);
+    mint(env, token_addr, &claimant, 1_000_000);
+
+    let dispute_id = client.file_dispute(&claimant, &respondent, &1u64, &50_000i128, &make_desc(env), &make_evidence(env));
+    client.authorize_arbitrator(admin, &arbitrator);
+    client.assign_arbitrator(admin, &dispute_id, &arbitrator);
+    close_evidence(env, client, dispute_id, &respondent);
+    (dispute_id, claimant, arbitrator)
+}
+
+/// Puts the respondent on record and moves past the rebuttal deadline
+fn close_evidence(env: &Env, client: &DisputeResolutionContractClient, dispute_id: u64, respondent: &Address) {
+    client.submit_evidence(respondent, &dispute_id, &String::from_str(env, "QmResponse"), &EvidenceKind::Statement);
+    let deadline = client.get_dispute(&dispute_id).unwrap().rebuttal_deadline;
+    env.ledger().with_mut(|li| li.timestamp = deadline + 1);
+}
+
+fn finalize_after_window(env: &Env, client: &DisputeResolutionContractClient, dispute_id: u64) {
+    let deadline = client.get_dispute(&dispute_id).unwrap().appeal_deadline;
+    env.ledger().with_mut(|li| li.timestamp = deadline + 1);
+    client.finalize_dispute(&dispute_id);
+}
+
 fn make_desc(env: &Env) -> String {
     String::from_str(env, "fraudulent clicks")
 }
@@ -143,9 +247,46 @@ fn test_file_dispute() {
     assert!(matches!(dispute.status, DisputeStatus::Filed));
     assert!(matches!(dispute.outcome, DisputeOutcome::Pending));
     assert!(dispute.arbitrator.is_none());
+    assert_eq!(dispute.escrow_id, None);
+    assert_eq!(dispute.filing_fee, 1000);
+
+    // fee is held by the contract until the ruling
+    let tc = TokenClient::new(&env, &token_addr);
+    assert_eq!(tc.balance(&client.address), 1000);
+    assert_eq!(tc.balance(&claimant), 999_000);
     let _ = token_admin;
 }
 
+#[test]
+fn test_file_dispute_freezes_matching_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    setup_vault(&env, &client, &admin);
+
+    let claimant = Address::generate(&env);
+    let respondent = Address::generate(&env);
+    mint(&env, &token_addr, &claimant, 1_000_000);
+
+    let with_escrow = client.file_dispute(&claimant, &respondent, &1u64, &50_000i128, &make_desc(&env), &make_evidence(&env));
+    let without_escrow = client.file_dispute(&claimant, &respondent, &2u64, &50_000i128, &make_desc(&env), &make_evidence(&env));
+
+    assert_eq!(client.get_dispute(&with_escrow).unwrap().escrow_id, Some(42));
+    assert_eq!(client.get_dispute(&without_escrow).unwrap().escrow_id, None);
+}
+
+#[test]
+#[should_panic(expected = "invalid claim amount")]
+fn test_file_dispute_zero_claim() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, token_addr) = setup(&env);
+
+    let claimant = Address::generate(&env);
+    mint(&env, &token_addr, &claimant, 1_000_000);
+    client.file_dispute(&claimant, &Address::generate(&env), &1u64, &0i128, &make_desc(&env), &make_evidence(&env));
+}
+
 #[test]
 fn test_file_multiple_disputes() {
     let env = Env::default();
@@ -184,7 +325,9 @@ fn test_assign_arbitrator() {
 
     let dispute = client.get_dispute(&dispute_id).unwrap();
     assert_eq!(dispute.arbitrator, Some(arbitrator));
-    assert!(matches!(dispute.status, DisputeStatus::UnderReview));
+    assert!(matches!(dispute.status, DisputeStatus::AwaitingEvidence));
+    assert_eq!(dispute.evidence_deadline, 432_000);
+    assert_eq!(dispute.rebuttal_deadline, 604_800);
 }
 
 #[test]
@@ -236,8 +379,10 @@ fn test_resolve_dispute() {
     mint(&env, &token_addr, &claimant, 1_000_000);
 
     let dispute_id = client.file_dispute(&claimant, &respondent, &1u64, &50_000i128, &make_desc(&env), &make_evidence(&env));
+    client.set_evidence_windows(&admin, &100u64, &100u64);
     client.authorize_arbitrator(&admin, &arbitrator);
     client.assign_arbitrator(&admin, &dispute_id, &arbitrator);
+    client.submit_evidence(&respondent, &dispute_id, &make_evidence(&env), &EvidenceKind::Analytics);
 
     env.ledger().with_mut(|li| { li.timestamp = 1000; });
 
@@ -269,6 +414,659 @@ fn test_resolve_by_wrong_arbitrator() {
     client.resolve_dispute(&wrong_arb, &dispute_id, &DisputeOutcome::Respondent, &String::from_str(&env, "wrong"));
 }
 
+#[test]
+fn test_resolve_for_claimant_awards_claim_and_returns_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let vault = setup_vault(&env, &client, &admin);
+    let (dispute_id, claimant, arbitrator) = assigned_dispute(&env, &client, &admin, &token_addr);
+
+    client.resolve_dispute(&arbitrator, &dispute_id, &DisputeOutcome::Claimant, &String::from_str(&env, "paid"));
+    finalize_after_window(&env, &client, dispute_id);
+
+    assert_eq!(vault.settled(), Some((42, claimant.clone(), 50_000)));
+    let tc = TokenClient::new(&env, &token_addr);
+    assert_eq!(tc.balance(&claimant), 1_000_000);
+    assert_eq!(tc.balance(&admin), 0);
+}
+
+#[test]
+fn test_resolve_for_respondent_forfeits_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let vault = setup_vault(&env, &client, &admin);
+    let (dispute_id, claimant, arbitrator) = assigned_dispute(&env, &client, &admin, &token_addr);
+
+    client.resolve_dispute(&arbitrator, &dispute_id, &DisputeOutcome::Respondent, &String::from_str(&env, "denied"));
+    finalize_after_window(&env, &client, dispute_id);
+
+    assert_eq!(vault.settled(), Some((42, claimant.clone(), 0)));
+    let tc = TokenClient::new(&env, &token_addr);
+    assert_eq!(tc.balance(&claimant), 999_000);
+    assert_eq!(tc.balance(&admin), 1000);
+}
+
+#[test]
+fn test_resolve_split_by_bps() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let vault = setup_vault(&env, &client, &admin);
+    let (dispute_id, claimant, arbitrator) = assigned_dispute(&env, &client, &admin, &token_addr);
+
+    client.resolve_dispute(&arbitrator, &dispute_id, &DisputeOutcome::Split(2_500), &String::from_str(&env, "split"));
+    finalize_after_window(&env, &client, dispute_id);
+
+    assert_eq!(vault.settled(), Some((42, claimant.clone(), 12_500)));
+    let tc = TokenClient::new(&env, &token_addr);
+    assert_eq!(tc.balance(&claimant), 999_250);
+    assert_eq!(tc.balance(&admin), 750);
+}
+
+#[test]
+fn test_resolve_no_action_unfreezes_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let vault = setup_vault(&env, &client, &admin);
+    let (dispute_id, claimant, arbitrator) = assigned_dispute(&env, &client, &admin, &token_addr);
+
+    client.resolve_dispute(&arbitrator, &dispute_id, &DisputeOutcome::NoAction, &String::from_str(&env, "dismissed"));
+    finalize_after_window(&env, &client, dispute_id);
+
+    assert_eq!(vault.released(), Some(42));
+    assert_eq!(vault.settled(), None);
+    assert_eq!(TokenClient::new(&env, &token_addr).balance(&claimant), 1_000_000);
+}
+
+/// Files a dispute against an attester's epoch-3 attestation on campaign 7
+/// and rules on it; returns (oracle, attester)
+fn attestation_ruling(env: &Env, outcome: DisputeOutcome) -> (MockOracleClient<'_>, Address) {
+    let (client, admin, _, token_addr) = setup(env);
+    let oracle_id = env.register_contract(None, MockOracle);
+    client.set_performance_oracle(&admin, &oracle_id);
+
+    let claimant = Address::generate(env);
+    let attester = Address::generate(env);
+    let arbitrator = Address::generate(env);
+    mint(env, &token_addr, &claimant, 1_000_000);
+    let dispute_id = client.file_dispute(&claimant, &attester, &7u64, &50_000i128, &make_desc(env), &make_evidence(env));
+    client.target_attestation(&claimant, &dispute_id, &3u64);
+
+    client.authorize_arbitrator(&admin, &arbitrator);
+    client.assign_arbitrator(&admin, &dispute_id, &arbitrator);
+    close_evidence(env, &client, dispute_id, &attester);
+    client.resolve_dispute(&arbitrator, &dispute_id, &outcome, &String::from_str(env, "ruled"));
+    finalize_after_window(env, &client, dispute_id);
+    (MockOracleClient::new(env, &oracle_id), attester)
+}
+
+#[test]
+fn test_ruling_for_claimant_slashes_targeted_attestation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (oracle, attester) = attestation_ruling(&env, DisputeOutcome::Split(2_500));
+    assert_eq!(oracle.slashed(), Some((7u64, 3u64, attester, 2_500u32)));
+}
+
+#[test]
+fn test_ruling_for_respondent_does_not_slash_attester() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (oracle, _) = attestation_ruling(&env, DisputeOutcome::Respondent);
+    assert_eq!(oracle.slashed(), None);
+}
+
+#[test]
+fn test_no_action_ruling_does_not_slash_attester() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (oracle, _) = attestation_ruling(&env, DisputeOutcome::NoAction);
+    assert_eq!(oracle.slashed(), None);
+}
+
+#[test]
+#[should_panic(expected = "dispute already under review")]
+fn test_target_attestation_after_assignment() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    client.set_performance_oracle(&admin, &env.register_contract(None, MockOracle));
+    let (dispute_id, claimant, _) = assigned_dispute(&env, &client, &admin, &token_addr);
+    client.target_attestation(&claimant, &dispute_id, &3u64);
+}
+
+#[test]
+#[should_panic(expected = "invalid split")]
+fn test_resolve_split_over_100_percent() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let (dispute_id, _, arbitrator) = assigned_dispute(&env, &client, &admin, &token_addr);
+
+    client.resolve_dispute(&arbitrator, &dispute_id, &DisputeOutcome::Split(10_001), &String::from_str(&env, "split"));
+}
+
+#[test]
+#[should_panic(expected = "dispute already resolved")]
+fn test_resolve_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let (dispute_id, _, arbitrator) = assigned_dispute(&env, &client, &admin, &token_addr);
+
+    client.resolve_dispute(&arbitrator, &dispute_id, &DisputeOutcome::Claimant, &String::from_str(&env, "once"));
+    client.resolve_dispute(&arbitrator, &dispute_id, &DisputeOutcome::Claimant, &String::from_str(&env, "twice"));
+}
+
+// ─── evidence ────────────────────────────────────────────────────────────────
+
+/// Dispute with an assigned arbitrator and 100s evidence / rebuttal windows
+fn evidence_setup(env: &Env, client: &DisputeResolutionContractClient, admin: &Address, token_addr: &Address) -> (u64, Address, Address, Address) {
+    let claimant = Address::generate(env);
+    let respondent = Address::generate(env);
+    let arbitrator = Address::generate(env);
+    mint(env, token_addr, &claimant, 1_000_000);
+
+    client.set_evidence_windows(admin, &100u64, &100u64);
+    let dispute_id = client.file_dispute(&claimant, &respondent, &1u64, &50_000i128, &make_desc(env), &make_evidence(env));
+    client.authorize_arbitrator(admin, &arbitrator);
+    client.assign_arbitrator(admin, &dispute_id, &arbitrator);
+    (dispute_id, claimant, respondent, arbitrator)
+}
+
+#[test]
+fn test_evidence_log_is_ordered() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let (dispute_id, claimant, respondent, _) = evidence_setup(&env, &client, &admin, &token_addr);
+
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    let idx = client.submit_evidence(&respondent, &dispute_id, &String::from_str(&env, "QmLogs"), &EvidenceKind::Analytics);
+    assert_eq!(idx, 1);
+    env.ledger().with_mut(|li| li.timestamp = 150);
+    client.submit_evidence(&claimant, &dispute_id, &String::from_str(&env, "QmRebut"), &EvidenceKind::Statement);
+
+    let log = client.get_evidence_log(&dispute_id);
+    assert_eq!(log.len(), 3);
+    assert_eq!(client.get_evidence_count(&dispute_id), 3);
+
+    let filed = log.get(0).unwrap();
+    assert_eq!(filed.party, claimant);
+    assert_eq!(filed.hash, make_evidence(&env));
+    assert!(matches!(filed.phase, EvidencePhase::Submission));
+
+    let response = log.get(1).unwrap();
+    assert_eq!(response.party, respondent);
+    assert_eq!(response.submitted_at, 50);
+    assert!(matches!(response.kind, EvidenceKind::Analytics));
+
+    let rebuttal = client.get_evidence(&dispute_id, &2u32).unwrap();
+    assert_eq!(rebuttal.submitted_at, 150);
+    assert!(matches!(rebuttal.phase, EvidencePhase::Rebuttal));
+}
+
+#[test]
+#[should_panic(expected = "not a party")]
+fn test_submit_evidence_by_stranger() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let (dispute_id, _, _, _) = evidence_setup(&env, &client, &admin, &token_addr);
+
+    client.submit_evidence(&Address::generate(&env), &dispute_id, &make_evidence(&env), &EvidenceKind::Other);
+}
+
+#[test]
+#[should_panic(expected = "party in default")]
+fn test_rebuttal_requires_party_on_record() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let (dispute_id, _, respondent, _) = evidence_setup(&env, &client, &admin, &token_addr);
+
+    env.ledger().with_mut(|li| li.timestamp = 150);
+    client.submit_evidence(&respondent, &dispute_id, &make_evidence(&env), &EvidenceKind::Statement);
+}
+
+#[test]
+#[should_panic(expected = "evidence window closed")]
+fn test_submit_evidence_after_rebuttal_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let (dispute_id, claimant, _, _) = evidence_setup(&env, &client, &admin, &token_addr);
+
+    env.ledger().with_mut(|li| li.timestamp = 201);
+    client.submit_evidence(&claimant, &dispute_id, &make_evidence(&env), &EvidenceKind::Statement);
+}
+
+#[test]
+#[should_panic(expected = "evidence window open")]
+fn test_resolve_before_evidence_closes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let (dispute_id, _, respondent, arbitrator) = evidence_setup(&env, &client, &admin, &token_addr);
+
+    client.submit_evidence(&respondent, &dispute_id, &make_evidence(&env), &EvidenceKind::Statement);
+    client.resolve_dispute(&arbitrator, &dispute_id, &DisputeOutcome::Claimant, &String::from_str(&env, "early"));
+}
+
+#[test]
+#[should_panic(expected = "party in default")]
+fn test_resolve_without_respondent_evidence() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let (dispute_id, _, _, arbitrator) = evidence_setup(&env, &client, &admin, &token_addr);
+
+    env.ledger().with_mut(|li| li.timestamp = 201);
+    client.resolve_dispute(&arbitrator, &dispute_id, &DisputeOutcome::Respondent, &String::from_str(&env, "no reply"));
+}
+
+#[test]
+fn test_default_judgment_against_silent_respondent() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let (dispute_id, _, _, _) = evidence_setup(&env, &client, &admin, &token_addr);
+
+    env.ledger().with_mut(|li| li.timestamp = 101);
+    let outcome = client.default_judgment(&dispute_id);
+
+    assert!(matches!(outcome, DisputeOutcome::Claimant));
+    let dispute = client.get_dispute(&dispute_id).unwrap();
+    assert!(matches!(dispute.status, DisputeStatus::Resolved));
+    assert_eq!(dispute.resolved_at, Some(101));
+}
+
+#[test]
+#[should_panic(expected = "no party in default")]
+fn test_default_judgment_when_both_on_record() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let (dispute_id, _, respondent, _) = evidence_setup(&env, &client, &admin, &token_addr);
+
+    client.submit_evidence(&respondent, &dispute_id, &make_evidence(&env), &EvidenceKind::Document);
+    env.ledger().with_mut(|li| li.timestamp = 101);
+    client.default_judgment(&dispute_id);
+}
+
+#[test]
+#[should_panic(expected = "evidence window open")]
+fn test_default_judgment_before_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let (dispute_id, _, _, _) = evidence_setup(&env, &client, &admin, &token_addr);
+
+    client.default_judgment(&dispute_id);
+}
+
+// ─── appeals ─────────────────────────────────────────────────────────────────
+
+struct AppealFixture {
+    dispute_id: u64,
+    claimant: Address,
+    respondent: Address,
+    panelists: [Address; 3],
+}
+
+/// Resolved dispute (respondent wins) with three staked arbitrators beside
+/// the original one, so any appeal panel is exactly `panelists`.
+fn appeal_setup(env: &Env, client: &DisputeResolutionContractClient, admin: &Address, token_addr: &Address) -> AppealFixture {
+    client.set_appeal_config(admin, &AppealConfig {
+        appeal_window: 1_000,
+        appeal_bond: 5_000,
+        panel_size: 3,
+        voting_period: 2_000,
+        min_stake: 10_000,
+        slash_bps: 1_000,
+    });
+
+    let claimant = Address::generate(env);
+    let respondent = Address::generate(env);
+    let arbitrator = Address::generate(env);
+    mint(env, token_addr, &claimant, 1_000_000);
+    mint(env, token_addr, &respondent, 1_000_000);
+
+    let panelists = [Address::generate(env), Address::generate(env), Address::generate(env)];
+    for arb in panelists.iter().chain([&arbitrator]) {
+        client.authorize_arbitrator(admin, arb);
+        mint(env, token_addr, arb, 10_000);
+        client.stake_arbitrator(arb, &10_000i128);
+    }
+
+    let dispute_id = client.file_dispute(&claimant, &respondent, &1u64, &50_000i128, &make_desc(env), &make_evidence(env));
+    client.assign_arbitrator(admin, &dispute_id, &arbitrator);
+    close_evidence(env, client, dispute_id, &respondent);
+    client.resolve_dispute(&arbitrator, &dispute_id, &DisputeOutcome::Respondent, &String::from_str(env, "denied"));
+
+    AppealFixture { dispute_id, claimant, respondent, panelists }
+}
+
+const PANEL_SEED: [u8; 32] = [7; 32];
+
+fn seed_hash(env: &Env) -> BytesN<32> {
+    env.crypto().sha256(&Bytes::from_array(env, &PANEL_SEED)).into()
+}
+
+/// Appeal, have the other party add its seed and reveal, leaving a drawn panel.
+fn appeal_and_draw(env: &Env, client: &DisputeResolutionContractClient, f: &AppealFixture, appellant: &Address) {
+    client.appeal_dispute(appellant, &f.dispute_id, &seed_hash(env));
+    let opponent = if appellant == &f.claimant { &f.respondent } else { &f.claimant };
+    client.contribute_seed(opponent, &f.dispute_id, &BytesN::from_array(env, &[9; 32]));
+    client.draw_panel(appellant, &f.dispute_id, &BytesN::from_array(env, &PANEL_SEED));
+}
+
+#[test]
+#[should_panic(expected = "appeal window open")]
+fn test_finalize_before_appeal_window_closes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+
+    client.finalize_dispute(&f.dispute_id);
+}
+
+#[test]
+fn test_appeal_draws_panel_and_holds_bond() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+
+    client.appeal_dispute(&f.claimant, &f.dispute_id, &seed_hash(&env));
+
+    let dispute = client.get_dispute(&f.dispute_id).unwrap();
+    assert!(matches!(dispute.status, DisputeStatus::Appealed));
+    assert!(client.get_appeal(&f.dispute_id).unwrap().panel.is_empty());
+
+    client.contribute_seed(&f.respondent, &f.dispute_id, &BytesN::from_array(&env, &[9; 32]));
+    client.draw_panel(&f.claimant, &f.dispute_id, &BytesN::from_array(&env, &PANEL_SEED));
+    let appeal = client.get_appeal(&f.dispute_id).unwrap();
+    assert_eq!(appeal.panel.len(), 3);
+    for p in f.panelists.iter() {
+        assert!(appeal.panel.contains(p));
+    }
+    assert_eq!(appeal.bond, 5_000);
+    assert_eq!(TokenClient::new(&env, &token_addr).balance(&f.claimant), 994_000);
+}
+
+#[test]
+#[should_panic(expected = "not a party")]
+fn test_appeal_by_stranger() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+
+    client.appeal_dispute(&Address::generate(&env), &f.dispute_id, &seed_hash(&env));
+}
+
+#[test]
+#[should_panic(expected = "appeal window closed")]
+fn test_appeal_after_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+
+    let deadline = client.get_dispute(&f.dispute_id).unwrap().appeal_deadline;
+    env.ledger().with_mut(|li| li.timestamp = deadline + 1);
+    client.appeal_dispute(&f.respondent, &f.dispute_id, &seed_hash(&env));
+}
+
+#[test]
+#[should_panic(expected = "insufficient arbitrators")]
+fn test_appeal_needs_full_panel() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+
+    client.unstake_arbitrator(&f.panelists[0], &1i128);
+    client.appeal_dispute(&f.claimant, &f.dispute_id, &seed_hash(&env));
+}
+
+#[test]
+fn test_panel_majority_overturns_and_slashes_minority() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let vault = setup_vault(&env, &client, &admin);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+    appeal_and_draw(&env, &client, &f, &f.claimant);
+
+    let [a, b, c] = &f.panelists;
+    client.cast_panel_vote(a, &f.dispute_id, &DisputeOutcome::Claimant);
+    assert!(matches!(client.get_dispute(&f.dispute_id).unwrap().status, DisputeStatus::Deliberating));
+    client.cast_panel_vote(b, &f.dispute_id, &DisputeOutcome::Respondent);
+    client.cast_panel_vote(c, &f.dispute_id, &DisputeOutcome::Claimant);
+
+    let dispute = client.get_dispute(&f.dispute_id).unwrap();
+    assert!(matches!(dispute.status, DisputeStatus::Closed));
+    assert!(matches!(dispute.outcome, DisputeOutcome::Claimant));
+    assert_eq!(vault.settled(), Some((42, f.claimant.clone(), 50_000)));
+
+    assert_eq!(client.get_arbitrator_stake(a), 10_000);
+    assert_eq!(client.get_arbitrator_stake(b), 9_000);
+    assert_eq!(client.get_arbitrator_stake(c), 10_000);
+
+    // bond and filing fee both come back to the successful appellant
+    assert_eq!(TokenClient::new(&env, &token_addr).balance(&f.claimant), 1_000_000);
+    // only the minority slash is forfeited
+    assert_eq!(TokenClient::new(&env, &token_addr).balance(&admin), 1_000);
+}
+
+#[test]
+fn test_panel_upholding_ruling_forfeits_bond() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+    appeal_and_draw(&env, &client, &f, &f.claimant);
+
+    for p in f.panelists.iter() {
+        client.cast_panel_vote(p, &f.dispute_id, &DisputeOutcome::Respondent);
+    }
+
+    let dispute = client.get_dispute(&f.dispute_id).unwrap();
+    assert!(matches!(dispute.outcome, DisputeOutcome::Respondent));
+    let tc = TokenClient::new(&env, &token_addr);
+    assert_eq!(tc.balance(&f.claimant), 994_000);
+    assert_eq!(tc.balance(&admin), 6_000);
+}
+
+#[test]
+fn test_finalize_appeal_slashes_absent_panelist() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+    appeal_and_draw(&env, &client, &f, &f.respondent);
+
+    let [a, b, c] = &f.panelists;
+    client.cast_panel_vote(a, &f.dispute_id, &DisputeOutcome::Split(5_000));
+    client.cast_panel_vote(b, &f.dispute_id, &DisputeOutcome::Split(5_000));
+
+    let deadline = client.get_appeal(&f.dispute_id).unwrap().voting_deadline;
+    env.ledger().with_mut(|li| li.timestamp = deadline + 1);
+    client.finalize_dispute(&f.dispute_id);
+
+    let dispute = client.get_dispute(&f.dispute_id).unwrap();
+    assert!(matches!(dispute.outcome, DisputeOutcome::Split(5_000)));
+    assert_eq!(client.get_arbitrator_stake(c), 9_000);
+    // panel seats are released, so stake can be withdrawn again
+    client.unstake_arbitrator(a, &10_000i128);
+}
+
+#[test]
+fn test_finalize_appeal_without_majority_keeps_ruling() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+    appeal_and_draw(&env, &client, &f, &f.claimant);
+
+    client.cast_panel_vote(&f.panelists[0], &f.dispute_id, &DisputeOutcome::Claimant);
+    let deadline = client.get_appeal(&f.dispute_id).unwrap().voting_deadline;
+    env.ledger().with_mut(|li| li.timestamp = deadline + 1);
+    client.finalize_dispute(&f.dispute_id);
+
+    let dispute = client.get_dispute(&f.dispute_id).unwrap();
+    assert!(matches!(dispute.outcome, DisputeOutcome::Respondent));
+    for p in f.panelists.iter() {
+        assert_eq!(client.get_arbitrator_stake(p), 10_000);
+    }
+}
+
+#[test]
+#[should_panic(expected = "seed window open")]
+fn test_draw_panel_before_seed_window_closes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+    client.appeal_dispute(&f.claimant, &f.dispute_id, &seed_hash(&env));
+
+    client.draw_panel(&f.claimant, &f.dispute_id, &BytesN::from_array(&env, &PANEL_SEED));
+}
+
+#[test]
+#[should_panic(expected = "seed mismatch")]
+fn test_draw_panel_with_wrong_seed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+    client.appeal_dispute(&f.claimant, &f.dispute_id, &seed_hash(&env));
+    client.contribute_seed(&f.respondent, &f.dispute_id, &BytesN::from_array(&env, &[9; 32]));
+
+    client.draw_panel(&f.claimant, &f.dispute_id, &BytesN::from_array(&env, &[8; 32]));
+}
+
+#[test]
+#[should_panic(expected = "not the opposing party")]
+fn test_appellant_cannot_contribute_counter_seed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+    client.appeal_dispute(&f.claimant, &f.dispute_id, &seed_hash(&env));
+
+    client.contribute_seed(&f.claimant, &f.dispute_id, &BytesN::from_array(&env, &[9; 32]));
+}
+
+#[test]
+fn test_draw_panel_after_seed_window_without_counter_seed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+    client.appeal_dispute(&f.claimant, &f.dispute_id, &seed_hash(&env));
+
+    let seed_deadline = client.get_appeal(&f.dispute_id).unwrap().seed_deadline;
+    env.ledger().with_mut(|li| li.timestamp = seed_deadline + 1);
+    client.draw_panel(&f.claimant, &f.dispute_id, &BytesN::from_array(&env, &PANEL_SEED));
+
+    let appeal = client.get_appeal(&f.dispute_id).unwrap();
+    assert_eq!(appeal.panel.len(), 3);
+    assert_eq!(appeal.voting_deadline, seed_deadline + 1 + 2_000);
+}
+
+#[test]
+#[should_panic(expected = "panel not drawn")]
+fn test_finalize_before_reveal_window_closes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+    client.appeal_dispute(&f.claimant, &f.dispute_id, &seed_hash(&env));
+
+    let seed_deadline = client.get_appeal(&f.dispute_id).unwrap().seed_deadline;
+    env.ledger().with_mut(|li| li.timestamp = seed_deadline + 1);
+    client.finalize_dispute(&f.dispute_id);
+}
+
+#[test]
+fn test_unrevealed_appeal_lapses_and_forfeits_bond() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+    client.appeal_dispute(&f.claimant, &f.dispute_id, &seed_hash(&env));
+    client.contribute_seed(&f.respondent, &f.dispute_id, &BytesN::from_array(&env, &[9; 32]));
+
+    let seed_deadline = client.get_appeal(&f.dispute_id).unwrap().seed_deadline;
+    env.ledger().with_mut(|li| li.timestamp = seed_deadline + 86_401);
+    client.finalize_dispute(&f.dispute_id);
+
+    let dispute = client.get_dispute(&f.dispute_id).unwrap();
+    assert!(matches!(dispute.status, DisputeStatus::Closed));
+    assert!(matches!(dispute.outcome, DisputeOutcome::Respondent));
+    let tc = TokenClient::new(&env, &token_addr);
+    assert_eq!(tc.balance(&f.claimant), 994_000);
+    assert_eq!(tc.balance(&admin), 6_000);
+}
+
+#[test]
+#[should_panic(expected = "reveal window closed")]
+fn test_draw_panel_after_reveal_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+    client.appeal_dispute(&f.claimant, &f.dispute_id, &seed_hash(&env));
+
+    let seed_deadline = client.get_appeal(&f.dispute_id).unwrap().seed_deadline;
+    env.ledger().with_mut(|li| li.timestamp = seed_deadline + 86_401);
+    client.draw_panel(&f.claimant, &f.dispute_id, &BytesN::from_array(&env, &PANEL_SEED));
+}
+
+#[test]
+#[should_panic(expected = "stake locked by open panel")]
+fn test_unstake_while_on_panel() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+    appeal_and_draw(&env, &client, &f, &f.claimant);
+
+    client.unstake_arbitrator(&f.panelists[0], &10_000i128);
+}
+
+#[test]
+#[should_panic(expected = "already voted")]
+fn test_panel_vote_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+    appeal_and_draw(&env, &client, &f, &f.claimant);
+
+    client.cast_panel_vote(&f.panelists[0], &f.dispute_id, &DisputeOutcome::Claimant);
+    client.cast_panel_vote(&f.panelists[0], &f.dispute_id, &DisputeOutcome::Claimant);
+}
+
+#[test]
+#[should_panic(expected = "not on panel")]
+fn test_panel_vote_by_outsider() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr) = setup(&env);
+    let f = appeal_setup(&env, &client, &admin, &token_addr);
+    appeal_and_draw(&env, &client, &f, &f.claimant);
+
+    client.cast_panel_vote(&Address::generate(&env), &f.dispute_id, &DisputeOutcome::Claimant);
+}
+
 // ─── read-only ───────────────────────────────────────────────────────────────
 
 #[test]
@@ -1,11 +1,35 @@
 #![cfg(test)]
 use super::*;
 use soroban_sdk::{
+    contract as soroban_contract, contractimpl as soroban_contractimpl,
     testutils::{Address as _, Ledger},
     token::{Client as TokenClient, StellarAssetClient},
     Address, Env, vec,
 };
 
+#[soroban_contract]
+pub struct MockSettlement;
+
+#[soroban_contractimpl]
+impl MockSettlement {
+    pub fn init(env: Env, token: Address) {
+        env.storage().instance().set(&symbol_short!("token"), &token);
+    }
+
+    /// Pulls the revenue from the source, like the real settlement contract
+    pub fn record_revenue(env: Env, source: Address, campaign_id: u64, amount: i128, publisher: Address) -> u64 {
+        source.require_auth();
+        let token: Address = env.storage().instance().get(&symbol_short!("token")).unwrap();
+        TokenClient::new(&env, &token).transfer(&source, &env.current_contract_address(), &amount);
+        env.storage().instance().set(&symbol_short!("last"), &(campaign_id, amount, publisher));
+        1
+    }
+
+    pub fn last(env: Env) -> Option<(u64, i128, Address)> {
+        env.storage().instance().get(&symbol_short!("last"))
+    }
+}
+
 // ─── helpers ────────────────────────────────────────────────────────────────
 
 fn deploy_token(env: &Env, admin: &Address) -> Address {
@@ -600,8 +624,9 @@ fn test_hold_for_fraud() {
 
     client.hold_for_fraud(&fraud_contract, &escrow_id);
 
+    assert!(client.is_held_for_fraud(&escrow_id));
     let escrow = client.get_escrow(&escrow_id).unwrap();
-    assert!(matches!(escrow.state, EscrowState::Disputed));
+    assert!(matches!(escrow.state, EscrowState::Locked));
 }
 
 #[test]
@@ -636,6 +661,326 @@ fn test_release_disputed_escrow_fails() {
     client.release_escrow(&depositor, &escrow_id); // should panic
 }
 
+#[test]
+fn test_release_fraud_hold_restores_state() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token_addr, _) = setup(&env);
+    let fraud_contract = Address::generate(&env);
+    client.set_fraud_contract(&admin, &fraud_contract);
+
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &admin, &token_addr, &depositor, 1_000_000);
+
+    let escrow_id = client.create_escrow(
+        &depositor, &1u64, &beneficiary, &100_000i128,
+        &0u64, &0u32, &999_999u64,
+        &vec![&env],
+    );
+
+    client.hold_for_fraud(&fraud_contract, &escrow_id);
+    assert!(client.is_held_for_fraud(&escrow_id));
+    assert_eq!(client.get_open_escrows(&beneficiary).len(), 0);
+
+    client.release_fraud_hold(&fraud_contract, &escrow_id);
+    assert!(!client.is_held_for_fraud(&escrow_id));
+    let escrow = client.get_escrow(&escrow_id).unwrap();
+    assert!(matches!(escrow.state, EscrowState::Locked));
+}
+
+#[test]
+fn test_get_open_escrows_by_beneficiary() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token_addr, _) = setup(&env);
+
+    let depositor = Address::generate(&env);
+    let publisher = Address::generate(&env);
+    let other = Address::generate(&env);
+    mint(&env, &admin, &token_addr, &depositor, 1_000_000);
+
+    let first = client.create_escrow(
+        &depositor, &1u64, &publisher, &100_000i128,
+        &0u64, &0u32, &999_999u64,
+        &vec![&env],
+    );
+    client.create_escrow(
+        &depositor, &2u64, &other, &100_000i128,
+        &0u64, &0u32, &999_999u64,
+        &vec![&env],
+    );
+    let third = client.create_escrow(
+        &depositor, &3u64, &publisher, &100_000i128,
+        &0u64, &0u32, &999_999u64,
+        &vec![&env],
+    );
+
+    assert_eq!(client.get_open_escrows(&publisher), vec![&env, first, third]);
+}
+
+#[test]
+#[should_panic(expected = "escrow not held")]
+fn test_release_fraud_hold_without_hold_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token_addr, _) = setup(&env);
+    let fraud_contract = Address::generate(&env);
+    client.set_fraud_contract(&admin, &fraud_contract);
+
+    let depositor = Address::generate(&env);
+    mint(&env, &admin, &token_addr, &depositor, 1_000_000);
+    let escrow_id = client.create_escrow(
+        &depositor, &1u64, &Address::generate(&env), &100_000i128,
+        &0u64, &0u32, &999_999u64,
+        &vec![&env],
+    );
+
+    client.release_fraud_hold(&fraud_contract, &escrow_id);
+}
+
+#[test]
+fn test_release_routes_through_revenue_settlement() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token_addr, _) = setup(&env);
+    let settlement = env.register_contract(None, MockSettlement);
+    MockSettlementClient::new(&env, &settlement).init(&token_addr);
+    client.set_revenue_settlement(&admin, &settlement);
+
+    let depositor = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let approver = Address::generate(&env);
+    mint(&env, &admin, &token_addr, &depositor, 1_000_000);
+    let escrow_id = client.create_escrow(
+        &depositor, &5u64, &beneficiary, &100_000i128,
+        &0u64, &0u32, &999_999u64,
+        &vec![&env, approver.clone()],
+    );
+    client.approve_release(&approver, &escrow_id);
+
+    client.release_partial(&depositor, &escrow_id, &40_000i128);
+    assert_eq!(MockSettlementClient::new(&env, &settlement).last(), Some((5, 40_000, beneficiary.clone())));
+    client.release_escrow(&depositor, &escrow_id);
+
+    let tc = TokenClient::new(&env, &token_addr);
+    assert_eq!(tc.balance(&beneficiary), 0);
+    assert_eq!(tc.balance(&settlement), 100_000);
+    assert_eq!(MockSettlementClient::new(&env, &settlement).last(), Some((5, 60_000, beneficiary)));
+}
+
+// ─── dispute freeze ──────────────────────────────────────────────────────────
+
+fn dispute_setup(env: &Env) -> (EscrowVaultContractClient<'_>, Address, Address, Address, Address, u64) {
+    let (client, admin, token_addr, _) = setup(env);
+    let dispute_contract = Address::generate(env);
+    client.set_dispute_contract(&admin, &dispute_contract);
+
+    let depositor = Address::generate(env);
+    let publisher = Address::generate(env);
+    mint(env, &admin, &token_addr, &depositor, 1_000_000);
+    let escrow_id = client.create_escrow(
+        &depositor, &7u64, &publisher, &100_000i128,
+        &0u64, &0u32, &100u64,
+        &vec![env],
+    );
+    (client, token_addr, dispute_contract, depositor, publisher, escrow_id)
+}
+
+#[test]
+fn test_freeze_for_dispute_matches_campaign_and_parties() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, dispute_contract, depositor, publisher, escrow_id) = dispute_setup(&env);
+
+    let stranger = Address::generate(&env);
+    assert_eq!(client.freeze_for_dispute(&dispute_contract, &7u64, &stranger, &publisher, &100_000i128), None);
+    assert_eq!(client.freeze_for_dispute(&dispute_contract, &8u64, &publisher, &depositor, &100_000i128), None);
+
+    let frozen = client.freeze_for_dispute(&dispute_contract, &7u64, &publisher, &depositor, &100_000i128);
+    assert_eq!(frozen, Some(escrow_id));
+    assert_eq!(client.get_disputed_amount(&escrow_id), Some(100_000));
+    assert_eq!(client.get_open_escrows(&publisher).len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "escrow is disputed")]
+fn test_refund_blocked_while_disputed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, dispute_contract, depositor, publisher, escrow_id) = dispute_setup(&env);
+    client.freeze_for_dispute(&dispute_contract, &7u64, &depositor, &publisher, &100_000i128);
+
+    env.ledger().with_mut(|li| li.timestamp = 200);
+    client.refund_escrow(&depositor, &escrow_id);
+}
+
+#[test]
+#[should_panic(expected = "unauthorized dispute contract")]
+fn test_freeze_for_dispute_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, depositor, publisher, _) = dispute_setup(&env);
+    client.freeze_for_dispute(&Address::generate(&env), &7u64, &depositor, &publisher, &100_000i128);
+}
+
+#[test]
+fn test_settle_dispute_splits_locked_funds() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, dispute_contract, depositor, publisher, escrow_id) = dispute_setup(&env);
+    client.freeze_for_dispute(&dispute_contract, &7u64, &publisher, &depositor, &100_000i128);
+
+    client.settle_dispute(&dispute_contract, &escrow_id, &publisher, &30_000i128);
+
+    let tc = TokenClient::new(&env, &token_addr);
+    assert_eq!(tc.balance(&publisher), 30_000);
+    assert_eq!(tc.balance(&depositor), 970_000);
+    let escrow = client.get_escrow(&escrow_id).unwrap();
+    assert_eq!(escrow.locked_amount, 0);
+    assert_eq!(escrow.released_amount, 30_000);
+    assert_eq!(escrow.refunded_amount, 70_000);
+    assert!(matches!(escrow.state, EscrowState::Released));
+}
+
+#[test]
+fn test_settle_dispute_caps_at_locked_amount() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, dispute_contract, depositor, publisher, escrow_id) = dispute_setup(&env);
+    client.freeze_for_dispute(&dispute_contract, &7u64, &depositor, &publisher, &100_000i128);
+
+    client.settle_dispute(&dispute_contract, &escrow_id, &depositor, &500_000i128);
+
+    let tc = TokenClient::new(&env, &token_addr);
+    assert_eq!(tc.balance(&depositor), 1_000_000);
+    assert_eq!(tc.balance(&publisher), 0);
+    assert!(matches!(client.get_escrow(&escrow_id).unwrap().state, EscrowState::Refunded));
+}
+
+#[test]
+fn test_settle_dispute_leaves_undisputed_funds_locked() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_addr, dispute_contract, depositor, publisher, escrow_id) = dispute_setup(&env);
+    client.freeze_for_dispute(&dispute_contract, &7u64, &depositor, &publisher, &40_000i128);
+
+    client.settle_dispute(&dispute_contract, &escrow_id, &depositor, &25_000i128);
+
+    let tc = TokenClient::new(&env, &token_addr);
+    assert_eq!(tc.balance(&depositor), 925_000);
+    assert_eq!(tc.balance(&publisher), 15_000);
+    let escrow = client.get_escrow(&escrow_id).unwrap();
+    assert_eq!(escrow.locked_amount, 60_000);
+    assert_eq!(escrow.refunded_amount, 25_000);
+    assert_eq!(escrow.released_amount, 15_000);
+    assert!(matches!(escrow.state, EscrowState::PartiallyReleased));
+    assert_eq!(tc.balance(&client.address), 60_000);
+}
+
+#[test]
+fn test_settle_dispute_routes_award_through_revenue_settlement() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token_addr, _) = setup(&env);
+    let dispute_contract = Address::generate(&env);
+    client.set_dispute_contract(&admin, &dispute_contract);
+    let settlement = env.register_contract(None, MockSettlement);
+    MockSettlementClient::new(&env, &settlement).init(&token_addr);
+    client.set_revenue_settlement(&admin, &settlement);
+
+    let depositor = Address::generate(&env);
+    let publisher = Address::generate(&env);
+    mint(&env, &admin, &token_addr, &depositor, 1_000_000);
+    let escrow_id = client.create_escrow(
+        &depositor, &7u64, &publisher, &100_000i128,
+        &0u64, &0u32, &100u64,
+        &vec![&env],
+    );
+    client.freeze_for_dispute(&dispute_contract, &7u64, &publisher, &depositor, &100_000i128);
+    client.settle_dispute(&dispute_contract, &escrow_id, &publisher, &30_000i128);
+
+    let tc = TokenClient::new(&env, &token_addr);
+    assert_eq!(tc.balance(&publisher), 0);
+    assert_eq!(tc.balance(&settlement), 30_000);
+    assert_eq!(MockSettlementClient::new(&env, &settlement).last(), Some((7, 30_000, publisher)));
+}
+
+#[test]
+#[should_panic(expected = "escrow not frozen for dispute")]
+fn test_settle_dispute_requires_freeze() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, dispute_contract, _, publisher, escrow_id) = dispute_setup(&env);
+    client.settle_dispute(&dispute_contract, &escrow_id, &publisher, &1i128);
+}
+
+#[test]
+fn test_release_dispute_hold_restores_state() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, dispute_contract, depositor, publisher, escrow_id) = dispute_setup(&env);
+    client.freeze_for_dispute(&dispute_contract, &7u64, &depositor, &publisher, &100_000i128);
+
+    client.release_dispute_hold(&dispute_contract, &escrow_id);
+    let escrow = client.get_escrow(&escrow_id).unwrap();
+    assert!(matches!(escrow.state, EscrowState::Locked));
+    assert_eq!(escrow.locked_amount, 100_000);
+}
+
+/// `dispute_setup` plus a registered fraud contract
+fn held_dispute_setup(env: &Env) -> (EscrowVaultContractClient<'_>, Address, Address, Address, Address, u64) {
+    let (client, admin, token_addr, _) = setup(env);
+    let dispute_contract = Address::generate(env);
+    let fraud_contract = Address::generate(env);
+    client.set_dispute_contract(&admin, &dispute_contract);
+    client.set_fraud_contract(&admin, &fraud_contract);
+
+    let depositor = Address::generate(env);
+    let publisher = Address::generate(env);
+    mint(env, &admin, &token_addr, &depositor, 1_000_000);
+    let escrow_id = client.create_escrow(
+        &depositor, &7u64, &publisher, &100_000i128,
+        &0u64, &0u32, &100u64,
+        &vec![env],
+    );
+    (client, dispute_contract, fraud_contract, depositor, publisher, escrow_id)
+}
+
+#[test]
+fn test_dispute_and_fraud_holds_are_independent() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, dispute_contract, fraud_contract, depositor, publisher, escrow_id) = held_dispute_setup(&env);
+
+    client.hold_for_fraud(&fraud_contract, &escrow_id);
+    let frozen = client.freeze_for_dispute(&dispute_contract, &7u64, &depositor, &publisher, &100_000i128);
+    assert_eq!(frozen, Some(escrow_id));
+
+    // The dispute ending without action must not lift the fraud hold
+    client.release_dispute_hold(&dispute_contract, &escrow_id);
+    assert!(client.is_held_for_fraud(&escrow_id));
+    assert_eq!(client.get_open_escrows(&publisher).len(), 0);
+
+    client.release_fraud_hold(&fraud_contract, &escrow_id);
+    assert_eq!(client.get_open_escrows(&publisher).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "escrow is disputed")]
+fn test_fraud_release_keeps_dispute_freeze() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, dispute_contract, fraud_contract, depositor, publisher, escrow_id) = held_dispute_setup(&env);
+
+    client.freeze_for_dispute(&dispute_contract, &7u64, &depositor, &publisher, &100_000i128);
+    client.hold_for_fraud(&fraud_contract, &escrow_id);
+    client.release_fraud_hold(&fraud_contract, &escrow_id);
+
+    env.ledger().with_mut(|li| li.timestamp = 200);
+    client.refund_escrow(&depositor, &escrow_id);
+}
+
 // ─── can_release ─────────────────────────────────────────────────────────────
 
 #[test]
@@ -665,3 +1010,42 @@ fn test_can_release_returns_true_when_conditions_met() {
     client.approve_release(&approver, &escrow_id);
     assert!(client.can_release(&escrow_id)); // now it can
 }
+
+// ─── loan defaults ───────────────────────────────────────────────────────────
+
+#[test]
+fn test_claim_for_default_seizes_borrower_escrows() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token_addr, _) = setup(&env);
+    let pool = Address::generate(&env);
+    client.set_lending_pool(&admin, &pool);
+
+    let borrower = Address::generate(&env);
+    let other = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    mint(&env, &admin, &token_addr, &borrower, 1_000_000);
+    mint(&env, &admin, &token_addr, &other, 1_000_000);
+    let seized_id = client.create_escrow(&borrower, &1u64, &beneficiary, &100_000i128, &0u64, &0u32, &1_000u64, &vec![&env]);
+    let kept_id = client.create_escrow(&other, &1u64, &beneficiary, &50_000i128, &0u64, &0u32, &1_000u64, &vec![&env]);
+
+    assert_eq!(client.claim_for_default(&pool, &1u64, &borrower), 100_000);
+    assert_eq!(TokenClient::new(&env, &token_addr).balance(&pool), 100_000);
+    let seized = client.get_escrow(&seized_id).unwrap();
+    assert!(seized.state == EscrowState::Refunded);
+    assert_eq!(seized.locked_amount, 0);
+    assert_eq!(client.get_escrow(&kept_id).unwrap().locked_amount, 50_000);
+
+    // Nothing left on a second claim
+    assert_eq!(client.claim_for_default(&pool, &1u64, &borrower), 0);
+}
+
+#[test]
+#[should_panic(expected = "unauthorized lending pool")]
+fn test_claim_for_default_wrong_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    client.set_lending_pool(&admin, &Address::generate(&env));
+    client.claim_for_default(&Address::generate(&env), &1u64, &Address::generate(&env));
+}
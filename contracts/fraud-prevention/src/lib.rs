@@ -1,11 +1,23 @@
 //! PulsarTrack - Fraud Prevention (Soroban)
 //! Advanced fraud prevention and view verification for ad campaigns on Stellar.
+//!
+//! Views are deduplicated per (campaign, publisher, viewer, time bucket) and
+//! scored by a pipeline of signals: viewer rate history, the publisher's
+//! flag history, anomaly-detector flags and an optional ed25519 attestation
+//! over the view ID. Each signal's weight is set in `ScoringConfig`; a
+//! weight of 0 switches the signal off.
+//!
+//! Only authorized reporters may flag publishers. Crossing the suspicious
+//! threshold suspends the node in publisher-network and holds the
+//! publisher's open escrows in escrow-vault; `clear_flag` reverses both.
+//! Reporters can also dispute receipts of campaign-orchestrator view batches
+//! while their challenge window is open.
 
 
 #![no_std]
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short,
-    Address, Bytes, BytesN, Env, String,
+    xdr::ToXdr, Address, Bytes, BytesN, Env, Vec,
 };
 
 // ============================================================
@@ -32,6 +44,22 @@ pub struct SuspiciousActivity {
     pub suspended: bool,
 }
 
+/// Weights of the view scoring pipeline. Scores start at `base_score`,
+/// lose points for each negative signal and are capped at 100.
+#[contracttype]
+#[derive(Clone)]
+pub struct ScoringConfig {
+    pub base_score: u32,
+    /// Added when `proof_data` is a valid attestation signature
+    pub attestation_bonus: u32,
+    /// Deducted per view the viewer already made in the current period
+    pub viewer_rate_penalty: u32,
+    /// Deducted per suspicious view on the publisher's record
+    pub publisher_flag_penalty: u32,
+    /// Deducted when anomaly-detector has flagged the publisher
+    pub anomaly_penalty: u32,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct VerificationCache {
@@ -41,6 +69,27 @@ pub struct VerificationCache {
     pub average_score: u32,
 }
 
+/// Mirrors campaign-orchestrator's signed view receipt
+#[contracttype]
+#[derive(Clone)]
+pub struct ViewReceipt {
+    pub campaign_id: u64,
+    pub publisher: Address,
+    pub viewer_key: BytesN<32>,
+    pub nonce: u64,
+    pub timestamp: u64,
+    pub signature: BytesN<64>,
+}
+
+/// Mirrors campaign-orchestrator's receipt inclusion proof
+#[contracttype]
+#[derive(Clone)]
+pub struct ViewProof {
+    pub batch_id: u64,
+    pub receipt: ViewReceipt,
+    pub proof: Vec<BytesN<32>>,
+}
+
 // ============================================================
 // Storage Keys
 // ============================================================
@@ -51,11 +100,18 @@ pub enum DataKey {
     CampaignLifecycle,
     PublisherNetwork,
     EscrowVault,
+    CampaignOrchestrator,
     VerificationThreshold,
     MaxViewsPerPeriod,
     SuspiciousThreshold,
+    AnomalyDetector,
+    AttestationSigner,
+    ScoringConfig,
+    DedupWindow,
     VerifyCounter,
     ViewRecord(BytesN<32>),
+    Reporter(Address),
+    HeldEscrows(Address),
     ViewerRateLimit(Address, u64),
     SuspiciousActivity(Address),
     VerificationCache(u64, u64),
@@ -70,6 +126,9 @@ const INSTANCE_BUMP_AMOUNT: u32 = 86_400;
 const PERSISTENT_LIFETIME_THRESHOLD: u32 = 120_960;
 const PERSISTENT_BUMP_AMOUNT: u32 = 1_051_200;
 
+const RATE_PERIOD_SECS: u64 = 3_600;
+const DEFAULT_DEDUP_WINDOW_SECS: u64 = 86_400;
+
 #[contract]
 pub struct FraudPreventionContract;
 
@@ -95,6 +154,12 @@ impl FraudPreventionContract {
         env.storage()
             .instance()
             .set(&DataKey::VerifyCounter, &0u64);
+        env.storage()
+            .instance()
+            .set(&DataKey::ScoringConfig, &Self::_default_scoring());
+        env.storage()
+            .instance()
+            .set(&DataKey::DedupWindow, &DEFAULT_DEDUP_WINDOW_SECS);
     }
 
     pub fn set_dependent_contracts(
@@ -115,17 +180,81 @@ impl FraudPreventionContract {
         env.storage().instance().set(&DataKey::EscrowVault, &vault);
     }
 
-    /// Verify an ad view
+    /// Admin: allow or revoke an address (anomaly-detector, oracles) to flag publishers
+    pub fn set_reporter(env: Env, admin: Address, reporter: Address, allowed: bool) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        let _ttl_key = DataKey::Reporter(reporter);
+        if allowed {
+            env.storage().persistent().set(&_ttl_key, &true);
+            env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        } else {
+            env.storage().persistent().remove(&_ttl_key);
+        }
+    }
+
+    /// Admin: set the campaign-orchestrator whose view batches can be disputed
+    pub fn set_campaign_orchestrator(env: Env, admin: Address, orchestrator: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::CampaignOrchestrator, &orchestrator);
+    }
+
+    /// Admin: set how many suspicious views suspend a publisher
+    pub fn set_suspicious_threshold(env: Env, admin: Address, threshold: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::SuspiciousThreshold, &threshold);
+    }
+
+    /// Admin: set the anomaly-detector consulted while scoring views
+    pub fn set_anomaly_detector(env: Env, admin: Address, detector: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::AnomalyDetector, &detector);
+    }
+
+    /// Admin: set the ed25519 key whose signatures over a view ID are
+    /// accepted as `proof_data`
+    pub fn set_attestation_signer(env: Env, admin: Address, signer: BytesN<32>) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::AttestationSigner, &signer);
+    }
+
+    /// Admin: replace the scoring weights
+    pub fn set_scoring_config(env: Env, admin: Address, config: ScoringConfig) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        if config.base_score > 100 {
+            panic!("invalid base score");
+        }
+        env.storage().instance().set(&DataKey::ScoringConfig, &config);
+    }
+
+    /// Admin: set how long a viewer's view of a campaign on one publisher
+    /// counts as a duplicate
+    pub fn set_dedup_window(env: Env, admin: Address, window_secs: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        if window_secs == 0 {
+            panic!("invalid window");
+        }
+        env.storage().instance().set(&DataKey::DedupWindow, &window_secs);
+    }
+
+    /// Verify an ad view. `proof_data` is an optional ed25519 signature by
+    /// the attestation signer over the view ID (see `get_view_id`).
     pub fn verify_view(
         env: Env,
         campaign_id: u64,
         publisher: Address,
         viewer: Address,
-        proof_data: Option<BytesN<32>>,
+        proof_data: Option<BytesN<64>>,
     ) -> bool {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         // Rate limiting: check viewer hasn't exceeded limit in current period
-        let current_period = env.ledger().timestamp() / 3600; // hourly buckets
+        let current_period = env.ledger().timestamp() / RATE_PERIOD_SECS; // hourly buckets
         let rate_key = DataKey::ViewerRateLimit(viewer.clone(), current_period);
         let view_count: u64 = env
             .storage()
@@ -143,7 +272,7 @@ impl FraudPreventionContract {
             panic!("rate limit exceeded");
         }
 
-        // Generate view ID from campaign + publisher + viewer + timestamp
+        // Generate view ID from campaign + publisher + viewer + time bucket
         let view_id = Self::_generate_view_id(&env, campaign_id, &publisher, &viewer);
 
         // Check for duplicate view
@@ -156,7 +285,14 @@ impl FraudPreventionContract {
         }
 
         // Calculate verification score
-        let score = Self::_calculate_score(&env, campaign_id, &publisher, &proof_data);
+        let score = Self::_calculate_score(
+            &env,
+            campaign_id,
+            &publisher,
+            view_count,
+            &view_id,
+            &proof_data,
+        );
         let threshold: u32 = env
             .storage()
             .instance()
@@ -234,9 +370,11 @@ impl FraudPreventionContract {
         true
     }
 
-    /// Flag suspicious publisher activity
-    pub fn flag_suspicious(env: Env, publisher: Address) {
+    /// Flag suspicious publisher activity (admin or authorized reporter)
+    pub fn flag_suspicious(env: Env, reporter: Address, publisher: Address) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_reporter(&env, &reporter);
+
         let key = DataKey::SuspiciousActivity(publisher.clone());
         let mut activity: SuspiciousActivity = env
             .storage()
@@ -259,13 +397,14 @@ impl FraudPreventionContract {
             .get(&DataKey::SuspiciousThreshold)
             .unwrap_or(100);
 
-        if activity.suspicious_views > threshold {
+        if activity.suspicious_views > threshold && !activity.suspended {
             activity.suspended = true;
             // Cross-contract call to suspend publisher
             if let Some(network_addr) = env.storage().instance().get::<DataKey, Address>(&DataKey::PublisherNetwork) {
                 let network_client = PublisherNetworkContractClient::new(&env, &network_addr);
                 network_client.suspend_publisher(&env.current_contract_address(), &publisher);
             }
+            Self::_hold_escrows(&env, &publisher);
         }
 
         env.storage().persistent().set(&key, &activity);
@@ -277,18 +416,54 @@ impl FraudPreventionContract {
         );
     }
 
-    /// Admin: clear suspicious flag
+    /// Dispute one receipt of a pending view batch in the campaign
+    /// orchestrator (admin or authorized reporter). Pass `duplicate_of` to
+    /// prove the same view was already committed elsewhere; without it the
+    /// receipt's signature is disputed.
+    pub fn dispute_batch_view(env: Env, reporter: Address, challenged: ViewProof, duplicate_of: Option<ViewProof>) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_reporter(&env, &reporter);
+
+        let orchestrator_addr: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::CampaignOrchestrator)
+            .expect("orchestrator not set");
+        let orchestrator_client = CampaignOrchestratorContractClient::new(&env, &orchestrator_addr);
+        orchestrator_client.challenge_view(&env.current_contract_address(), &challenged, &duplicate_of);
+
+        env.events().publish(
+            (symbol_short!("batch"), symbol_short!("disputed")),
+            (challenged.batch_id, challenged.receipt.nonce),
+        );
+    }
+
+    /// Admin: clear suspicious flag, reinstating a suspended publisher and
+    /// releasing the escrows held on its account
     pub fn clear_flag(env: Env, admin: Address, publisher: Address) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        admin.require_auth();
-        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        if admin != stored_admin {
-            panic!("unauthorized");
+        Self::_require_admin(&env, &admin);
+
+        let activity: Option<SuspiciousActivity> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::SuspiciousActivity(publisher.clone()));
+        if activity.is_some_and(|a| a.suspended) {
+            if let Some(network_addr) = env.storage().instance().get::<DataKey, Address>(&DataKey::PublisherNetwork) {
+                let network_client = PublisherNetworkContractClient::new(&env, &network_addr);
+                network_client.reinstate_publisher(&env.current_contract_address(), &publisher);
+            }
         }
+        Self::_release_escrows(&env, &publisher);
 
         env.storage()
             .persistent()
-            .remove(&DataKey::SuspiciousActivity(publisher));
+            .remove(&DataKey::SuspiciousActivity(publisher.clone()));
+
+        env.events().publish(
+            (symbol_short!("publisher"), symbol_short!("cleared")),
+            publisher,
+        );
     }
 
     /// Admin: update verification threshold
@@ -345,6 +520,43 @@ impl FraudPreventionContract {
         }
     }
 
+    pub fn get_scoring_config(env: Env) -> ScoringConfig {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .instance()
+            .get(&DataKey::ScoringConfig)
+            .unwrap_or(Self::_default_scoring())
+    }
+
+    /// View ID for a view made now; the message attestation signers sign.
+    pub fn get_view_id(
+        env: Env,
+        campaign_id: u64,
+        publisher: Address,
+        viewer: Address,
+    ) -> BytesN<32> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_generate_view_id(&env, campaign_id, &publisher, &viewer)
+    }
+
+    pub fn get_view_record(env: Env, view_id: BytesN<32>) -> Option<ViewRecord> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::ViewRecord(view_id))
+    }
+
+    pub fn get_held_escrows(env: Env, publisher: Address) -> Vec<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::HeldEscrows(publisher))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    pub fn is_reporter(env: Env, reporter: Address) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().has(&DataKey::Reporter(reporter))
+    }
+
     pub fn get_total_verifications(env: Env) -> u64 {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage()
@@ -357,14 +569,85 @@ impl FraudPreventionContract {
     // Internal Helpers
     // ============================================================
 
-    fn _generate_view_id(env: &Env, campaign_id: u64, publisher: &Address, viewer: &Address) -> BytesN<32> {
-        // Create a deterministic ID from campaign+publisher+viewer+timestamp
-        let mut data = Bytes::new(env);
-        // Combine relevant data - use campaign_id bytes
-        let campaign_bytes = campaign_id.to_be_bytes();
-        for b in campaign_bytes.iter() {
-            data.push_back(*b);
+    fn _require_admin(env: &Env, admin: &Address) {
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if *admin != stored_admin {
+            panic!("unauthorized");
+        }
+    }
+
+    fn _require_reporter(env: &Env, reporter: &Address) {
+        reporter.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if *reporter != stored_admin
+            && !env.storage().persistent().has(&DataKey::Reporter(reporter.clone()))
+        {
+            panic!("unauthorized reporter");
+        }
+    }
+
+    fn _hold_escrows(env: &Env, publisher: &Address) {
+        let vault_addr = match env.storage().instance().get::<DataKey, Address>(&DataKey::EscrowVault) {
+            Some(addr) => addr,
+            None => return,
+        };
+        let vault_client = EscrowVaultContractClient::new(env, &vault_addr);
+        let mut held: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::HeldEscrows(publisher.clone()))
+            .unwrap_or(Vec::new(env));
+        for escrow_id in vault_client.get_open_escrows(publisher).iter() {
+            vault_client.hold_for_fraud(&env.current_contract_address(), &escrow_id);
+            held.push_back(escrow_id);
+        }
+
+        let _ttl_key = DataKey::HeldEscrows(publisher.clone());
+        env.storage().persistent().set(&_ttl_key, &held);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    fn _release_escrows(env: &Env, publisher: &Address) {
+        let held: Vec<u64> = match env
+            .storage()
+            .persistent()
+            .get(&DataKey::HeldEscrows(publisher.clone()))
+        {
+            Some(held) => held,
+            None => return,
+        };
+        let vault_addr: Address = env.storage().instance().get(&DataKey::EscrowVault).unwrap();
+        let vault_client = EscrowVaultContractClient::new(env, &vault_addr);
+        for escrow_id in held.iter() {
+            vault_client.release_fraud_hold(&env.current_contract_address(), &escrow_id);
         }
+        env.storage().persistent().remove(&DataKey::HeldEscrows(publisher.clone()));
+    }
+
+    fn _default_scoring() -> ScoringConfig {
+        ScoringConfig {
+            base_score: 90,
+            attestation_bonus: 10,
+            viewer_rate_penalty: 2,
+            publisher_flag_penalty: 2,
+            anomaly_penalty: 40,
+        }
+    }
+
+    fn _generate_view_id(env: &Env, campaign_id: u64, publisher: &Address, viewer: &Address) -> BytesN<32> {
+        // Deterministic ID from campaign + publisher + viewer + dedup time bucket
+        let window: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::DedupWindow)
+            .unwrap_or(DEFAULT_DEDUP_WINDOW_SECS);
+        let bucket = env.ledger().timestamp() / window;
+
+        let mut data = Bytes::from_array(env, &campaign_id.to_be_bytes());
+        data.append(&publisher.clone().to_xdr(env));
+        data.append(&viewer.clone().to_xdr(env));
+        data.extend_from_array(&bucket.to_be_bytes());
         env.crypto().sha256(&data).into()
     }
 
@@ -372,13 +655,63 @@ impl FraudPreventionContract {
         env: &Env,
         campaign_id: u64,
         publisher: &Address,
-        proof_data: &Option<BytesN<32>>,
+        prior_views: u64,
+        view_id: &BytesN<32>,
+        proof_data: &Option<BytesN<64>>,
     ) -> u32 {
-        let base_score: u32 = 80;
-        let proof_bonus: u32 = if proof_data.is_some() { 10 } else { 0 };
-        
-        let score = base_score + proof_bonus;
-        
+        let config: ScoringConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::ScoringConfig)
+            .unwrap_or(Self::_default_scoring());
+        let mut score = config.base_score;
+
+        // Attestation: the signature must cover this exact view ID
+        if let Some(signature) = proof_data {
+            let signer: BytesN<32> = env
+                .storage()
+                .instance()
+                .get(&DataKey::AttestationSigner)
+                .expect("attestation signer not set");
+            let message = Bytes::from_array(env, &view_id.to_array());
+            env.crypto().ed25519_verify(&signer, &message, signature);
+            score += config.attestation_bonus;
+        }
+
+        // Viewer rate history within the current period
+        let rate_penalty = (prior_views as u32).saturating_mul(config.viewer_rate_penalty);
+        score = score.saturating_sub(rate_penalty);
+
+        // Publisher flag history; a suspended publisher never verifies
+        if let Some(activity) = env
+            .storage()
+            .persistent()
+            .get::<DataKey, SuspiciousActivity>(&DataKey::SuspiciousActivity(publisher.clone()))
+        {
+            if activity.suspended {
+                score = 0;
+            } else {
+                let flags = activity.suspicious_views.min(u32::MAX as u64) as u32;
+                score = score.saturating_sub(flags.saturating_mul(config.publisher_flag_penalty));
+            }
+        }
+
+        // Anomaly-detector flags
+        if config.anomaly_penalty > 0 {
+            if let Some(detector_addr) = env
+                .storage()
+                .instance()
+                .get::<DataKey, Address>(&DataKey::AnomalyDetector)
+            {
+                let detector_client = AnomalyDetectorContractClient::new(env, &detector_addr);
+                if detector_client.is_publisher_flagged(publisher) {
+                    score = score.saturating_sub(config.anomaly_penalty);
+                }
+            }
+        }
+
+        let score = score.min(100);
+
         // If score is very low, trigger campaign pause
         if score < 50 {
            if let Some(lifecycle_addr) = env.storage().instance().get::<DataKey, Address>(&DataKey::CampaignLifecycle) {
@@ -408,6 +741,28 @@ impl PublisherNetworkContract {
     pub fn suspend_publisher(env: Env, fraud_contract: Address, publisher: Address) {
         let _ = (env, fraud_contract, publisher);
     }
+    pub fn reinstate_publisher(env: Env, fraud_contract: Address, publisher: Address) {
+        let _ = (env, fraud_contract, publisher);
+    }
+}
+
+#[contract]
+pub struct AnomalyDetectorContract;
+#[contractimpl]
+impl AnomalyDetectorContract {
+    pub fn is_publisher_flagged(env: Env, publisher: Address) -> bool {
+        let _ = (env, publisher);
+        false
+    }
+}
+
+#[contract]
+pub struct CampaignOrchestratorContract;
+#[contractimpl]
+impl CampaignOrchestratorContract {
+    pub fn challenge_view(env: Env, fraud_contract: Address, challenged: ViewProof, duplicate_of: Option<ViewProof>) {
+        let _ = (env, fraud_contract, challenged, duplicate_of);
+    }
 }
 
 #[contract]
@@ -417,6 +772,13 @@ impl EscrowVaultContract {
     pub fn hold_for_fraud(env: Env, fraud_contract: Address, escrow_id: u64) {
         let _ = (env, fraud_contract, escrow_id);
     }
+    pub fn release_fraud_hold(env: Env, fraud_contract: Address, escrow_id: u64) {
+        let _ = (env, fraud_contract, escrow_id);
+    }
+    pub fn get_open_escrows(env: Env, beneficiary: Address) -> Vec<u64> {
+        let _ = beneficiary;
+        Vec::new(&env)
+    }
 }
 
 mod test;
@@ -1,10 +1,92 @@
 #![cfg(test)]
 use super::*;
+use ed25519_dalek::{Signer, SigningKey};
 use soroban_sdk::{
+    contract as soroban_contract, contractimpl as soroban_contractimpl,
     testutils::{Address as _, Ledger},
-    Address, BytesN, Env,
+    vec, Address, BytesN, Env, Vec,
 };
 
+// ─── Mock anomaly detector ───────────────────────────────────────────────────
+
+#[soroban_contract]
+pub struct MockAnomalyDetector;
+
+#[soroban_contractimpl]
+impl MockAnomalyDetector {
+    pub fn flag(env: Env, publisher: Address) {
+        env.storage().instance().set(&publisher, &true);
+    }
+
+    pub fn is_publisher_flagged(env: Env, publisher: Address) -> bool {
+        env.storage().instance().get(&publisher).unwrap_or(false)
+    }
+}
+
+// ─── Mock publisher-network / escrow-vault ──────────────────────────────────
+// Record the enforcement calls fraud-prevention makes when a publisher
+// crosses the suspicious threshold and when its flag is cleared.
+
+#[soroban_contract]
+pub struct MockNetwork;
+
+#[soroban_contractimpl]
+impl MockNetwork {
+    pub fn suspend_publisher(env: Env, _fraud_contract: Address, publisher: Address) {
+        env.storage().instance().set(&publisher, &true);
+    }
+
+    pub fn reinstate_publisher(env: Env, _fraud_contract: Address, publisher: Address) {
+        env.storage().instance().set(&publisher, &false);
+    }
+
+    pub fn is_suspended(env: Env, publisher: Address) -> bool {
+        env.storage().instance().get(&publisher).unwrap_or(false)
+    }
+}
+
+#[soroban_contract]
+pub struct MockVault;
+
+#[soroban_contractimpl]
+impl MockVault {
+    pub fn set_open_escrows(env: Env, beneficiary: Address, ids: Vec<u64>) {
+        env.storage().instance().set(&beneficiary, &ids);
+    }
+
+    pub fn get_open_escrows(env: Env, beneficiary: Address) -> Vec<u64> {
+        env.storage().instance().get(&beneficiary).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn hold_for_fraud(env: Env, _fraud_contract: Address, escrow_id: u64) {
+        env.storage().instance().set(&escrow_id, &true);
+    }
+
+    pub fn release_fraud_hold(env: Env, _fraud_contract: Address, escrow_id: u64) {
+        env.storage().instance().set(&escrow_id, &false);
+    }
+
+    pub fn is_held(env: Env, escrow_id: u64) -> bool {
+        env.storage().instance().get(&escrow_id).unwrap_or(false)
+    }
+}
+
+#[soroban_contract]
+pub struct MockOrchestrator;
+
+#[soroban_contractimpl]
+impl MockOrchestrator {
+    pub fn challenge_view(env: Env, _fraud_contract: Address, challenged: ViewProof, duplicate_of: Option<ViewProof>) {
+        env.storage()
+            .instance()
+            .set(&challenged.batch_id, &(challenged.receipt.nonce, duplicate_of.is_some()));
+    }
+
+    pub fn challenged(env: Env, batch_id: u64) -> Option<(u64, bool)> {
+        env.storage().instance().get(&batch_id)
+    }
+}
+
 // ─── helpers ─────────────────────────────────────────────────────────────────
 
 fn setup(env: &Env) -> (FraudPreventionContractClient, Address) {
@@ -17,6 +99,32 @@ fn setup(env: &Env) -> (FraudPreventionContractClient, Address) {
     (client, admin)
 }
 
+/// Register an attestation key and return it for signing view IDs.
+fn setup_attestation(env: &Env, client: &FraudPreventionContractClient, admin: &Address) -> SigningKey {
+    let key = SigningKey::from_bytes(&[7u8; 32]);
+    let public = BytesN::from_array(env, &key.verifying_key().to_bytes());
+    client.set_attestation_signer(admin, &public);
+    key
+}
+
+/// Wire fraud-prevention to mock network and vault contracts and make it
+/// suspend publishers on their second suspicious view.
+fn setup_enforcement(
+    env: &Env,
+    client: &FraudPreventionContractClient,
+    admin: &Address,
+) -> (MockNetworkClient<'static>, MockVaultClient<'static>) {
+    let network = env.register_contract(None, MockNetwork);
+    let vault = env.register_contract(None, MockVault);
+    client.set_dependent_contracts(admin, &Address::generate(env), &network, &vault);
+    client.set_suspicious_threshold(admin, &1u64);
+    (MockNetworkClient::new(env, &network), MockVaultClient::new(env, &vault))
+}
+
+fn attest(env: &Env, key: &SigningKey, view_id: &BytesN<32>) -> BytesN<64> {
+    BytesN::from_array(env, &key.sign(&view_id.to_array()).to_bytes())
+}
+
 // ─── initialize ──────────────────────────────────────────────────────────────
 
 #[test]
@@ -143,7 +251,7 @@ fn test_flag_suspicious() {
 
     let publisher = Address::generate(&env);
 
-    client.flag_suspicious(&publisher);
+    client.flag_suspicious(&admin, &publisher);
 
     let status = client.get_suspicious_status(&publisher).unwrap();
     assert_eq!(status.suspicious_views, 1);
@@ -155,13 +263,13 @@ fn test_flag_suspicious() {
 fn test_flag_suspicious_accumulates() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _) = setup(&env);
+    let (client, admin) = setup(&env);
 
     let publisher = Address::generate(&env);
 
-    client.flag_suspicious(&publisher);
-    client.flag_suspicious(&publisher);
-    client.flag_suspicious(&publisher);
+    client.flag_suspicious(&admin, &publisher);
+    client.flag_suspicious(&admin, &publisher);
+    client.flag_suspicious(&admin, &publisher);
 
     let status = client.get_suspicious_status(&publisher).unwrap();
     assert_eq!(status.suspicious_views, 3);
@@ -188,7 +296,7 @@ fn test_clear_flag() {
     let (client, admin) = setup(&env);
 
     let publisher = Address::generate(&env);
-    client.flag_suspicious(&publisher);
+    client.flag_suspicious(&admin, &publisher);
     assert!(client.get_suspicious_status(&publisher).is_some());
 
     client.clear_flag(&admin, &publisher);
@@ -200,14 +308,131 @@ fn test_clear_flag() {
 fn test_clear_flag_unauthorized() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _) = setup(&env);
+    let (client, admin) = setup(&env);
     let stranger = Address::generate(&env);
     let publisher = Address::generate(&env);
 
-    client.flag_suspicious(&publisher);
+    client.flag_suspicious(&admin, &publisher);
     client.clear_flag(&stranger, &publisher);
 }
 
+#[test]
+fn test_authorized_reporter_can_flag() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let detector = Address::generate(&env);
+    let publisher = Address::generate(&env);
+
+    client.set_reporter(&admin, &detector, &true);
+    assert!(client.is_reporter(&detector));
+    client.flag_suspicious(&detector, &publisher);
+
+    assert_eq!(client.get_suspicious_status(&publisher).unwrap().total_flags, 1);
+}
+
+#[test]
+#[should_panic(expected = "unauthorized reporter")]
+fn test_flag_suspicious_unauthorized_reporter() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    client.flag_suspicious(&Address::generate(&env), &Address::generate(&env));
+}
+
+#[test]
+#[should_panic(expected = "unauthorized reporter")]
+fn test_revoked_reporter_cannot_flag() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let detector = Address::generate(&env);
+
+    client.set_reporter(&admin, &detector, &true);
+    client.set_reporter(&admin, &detector, &false);
+    client.flag_suspicious(&detector, &Address::generate(&env));
+}
+
+#[test]
+fn test_crossing_threshold_suspends_and_holds_escrows() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let (network, vault) = setup_enforcement(&env, &client, &admin);
+    let publisher = Address::generate(&env);
+    vault.set_open_escrows(&publisher, &vec![&env, 4u64, 9u64]);
+
+    client.flag_suspicious(&admin, &publisher);
+    assert!(!network.is_suspended(&publisher));
+    assert!(!vault.is_held(&4u64));
+
+    client.flag_suspicious(&admin, &publisher);
+    assert!(client.is_publisher_suspended(&publisher));
+    assert!(network.is_suspended(&publisher));
+    assert!(vault.is_held(&4u64) && vault.is_held(&9u64));
+    assert_eq!(client.get_held_escrows(&publisher), vec![&env, 4u64, 9u64]);
+}
+
+#[test]
+fn test_clear_flag_reinstates_and_releases_holds() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let (network, vault) = setup_enforcement(&env, &client, &admin);
+    let publisher = Address::generate(&env);
+    vault.set_open_escrows(&publisher, &vec![&env, 4u64]);
+
+    client.flag_suspicious(&admin, &publisher);
+    client.flag_suspicious(&admin, &publisher);
+    client.clear_flag(&admin, &publisher);
+
+    assert!(!client.is_publisher_suspended(&publisher));
+    assert!(!network.is_suspended(&publisher));
+    assert!(!vault.is_held(&4u64));
+    assert!(client.get_held_escrows(&publisher).is_empty());
+}
+
+fn batch_receipt(env: &Env, batch_id: u64) -> ViewProof {
+    let receipt = ViewReceipt {
+        campaign_id: 1,
+        publisher: Address::generate(env),
+        viewer_key: BytesN::from_array(env, &[3u8; 32]),
+        nonce: 3,
+        timestamp: 0,
+        signature: BytesN::from_array(env, &[0u8; 64]),
+    };
+    ViewProof { batch_id, receipt, proof: Vec::new(env) }
+}
+
+#[test]
+fn test_dispute_batch_view_forwards_challenge() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let orchestrator_id = env.register_contract(None, MockOrchestrator);
+    client.set_campaign_orchestrator(&admin, &orchestrator_id);
+    let detector = Address::generate(&env);
+    client.set_reporter(&admin, &detector, &true);
+
+    client.dispute_batch_view(&detector, &batch_receipt(&env, 7), &None);
+
+    let orchestrator = MockOrchestratorClient::new(&env, &orchestrator_id);
+    assert_eq!(orchestrator.challenged(&7u64), Some((3, false)));
+}
+
+#[test]
+#[should_panic(expected = "unauthorized reporter")]
+fn test_dispute_batch_view_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let orchestrator_id = env.register_contract(None, MockOrchestrator);
+    client.set_campaign_orchestrator(&admin, &orchestrator_id);
+
+    client.dispute_batch_view(&Address::generate(&env), &batch_receipt(&env, 7), &None);
+}
+
 // ─── verify_view ─────────────────────────────────────────────────────────────
 
 #[test]
@@ -223,13 +448,154 @@ fn test_verify_view_with_proof() {
     let network = Address::generate(&env);
     let vault = Address::generate(&env);
     client.set_dependent_contracts(&admin, &lifecycle, &network, &vault);
+    let key = setup_attestation(&env, &client, &admin);
 
-    // Threshold is 80, base_score=80 + proof_bonus=10 = 90 >= 80 → verified
-    let proof = BytesN::from_array(&env, &[1u8; 32]);
+    // base_score=90 + attestation_bonus=10 = 100 >= 80 → verified
+    let view_id = client.get_view_id(&1u64, &publisher, &viewer);
+    let proof = attest(&env, &key, &view_id);
     let result = client.verify_view(&1u64, &publisher, &viewer, &Some(proof));
     assert!(result);
 
     assert_eq!(client.get_total_verifications(), 1);
+    assert_eq!(client.get_view_record(&view_id).unwrap().verification_score, 100);
+}
+
+#[test]
+#[should_panic]
+fn test_verify_view_with_forged_proof() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    setup_attestation(&env, &client, &admin);
+
+    let publisher = Address::generate(&env);
+    let viewer = Address::generate(&env);
+
+    // Signed by a key that is not the attestation signer
+    let forger = SigningKey::from_bytes(&[9u8; 32]);
+    let view_id = client.get_view_id(&1u64, &publisher, &viewer);
+    let proof = attest(&env, &forger, &view_id);
+    client.verify_view(&1u64, &publisher, &viewer, &Some(proof));
+}
+
+#[test]
+fn test_second_view_of_campaign_is_not_duplicate() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let publisher = Address::generate(&env);
+    let viewer_a = Address::generate(&env);
+    let viewer_b = Address::generate(&env);
+
+    client.verify_view(&1u64, &publisher, &viewer_a, &None);
+    client.verify_view(&1u64, &publisher, &viewer_b, &None);
+    client.verify_view(&1u64, &Address::generate(&env), &viewer_a, &None);
+
+    assert_eq!(client.get_total_verifications(), 3);
+}
+
+#[test]
+#[should_panic(expected = "duplicate view")]
+fn test_repeat_view_in_window_is_duplicate() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let publisher = Address::generate(&env);
+    let viewer = Address::generate(&env);
+
+    client.verify_view(&1u64, &publisher, &viewer, &None);
+    client.verify_view(&1u64, &publisher, &viewer, &None);
+}
+
+#[test]
+fn test_repeat_view_after_window_counts() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    client.set_dedup_window(&admin, &3_600u64);
+
+    let publisher = Address::generate(&env);
+    let viewer = Address::generate(&env);
+
+    client.verify_view(&1u64, &publisher, &viewer, &None);
+    env.ledger().with_mut(|li| {
+        li.timestamp += 3_600;
+    });
+    client.verify_view(&1u64, &publisher, &viewer, &None);
+
+    assert_eq!(client.get_total_verifications(), 2);
+}
+
+#[test]
+fn test_viewer_rate_history_lowers_score() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+
+    let publisher = Address::generate(&env);
+    let viewer = Address::generate(&env);
+
+    client.verify_view(&1u64, &publisher, &viewer, &None);
+    client.verify_view(&2u64, &publisher, &viewer, &None);
+    client.verify_view(&3u64, &publisher, &viewer, &None);
+
+    let first = client.get_view_id(&1u64, &publisher, &viewer);
+    let third = client.get_view_id(&3u64, &publisher, &viewer);
+    assert_eq!(client.get_view_record(&first).unwrap().verification_score, 90);
+    assert_eq!(client.get_view_record(&third).unwrap().verification_score, 86);
+}
+
+#[test]
+fn test_publisher_flags_lower_score() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+
+    let publisher = Address::generate(&env);
+    let viewer = Address::generate(&env);
+    for _ in 0..3 {
+        client.flag_suspicious(&admin, &publisher);
+    }
+
+    client.verify_view(&1u64, &publisher, &viewer, &None);
+    let view_id = client.get_view_id(&1u64, &publisher, &viewer);
+    assert_eq!(client.get_view_record(&view_id).unwrap().verification_score, 84);
+}
+
+#[test]
+#[should_panic(expected = "verification failed")]
+fn test_anomaly_flagged_publisher_fails_verification() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+
+    let detector_id = env.register_contract(None, MockAnomalyDetector);
+    let publisher = Address::generate(&env);
+    MockAnomalyDetectorClient::new(&env, &detector_id).flag(&publisher);
+    client.set_anomaly_detector(&admin, &detector_id);
+
+    // 90 - 40 = 50 < 80
+    client.verify_view(&1u64, &publisher, &Address::generate(&env), &None);
+}
+
+#[test]
+fn test_scoring_signal_can_be_disabled() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+
+    let detector_id = env.register_contract(None, MockAnomalyDetector);
+    let publisher = Address::generate(&env);
+    MockAnomalyDetectorClient::new(&env, &detector_id).flag(&publisher);
+    client.set_anomaly_detector(&admin, &detector_id);
+
+    let mut config = client.get_scoring_config();
+    config.anomaly_penalty = 0;
+    client.set_scoring_config(&admin, &config);
+
+    assert!(client.verify_view(&1u64, &publisher, &Address::generate(&env), &None));
 }
 
 #[test]
@@ -246,7 +612,7 @@ fn test_verify_view_without_proof() {
     let vault = Address::generate(&env);
     client.set_dependent_contracts(&admin, &lifecycle, &network, &vault);
 
-    // base_score = 80, no proof bonus → 80 >= 80 → verified
+    // base_score = 90, no attestation bonus → 90 >= 80 → verified
     let result = client.verify_view(&1u64, &publisher, &viewer, &None);
     assert!(result);
 }
@@ -318,5 +684,5 @@ fn test_fraud_integration() {
     // Normally we'd call flag_suspicious multiple times
     // For this test, let's just verify it can be called without panic
     // (Actual cross-contract verification would require registering the other contracts too)
-    client.flag_suspicious(&publisher);
+    client.flag_suspicious(&admin, &publisher);
 }
@@ -5,12 +5,16 @@
 //! - ("proposal", "created"): [proposal_id: u64, proposer: Address]
 //! - ("gov", "voted"): [proposal_id: u64, voter: Address, power: i128]
 //! - ("proposal", "finalized"): [proposal_id: u64, status: ProposalStatus]
+//! - ("proposal", "queued"): [proposal_id: u64, entry_ids: Vec<u64>]
+//! - ("proposal", "executed"): [proposal_id: u64]
+//! - ("proposal", "failed"): [proposal_id: u64]
+//! - ("proposal", "expired"): [proposal_id: u64]
 
 
 #![no_std]
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short,
-    Address, Env, String,
+    vec, Address, Env, IntoVal, String, Symbol, Val, Vec,
 };
 
 // ============================================================
@@ -23,11 +27,33 @@ pub enum ProposalStatus {
     Active,
     Passed,
     Rejected,
+    Queued,
     Executed,
+    Failed,
     Cancelled,
     Expired,
 }
 
+/// Mirror of timelock-executor's `TimelockStatus`, read back via `get_status`.
+#[contracttype]
+#[derive(Clone, PartialEq)]
+pub enum TimelockStatus {
+    Queued,
+    Executed,
+    Failed,
+    Cancelled,
+    Expired,
+}
+
+/// A call the proposal performs through the timelock once passed.
+#[contracttype]
+#[derive(Clone)]
+pub struct ProposalAction {
+    pub target_contract: Address,
+    pub function_name: Symbol,
+    pub args: Vec<Val>,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub enum VoteChoice {
@@ -42,7 +68,7 @@ pub struct Proposal {
     pub proposer: Address,
     pub title: String,
     pub description: String,
-    pub target_contract: Option<Address>,
+    pub actions: Vec<ProposalAction>,
     pub status: ProposalStatus,
     pub votes_for: i128,
     pub votes_against: i128,
@@ -76,7 +102,10 @@ pub enum DataKey {
     QuorumBps,
     PassThreshold,
     ProposerMinTokens,
+    Timelock,
+    TimelockDelay,
     Proposal(u64),
+    TimelockEntries(u64),
     Vote(u64, Address),
     HasVoted(u64, Address),
 }
@@ -137,7 +166,7 @@ impl GovernanceDaoContract {
         proposer: Address,
         title: String,
         description: String,
-        target_contract: Option<Address>,
+        actions: Vec<ProposalAction>,
     ) -> u64 {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         proposer.require_auth();
@@ -170,7 +199,7 @@ impl GovernanceDaoContract {
             proposer: proposer.clone(),
             title,
             description,
-            target_contract,
+            actions,
             status: ProposalStatus::Active,
             votes_for: 0,
             votes_against: 0,
@@ -202,8 +231,9 @@ impl GovernanceDaoContract {
         proposal_id
     }
 
-    /// Cast a vote on a proposal
-    pub fn cast_vote(env: Env, voter: Address, proposal_id: u64, choice: VoteChoice, power: i128) {
+    /// Cast a vote with the voter's governance-token power as of the
+    /// proposal's start ledger, so tokens moved after creation cannot vote twice.
+    pub fn cast_vote(env: Env, voter: Address, proposal_id: u64, choice: VoteChoice) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         voter.require_auth();
 
@@ -230,6 +260,17 @@ impl GovernanceDaoContract {
             panic!("voting period ended");
         }
 
+        let token_address: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::GovernanceToken)
+            .unwrap();
+        let power: i128 = env.invoke_contract(
+            &token_address,
+            &Symbol::new(&env, "voting_power_at"),
+            vec![&env, voter.clone().into_val(&env), proposal.start_ledger.into_val(&env)],
+        );
+
         if power <= 0 {
             panic!("invalid voting power");
         }
@@ -327,7 +368,154 @@ impl GovernanceDaoContract {
         );
     }
 
-    /// Mark proposal as executed (admin only)
+    /// Queue every action of a passed proposal in the timelock. Anyone can call this.
+    pub fn queue_proposal(env: Env, proposal_id: u64) -> Vec<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let mut proposal: Proposal = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Proposal(proposal_id))
+            .expect("proposal not found");
+
+        if proposal.status != ProposalStatus::Passed {
+            panic!("proposal not passed");
+        }
+        if proposal.actions.is_empty() {
+            panic!("proposal has no actions");
+        }
+
+        let timelock: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Timelock)
+            .expect("timelock not set");
+        let delay: u64 = env.storage().instance().get(&DataKey::TimelockDelay).unwrap();
+        let queue_fn = Symbol::new(&env, "queue");
+
+        let mut entry_ids: Vec<u64> = Vec::new(&env);
+        for action in proposal.actions.iter() {
+            let entry_id: u64 = env.invoke_contract(
+                &timelock,
+                &queue_fn,
+                vec![
+                    &env,
+                    env.current_contract_address().into_val(&env),
+                    action.target_contract.into_val(&env),
+                    action.function_name.into_val(&env),
+                    action.args.into_val(&env),
+                    proposal.title.into_val(&env),
+                    delay.into_val(&env),
+                ],
+            );
+            entry_ids.push_back(entry_id);
+        }
+
+        proposal.status = ProposalStatus::Queued;
+        let _ttl_key = DataKey::Proposal(proposal_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &proposal);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let _ttl_key = DataKey::TimelockEntries(proposal_id);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &entry_ids);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("proposal"), symbol_short!("queued")),
+            (proposal_id, entry_ids.clone()),
+        );
+
+        entry_ids
+    }
+
+    /// Pull the timelock's results for a queued proposal. Flips it to
+    /// `Executed` once every action succeeded, `Failed` if any action
+    /// failed or was cancelled, and otherwise `Expired` if any action
+    /// missed its grace period. Anyone can call this.
+    pub fn sync_execution(env: Env, proposal_id: u64) -> ProposalStatus {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let mut proposal: Proposal = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Proposal(proposal_id))
+            .expect("proposal not found");
+
+        if proposal.status != ProposalStatus::Queued {
+            panic!("proposal not queued");
+        }
+
+        let timelock: Address = env.storage().instance().get(&DataKey::Timelock).unwrap();
+        let entry_ids: Vec<u64> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::TimelockEntries(proposal_id))
+            .unwrap();
+        let status_fn = Symbol::new(&env, "get_status");
+
+        let mut all_executed = true;
+        let mut expired = false;
+        for entry_id in entry_ids.iter() {
+            let status: Option<TimelockStatus> =
+                env.invoke_contract(&timelock, &status_fn, vec![&env, entry_id.into_val(&env)]);
+            match status {
+                Some(TimelockStatus::Executed) => {}
+                Some(TimelockStatus::Queued) => all_executed = false,
+                Some(TimelockStatus::Expired) => expired = true,
+                _ => {
+                    proposal.status = ProposalStatus::Failed;
+                    break;
+                }
+            }
+        }
+
+        if proposal.status == ProposalStatus::Queued && expired {
+            proposal.status = ProposalStatus::Expired;
+        } else if proposal.status == ProposalStatus::Queued && all_executed {
+            proposal.status = ProposalStatus::Executed;
+            proposal.executed_at = Some(env.ledger().timestamp());
+        }
+
+        if proposal.status != ProposalStatus::Queued {
+            let _ttl_key = DataKey::Proposal(proposal_id);
+            env.storage()
+                .persistent()
+                .set(&_ttl_key, &proposal);
+            env.storage()
+                .persistent()
+                .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+            let topic = match proposal.status {
+                ProposalStatus::Executed => symbol_short!("executed"),
+                ProposalStatus::Expired => symbol_short!("expired"),
+                _ => symbol_short!("failed"),
+            };
+            env.events().publish((symbol_short!("proposal"), topic), proposal_id);
+        }
+
+        proposal.status
+    }
+
+    /// Admin: set the timelock that executes proposal actions, and the delay to queue them with.
+    /// The timelock must list this contract as a proposer.
+    pub fn set_timelock(env: Env, admin: Address, timelock: Address, delay_secs: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        env.storage().instance().set(&DataKey::Timelock, &timelock);
+        env.storage().instance().set(&DataKey::TimelockDelay, &delay_secs);
+    }
+
+    /// Mark a proposal without actions as executed (admin only). Proposals
+    /// with actions execute through the timelock instead.
     pub fn execute_proposal(env: Env, admin: Address, proposal_id: u64) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         admin.require_auth();
@@ -345,6 +533,9 @@ impl GovernanceDaoContract {
         if proposal.status != ProposalStatus::Passed {
             panic!("proposal not passed");
         }
+        if !proposal.actions.is_empty() {
+            panic!("proposal has actions: use queue_proposal");
+        }
 
         proposal.status = ProposalStatus::Executed;
         proposal.executed_at = Some(env.ledger().timestamp());
@@ -395,6 +586,14 @@ impl GovernanceDaoContract {
             .get(&DataKey::Proposal(proposal_id))
     }
 
+    pub fn get_timelock_entries(env: Env, proposal_id: u64) -> Vec<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::TimelockEntries(proposal_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
     pub fn get_vote(env: Env, proposal_id: u64, voter: Address) -> Option<Vote> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage()
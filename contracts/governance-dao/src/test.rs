@@ -3,7 +3,7 @@ use super::*;
 use soroban_sdk::{
     contract as soroban_contract, contractimpl as soroban_contractimpl,
     testutils::{Address as _, Ledger},
-    Address, Env, String,
+    vec, Address, Env, IntoVal, String, Symbol, Val, Vec,
 };
 
 // ─── Mock governance token ───────────────────────────────────────────────────
@@ -26,6 +26,55 @@ impl MockGovToken {
     pub fn total_supply(env: Env) -> i128 {
         env.storage().instance().get::<u32, i128>(&0u32).unwrap_or(0)
     }
+
+    /// Store the power `voting_power_at` reports for `voter`.
+    pub fn set_power(env: Env, voter: Address, amount: i128) {
+        env.storage().instance().set(&voter, &amount);
+    }
+
+    /// Called by GovernanceDaoContract::cast_vote; remembers the snapshot
+    /// ledger it was asked about.
+    pub fn voting_power_at(env: Env, voter: Address, ledger: u32) -> i128 {
+        env.storage().instance().set(&1u32, &ledger);
+        env.storage().instance().get(&voter).unwrap_or(0)
+    }
+
+    pub fn last_snapshot_ledger(env: Env) -> u32 {
+        env.storage().instance().get(&1u32).unwrap_or(0)
+    }
+}
+
+// ─── Mock timelock ───────────────────────────────────────────────────────────
+// Records what queue_proposal submits and lets tests decide each entry's
+// outcome, which sync_execution then reads back through `get_status`.
+
+#[soroban_contract]
+pub struct MockTimelock;
+
+#[soroban_contractimpl]
+impl MockTimelock {
+    pub fn queue(
+        env: Env,
+        _proposer: Address,
+        _target_contract: Address,
+        _function_name: Symbol,
+        _args: Vec<Val>,
+        _description: String,
+        _delay_secs: u64,
+    ) -> u64 {
+        let id: u64 = env.storage().instance().get(&0u64).unwrap_or(0) + 1;
+        env.storage().instance().set(&0u64, &id);
+        env.storage().instance().set(&id, &TimelockStatus::Queued);
+        id
+    }
+
+    pub fn set_status(env: Env, entry_id: u64, status: TimelockStatus) {
+        env.storage().instance().set(&entry_id, &status);
+    }
+
+    pub fn get_status(env: Env, entry_id: u64) -> Option<TimelockStatus> {
+        env.storage().instance().get(&entry_id)
+    }
 }
 
 // ─── helpers ─────────────────────────────────────────────────────────────────
@@ -39,11 +88,10 @@ fn deploy_mock_gov_token(env: &Env, total_supply: i128) -> Address {
 }
 
 /// Deploy + initialize with: voting_period=100 ledgers, quorum=10%, pass=51%.
-/// Uses a plain address as the governance token for tests that don't finalize.
+/// Uses a MockGovToken with zero supply for tests that don't finalize.
 fn setup(env: &Env) -> (GovernanceDaoContractClient, Address, Address, Address) {
     let admin = Address::generate(env);
-    // For simple tests (no finalize) any address works as the governance token.
-    let token_addr = Address::generate(env);
+    let token_addr = deploy_mock_gov_token(env, 0);
 
     let contract_id = env.register_contract(None, GovernanceDaoContract);
     let client = GovernanceDaoContractClient::new(env, &contract_id);
@@ -67,6 +115,52 @@ fn setup_with_mock_token(
     (client, admin, token_addr)
 }
 
+/// Give `voter` snapshot voting power on the mock governance token.
+fn set_power(env: &Env, token: &Address, voter: &Address, amount: i128) {
+    MockGovTokenClient::new(env, token).set_power(voter, &amount);
+}
+
+fn no_actions(env: &Env) -> Vec<ProposalAction> {
+    Vec::new(env)
+}
+
+fn make_actions(env: &Env) -> Vec<ProposalAction> {
+    let target = Address::generate(env);
+    vec![
+        env,
+        ProposalAction {
+            target_contract: target.clone(),
+            function_name: Symbol::new(env, "set_platform_fee"),
+            args: vec![env, 3u32.into_val(env)],
+        },
+        ProposalAction {
+            target_contract: target,
+            function_name: Symbol::new(env, "pause"),
+            args: Vec::new(env),
+        },
+    ]
+}
+
+/// Create a proposal with actions and drive it to `Passed`.
+fn passed_proposal_with_actions(
+    env: &Env,
+    client: &GovernanceDaoContractClient,
+    token: &Address,
+) -> u64 {
+    let proposer = Address::generate(env);
+    let voter = Address::generate(env);
+    let proposal_id = client.create_proposal(
+        &proposer, &make_title(env), &make_desc(env), &make_actions(env),
+    );
+    set_power(env, token, &voter, 200);
+    client.cast_vote(&voter, &proposal_id, &VoteChoice::For);
+    env.ledger().with_mut(|li| {
+        li.sequence_number = 200;
+    });
+    client.finalize_proposal(&proposal_id);
+    proposal_id
+}
+
 fn make_title(env: &Env) -> String {
     String::from_str(env, "Increase treasury allocation")
 }
@@ -129,7 +223,7 @@ fn test_create_proposal() {
         &proposer,
         &make_title(&env),
         &make_desc(&env),
-        &None,
+        &no_actions(&env),
     );
 
     assert_eq!(proposal_id, 1);
@@ -150,8 +244,8 @@ fn test_create_multiple_proposals() {
     let (client, _, _, _) = setup(&env);
     let proposer = Address::generate(&env);
 
-    client.create_proposal(&proposer, &make_title(&env), &make_desc(&env), &None);
-    client.create_proposal(&proposer, &make_title(&env), &make_desc(&env), &None);
+    client.create_proposal(&proposer, &make_title(&env), &make_desc(&env), &no_actions(&env));
+    client.create_proposal(&proposer, &make_title(&env), &make_desc(&env), &no_actions(&env));
 
     assert_eq!(client.get_proposal_count(), 2);
 }
@@ -162,15 +256,16 @@ fn test_create_multiple_proposals() {
 fn test_cast_vote_for() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _, _, _) = setup(&env);
+    let (client, _, _, token) = setup(&env);
     let proposer = Address::generate(&env);
     let voter = Address::generate(&env);
 
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
-    client.cast_vote(&voter, &proposal_id, &VoteChoice::For, &1_000i128);
+    set_power(&env, &token, &voter, 1_000);
+    client.cast_vote(&voter, &proposal_id, &VoteChoice::For);
 
     let proposal = client.get_proposal(&proposal_id).unwrap();
     assert_eq!(proposal.votes_for, 1_000);
@@ -186,15 +281,16 @@ fn test_cast_vote_for() {
 fn test_cast_vote_against() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _, _, _) = setup(&env);
+    let (client, _, _, token) = setup(&env);
     let proposer = Address::generate(&env);
     let voter = Address::generate(&env);
 
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
-    client.cast_vote(&voter, &proposal_id, &VoteChoice::Against, &500i128);
+    set_power(&env, &token, &voter, 500);
+    client.cast_vote(&voter, &proposal_id, &VoteChoice::Against);
 
     let proposal = client.get_proposal(&proposal_id).unwrap();
     assert_eq!(proposal.votes_against, 500);
@@ -205,15 +301,16 @@ fn test_cast_vote_against() {
 fn test_cast_vote_abstain() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _, _, _) = setup(&env);
+    let (client, _, _, token) = setup(&env);
     let proposer = Address::generate(&env);
     let voter = Address::generate(&env);
 
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
-    client.cast_vote(&voter, &proposal_id, &VoteChoice::Abstain, &200i128);
+    set_power(&env, &token, &voter, 200);
+    client.cast_vote(&voter, &proposal_id, &VoteChoice::Abstain);
 
     let proposal = client.get_proposal(&proposal_id).unwrap();
     assert_eq!(proposal.votes_abstain, 200);
@@ -223,40 +320,69 @@ fn test_cast_vote_abstain() {
 fn test_vote_tally_accumulates() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _, _, _) = setup(&env);
+    let (client, _, _, token) = setup(&env);
     let proposer = Address::generate(&env);
     let voter_a = Address::generate(&env);
     let voter_b = Address::generate(&env);
     let voter_c = Address::generate(&env);
 
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
-    client.cast_vote(&voter_a, &proposal_id, &VoteChoice::For, &300i128);
-    client.cast_vote(&voter_b, &proposal_id, &VoteChoice::For, &200i128);
-    client.cast_vote(&voter_c, &proposal_id, &VoteChoice::Against, &100i128);
+    set_power(&env, &token, &voter_a, 300);
+    client.cast_vote(&voter_a, &proposal_id, &VoteChoice::For);
+    set_power(&env, &token, &voter_b, 200);
+    client.cast_vote(&voter_b, &proposal_id, &VoteChoice::For);
+    set_power(&env, &token, &voter_c, 100);
+    client.cast_vote(&voter_c, &proposal_id, &VoteChoice::Against);
 
     let proposal = client.get_proposal(&proposal_id).unwrap();
     assert_eq!(proposal.votes_for, 500);
     assert_eq!(proposal.votes_against, 100);
 }
 
+#[test]
+fn test_vote_uses_power_at_proposal_start() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, token) = setup(&env);
+    let proposer = Address::generate(&env);
+    let voter = Address::generate(&env);
+
+    env.ledger().with_mut(|li| {
+        li.sequence_number = 42;
+    });
+    let proposal_id = client.create_proposal(
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
+    );
+    env.ledger().with_mut(|li| {
+        li.sequence_number = 60;
+    });
+
+    set_power(&env, &token, &voter, 700);
+    client.cast_vote(&voter, &proposal_id, &VoteChoice::For);
+
+    assert_eq!(MockGovTokenClient::new(&env, &token).last_snapshot_ledger(), 42);
+    assert_eq!(client.get_vote(&proposal_id, &voter).unwrap().power, 700);
+}
+
 #[test]
 #[should_panic(expected = "already voted")]
 fn test_double_vote_rejected() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _, _, _) = setup(&env);
+    let (client, _, _, token) = setup(&env);
     let proposer = Address::generate(&env);
     let voter = Address::generate(&env);
 
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
-    client.cast_vote(&voter, &proposal_id, &VoteChoice::For, &1_000i128);
-    client.cast_vote(&voter, &proposal_id, &VoteChoice::Against, &1_000i128); // panic
+    set_power(&env, &token, &voter, 1_000);
+    client.cast_vote(&voter, &proposal_id, &VoteChoice::For);
+    client.cast_vote(&voter, &proposal_id, &VoteChoice::Against); // panic
 }
 
 #[test]
@@ -264,15 +390,16 @@ fn test_double_vote_rejected() {
 fn test_vote_zero_power_rejected() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _, _, _) = setup(&env);
+    let (client, _, _, token) = setup(&env);
     let proposer = Address::generate(&env);
     let voter = Address::generate(&env);
 
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
-    client.cast_vote(&voter, &proposal_id, &VoteChoice::For, &0i128);
+    set_power(&env, &token, &voter, 0);
+    client.cast_vote(&voter, &proposal_id, &VoteChoice::For);
 }
 
 #[test]
@@ -280,12 +407,12 @@ fn test_vote_zero_power_rejected() {
 fn test_vote_after_period_ends() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _, _, _) = setup(&env);
+    let (client, _, _, token) = setup(&env);
     let proposer = Address::generate(&env);
     let voter = Address::generate(&env);
 
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
     // start=0, period=100 → end=100; advance past it
@@ -293,7 +420,8 @@ fn test_vote_after_period_ends() {
         li.sequence_number = 200;
     });
 
-    client.cast_vote(&voter, &proposal_id, &VoteChoice::For, &1_000i128);
+    set_power(&env, &token, &voter, 1_000);
+    client.cast_vote(&voter, &proposal_id, &VoteChoice::For);
 }
 
 #[test]
@@ -301,16 +429,17 @@ fn test_vote_after_period_ends() {
 fn test_vote_on_cancelled_proposal() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, _, _, _) = setup(&env);
+    let (client, _, _, token) = setup(&env);
     let proposer = Address::generate(&env);
     let voter = Address::generate(&env);
 
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
     client.cancel_proposal(&proposer, &proposal_id);
-    client.cast_vote(&voter, &proposal_id, &VoteChoice::For, &1_000i128);
+    set_power(&env, &token, &voter, 1_000);
+    client.cast_vote(&voter, &proposal_id, &VoteChoice::For);
 }
 
 // ─── finalize_proposal ────────────────────────────────────────────────────────
@@ -321,16 +450,17 @@ fn test_finalize_proposal_passed() {
     env.mock_all_auths();
 
     // total supply = 1_000; quorum_bps=1_000 (10%) → need ≥100 votes
-    let (client, _, _) = setup_with_mock_token(&env, 1_000);
+    let (client, _, token) = setup_with_mock_token(&env, 1_000);
 
     let proposer = Address::generate(&env);
     let voter = Address::generate(&env);
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
     // 200 for votes  (200*10_000 >= 1_000*1_000 → quorum met; 100% >= 51% → Passed)
-    client.cast_vote(&voter, &proposal_id, &VoteChoice::For, &200i128);
+    set_power(&env, &token, &voter, 200);
+    client.cast_vote(&voter, &proposal_id, &VoteChoice::For);
 
     env.ledger().with_mut(|li| {
         li.sequence_number = 200;
@@ -348,15 +478,16 @@ fn test_finalize_proposal_rejected_no_quorum() {
     env.mock_all_auths();
 
     // total supply = 10_000; quorum needs ≥1_000 votes; cast only 50
-    let (client, _, _) = setup_with_mock_token(&env, 10_000);
+    let (client, _, token) = setup_with_mock_token(&env, 10_000);
 
     let proposer = Address::generate(&env);
     let voter = Address::generate(&env);
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
-    client.cast_vote(&voter, &proposal_id, &VoteChoice::For, &50i128);
+    set_power(&env, &token, &voter, 50);
+    client.cast_vote(&voter, &proposal_id, &VoteChoice::For);
 
     env.ledger().with_mut(|li| {
         li.sequence_number = 200;
@@ -385,11 +516,13 @@ fn test_finalize_proposal_rejected_not_enough_for_votes() {
     let voter_for = Address::generate(&env);
     let voter_against = Address::generate(&env);
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
-    client.cast_vote(&voter_for, &proposal_id, &VoteChoice::For, &400i128);
-    client.cast_vote(&voter_against, &proposal_id, &VoteChoice::Against, &600i128);
+    set_power(&env, &token_addr, &voter_for, 400);
+    client.cast_vote(&voter_for, &proposal_id, &VoteChoice::For);
+    set_power(&env, &token_addr, &voter_against, 600);
+    client.cast_vote(&voter_against, &proposal_id, &VoteChoice::Against);
 
     env.ledger().with_mut(|li| {
         li.sequence_number = 200;
@@ -410,7 +543,7 @@ fn test_finalize_still_active() {
     let proposer = Address::generate(&env);
 
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
     // sequence_number=0, end_ledger=100 → still active
@@ -424,15 +557,16 @@ fn test_execute_proposal() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, admin, _) = setup_with_mock_token(&env, 1_000);
+    let (client, admin, token) = setup_with_mock_token(&env, 1_000);
 
     let proposer = Address::generate(&env);
     let voter = Address::generate(&env);
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
-    client.cast_vote(&voter, &proposal_id, &VoteChoice::For, &200i128);
+    set_power(&env, &token, &voter, 200);
+    client.cast_vote(&voter, &proposal_id, &VoteChoice::For);
 
     env.ledger().with_mut(|li| {
         li.sequence_number = 200;
@@ -459,7 +593,7 @@ fn test_execute_active_proposal_fails() {
     let proposer = Address::generate(&env);
 
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
     client.execute_proposal(&admin, &proposal_id);
@@ -471,16 +605,17 @@ fn test_execute_proposal_by_stranger_fails() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let (client, _, _) = setup_with_mock_token(&env, 1_000);
+    let (client, _, token) = setup_with_mock_token(&env, 1_000);
 
     let proposer = Address::generate(&env);
     let voter = Address::generate(&env);
     let stranger = Address::generate(&env);
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
-    client.cast_vote(&voter, &proposal_id, &VoteChoice::For, &200i128);
+    set_power(&env, &token, &voter, 200);
+    client.cast_vote(&voter, &proposal_id, &VoteChoice::For);
 
     env.ledger().with_mut(|li| {
         li.sequence_number = 200;
@@ -490,6 +625,123 @@ fn test_execute_proposal_by_stranger_fails() {
     client.execute_proposal(&stranger, &proposal_id);
 }
 
+// ─── timelock pipeline ───────────────────────────────────────────────────────
+
+#[test]
+fn test_queue_and_sync_proposal_through_timelock() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_with_mock_token(&env, 1_000);
+    let timelock_id = env.register_contract(None, MockTimelock);
+    let timelock = MockTimelockClient::new(&env, &timelock_id);
+    client.set_timelock(&admin, &timelock_id, &3_600u64);
+
+    let proposal_id = passed_proposal_with_actions(&env, &client, &token);
+    assert_eq!(client.get_proposal(&proposal_id).unwrap().actions.len(), 2);
+
+    // No admin involvement: anyone can queue a passed proposal
+    let entry_ids = client.queue_proposal(&proposal_id);
+    assert_eq!(entry_ids.len(), 2);
+    assert_eq!(client.get_timelock_entries(&proposal_id), entry_ids);
+    assert!(matches!(client.get_proposal(&proposal_id).unwrap().status, ProposalStatus::Queued));
+
+    // One action still pending → stays queued
+    timelock.set_status(&entry_ids.get(0).unwrap(), &TimelockStatus::Executed);
+    assert!(matches!(client.sync_execution(&proposal_id), ProposalStatus::Queued));
+
+    timelock.set_status(&entry_ids.get(1).unwrap(), &TimelockStatus::Executed);
+    assert!(matches!(client.sync_execution(&proposal_id), ProposalStatus::Executed));
+
+    let p = client.get_proposal(&proposal_id).unwrap();
+    assert!(matches!(p.status, ProposalStatus::Executed));
+    assert!(p.executed_at.is_some());
+}
+
+#[test]
+fn test_sync_marks_proposal_failed_when_action_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_with_mock_token(&env, 1_000);
+    let timelock_id = env.register_contract(None, MockTimelock);
+    let timelock = MockTimelockClient::new(&env, &timelock_id);
+    client.set_timelock(&admin, &timelock_id, &3_600u64);
+
+    let proposal_id = passed_proposal_with_actions(&env, &client, &token);
+    let entry_ids = client.queue_proposal(&proposal_id);
+    timelock.set_status(&entry_ids.get(0).unwrap(), &TimelockStatus::Failed);
+
+    assert!(matches!(client.sync_execution(&proposal_id), ProposalStatus::Failed));
+    assert!(client.get_proposal(&proposal_id).unwrap().executed_at.is_none());
+}
+
+#[test]
+fn test_sync_marks_proposal_expired_when_action_misses_grace() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_with_mock_token(&env, 1_000);
+    let timelock_id = env.register_contract(None, MockTimelock);
+    let timelock = MockTimelockClient::new(&env, &timelock_id);
+    client.set_timelock(&admin, &timelock_id, &3_600u64);
+
+    let proposal_id = passed_proposal_with_actions(&env, &client, &token);
+    let entry_ids = client.queue_proposal(&proposal_id);
+    timelock.set_status(&entry_ids.get(0).unwrap(), &TimelockStatus::Executed);
+    timelock.set_status(&entry_ids.get(1).unwrap(), &TimelockStatus::Expired);
+
+    assert!(matches!(client.sync_execution(&proposal_id), ProposalStatus::Expired));
+    assert!(matches!(client.get_proposal(&proposal_id).unwrap().status, ProposalStatus::Expired));
+}
+
+#[test]
+#[should_panic(expected = "proposal not passed")]
+fn test_queue_active_proposal_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, _) = setup(&env);
+    let timelock_id = env.register_contract(None, MockTimelock);
+    client.set_timelock(&admin, &timelock_id, &3_600u64);
+    let proposer = Address::generate(&env);
+
+    let proposal_id = client.create_proposal(
+        &proposer, &make_title(&env), &make_desc(&env), &make_actions(&env),
+    );
+    client.queue_proposal(&proposal_id);
+}
+
+#[test]
+#[should_panic(expected = "proposal has no actions")]
+fn test_queue_proposal_without_actions_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_with_mock_token(&env, 1_000);
+    let timelock_id = env.register_contract(None, MockTimelock);
+    client.set_timelock(&admin, &timelock_id, &3_600u64);
+
+    let proposer = Address::generate(&env);
+    let voter = Address::generate(&env);
+    let proposal_id = client.create_proposal(
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
+    );
+    set_power(&env, &token, &voter, 200);
+    client.cast_vote(&voter, &proposal_id, &VoteChoice::For);
+    env.ledger().with_mut(|li| {
+        li.sequence_number = 200;
+    });
+    client.finalize_proposal(&proposal_id);
+    client.queue_proposal(&proposal_id);
+}
+
+#[test]
+#[should_panic(expected = "proposal has actions: use queue_proposal")]
+fn test_admin_cannot_shortcut_proposal_with_actions() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, token) = setup_with_mock_token(&env, 1_000);
+
+    let proposal_id = passed_proposal_with_actions(&env, &client, &token);
+    client.execute_proposal(&admin, &proposal_id);
+}
+
 // ─── cancel_proposal ─────────────────────────────────────────────────────────
 
 #[test]
@@ -500,7 +752,7 @@ fn test_cancel_proposal_by_proposer() {
     let proposer = Address::generate(&env);
 
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
     client.cancel_proposal(&proposer, &proposal_id);
@@ -517,7 +769,7 @@ fn test_cancel_proposal_by_admin() {
     let proposer = Address::generate(&env);
 
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
     client.cancel_proposal(&admin, &proposal_id);
@@ -536,7 +788,7 @@ fn test_cancel_proposal_by_stranger_fails() {
     let stranger = Address::generate(&env);
 
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
     client.cancel_proposal(&stranger, &proposal_id);
@@ -553,7 +805,7 @@ fn test_has_voted_false_before_vote() {
     let voter = Address::generate(&env);
 
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
     assert!(!client.has_voted(&proposal_id, &voter));
@@ -568,7 +820,7 @@ fn test_get_vote_none_before_voting() {
     let voter = Address::generate(&env);
 
     let proposal_id = client.create_proposal(
-        &proposer, &make_title(&env), &make_desc(&env), &None,
+        &proposer, &make_title(&env), &make_desc(&env), &no_actions(&env),
     );
 
     assert!(client.get_vote(&proposal_id, &voter).is_none());
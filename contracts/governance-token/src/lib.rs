@@ -5,20 +5,30 @@
 #![no_std]
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short,
-    Address, Env, String,
+    Address, Env, String, Vec,
 };
 
 // ============================================================
 // Data Types
 // ============================================================
 
+/// Share of a delegator's balance whose votes go to `delegate`.
 #[contracttype]
 #[derive(Clone)]
 pub struct Delegation {
     pub delegate: Address,
+    pub bps: u32,
     pub delegated_at: u64,
 }
 
+/// Voting power of an account from `ledger` onwards.
+#[contracttype]
+#[derive(Clone)]
+pub struct VotingCheckpoint {
+    pub ledger: u32,
+    pub votes: i128,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct TokenMetadata {
@@ -39,11 +49,16 @@ pub enum DataKey {
     Metadata,
     Balance(Address),
     Allowance(Address, Address),
-    Delegation(Address),
-    VotingSnapshot(Address, u32), // Address, ledger_sequence
+    Delegation(Address),       // delegator -> Vec<Delegation>
+    DelegatedVotes(Address, u32), // delegate, hops travelled -> votes received
+    Delegators(Address),       // delegate -> Vec<Address>
+    CheckpointCount(Address),
+    VotingSnapshot(Address, u32), // Address, checkpoint index
 }
 
 pub const MAX_SUPPLY: i128 = 1_000_000_000_000; // 1M tokens with 6 decimals
+pub const MAX_DELEGATES: u32 = 10;
+pub const MAX_DELEGATION_DEPTH: u32 = 3; // hops delegated votes may travel
 
 // ============================================================
 // Contract
@@ -147,6 +162,7 @@ impl GovernanceTokenContract {
         env.storage()
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        Self::_sync_votes(&env, &from, from_balance);
 
         let to_balance: i128 = env
             .storage()
@@ -160,6 +176,7 @@ impl GovernanceTokenContract {
         env.storage()
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        Self::_sync_votes(&env, &to, to_balance);
 
         env.events().publish(
             (symbol_short!("transfer"),),
@@ -206,6 +223,7 @@ impl GovernanceTokenContract {
         env.storage()
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        Self::_sync_votes(&env, &from, from_balance);
 
         let to_balance: i128 = env
             .storage()
@@ -219,6 +237,7 @@ impl GovernanceTokenContract {
         env.storage()
             .persistent()
             .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        Self::_sync_votes(&env, &to, to_balance);
     }
 
     /// Approve token spending
@@ -267,7 +286,7 @@ impl GovernanceTokenContract {
             .persistent()
             .get(&DataKey::Balance(recipient.clone()))
             .unwrap_or(0);
-        let _ttl_key = DataKey::Balance(recipient);
+        let _ttl_key = DataKey::Balance(recipient.clone());
         env.storage()
             .persistent()
             .set(&_ttl_key, &(balance + amount));
@@ -277,6 +296,8 @@ impl GovernanceTokenContract {
         env.storage()
             .instance()
             .set(&DataKey::TotalSupply, &(current_supply + amount));
+
+        Self::_sync_votes(&env, &recipient, balance);
     }
 
     /// Burn tokens
@@ -294,7 +315,7 @@ impl GovernanceTokenContract {
             panic!("insufficient balance");
         }
 
-        let _ttl_key = DataKey::Balance(from);
+        let _ttl_key = DataKey::Balance(from.clone());
         env.storage()
             .persistent()
             .set(&_ttl_key, &(balance - amount));
@@ -310,66 +331,394 @@ impl GovernanceTokenContract {
         env.storage()
             .instance()
             .set(&DataKey::TotalSupply, &(supply - amount));
+
+        Self::_sync_votes(&env, &from, balance);
     }
 
-    /// Delegate voting power
-    pub fn delegate(env: Env, delegator: Address, delegate_to: Address) {
+    /// Delegate `bps` of the delegator's balance, and of the votes delegated
+    /// to it, to `delegate_to`, replacing any earlier share given to the same
+    /// delegate. Shares across all delegates may not exceed 100%; the
+    /// remainder stays with the delegator.
+    pub fn delegate(env: Env, delegator: Address, delegate_to: Address, bps: u32) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         delegator.require_auth();
 
+        if bps == 0 || bps > 10_000 {
+            panic!("invalid bps");
+        }
+        if delegator == delegate_to {
+            panic!("cannot delegate to self");
+        }
+        Self::_check_no_cycle(&env, &delegator, &delegate_to);
+
+        let mut delegations = Self::_delegations_of(&env, &delegator);
+        let mut total_bps = bps;
+        let mut existing: Option<u32> = None;
+        for (i, d) in delegations.iter().enumerate() {
+            if d.delegate == delegate_to {
+                existing = Some(i as u32);
+            } else {
+                total_bps += d.bps;
+            }
+        }
+        if total_bps > 10_000 {
+            panic!("delegation exceeds 100%");
+        }
+
         let delegation = Delegation {
             delegate: delegate_to.clone(),
+            bps,
             delegated_at: env.ledger().timestamp(),
         };
+        let old_bps = match existing {
+            Some(i) => {
+                let old = delegations.get(i).unwrap();
+                delegations.set(i, delegation);
+                old.bps
+            }
+            None => {
+                if delegations.len() >= MAX_DELEGATES {
+                    panic!("too many delegates");
+                }
+                delegations.push_back(delegation);
+                let mut delegators = Self::get_delegators(env.clone(), delegate_to.clone());
+                delegators.push_back(delegator.clone());
+                Self::_set_delegators(&env, &delegate_to, &delegators);
+                0
+            }
+        };
+        Self::_set_delegations(&env, &delegator, &delegations);
 
-        let _ttl_key = DataKey::Delegation(delegator.clone());
-        env.storage()
-            .persistent()
-            .set(&_ttl_key, &delegation);
-        env.storage()
-            .persistent()
-            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        for hop in 0..MAX_DELEGATION_DEPTH {
+            let votes = Self::_votes_at_hop(&env, &delegator, hop);
+            let delta = Self::_portion(votes, bps) - Self::_portion(votes, old_bps);
+            Self::_add_delegated_votes(&env, &delegate_to, hop + 1, delta);
+        }
+        Self::_checkpoint(&env, &delegator);
 
         env.events().publish(
             (symbol_short!("delegate"),),
+            (delegator, delegate_to, bps),
+        );
+    }
+
+    /// Withdraw the share delegated to a single delegate
+    pub fn undelegate(env: Env, delegator: Address, delegate_to: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        delegator.require_auth();
+
+        let mut delegations = Self::_delegations_of(&env, &delegator);
+        let index = delegations
+            .iter()
+            .position(|d| d.delegate == delegate_to)
+            .expect("delegation not found") as u32;
+        let removed = delegations.get(index).unwrap();
+        delegations.remove(index);
+        Self::_set_delegations(&env, &delegator, &delegations);
+        Self::_release_delegate(&env, &delegator, &removed);
+
+        env.events().publish(
+            (symbol_short!("undeleg"),),
             (delegator, delegate_to),
         );
     }
 
-    /// Revoke delegation
+    /// Revoke every delegation
     pub fn revoke_delegation(env: Env, delegator: Address) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         delegator.require_auth();
+
+        let delegations = Self::_delegations_of(&env, &delegator);
         env.storage()
             .persistent()
-            .remove(&DataKey::Delegation(delegator));
+            .remove(&DataKey::Delegation(delegator.clone()));
+        for d in delegations.iter() {
+            Self::_release_delegate(&env, &delegator, &d);
+        }
     }
 
-    /// Get voting power (0 if delegated)
+    /// Get voting power: the undelegated part of the balance and of the votes
+    /// delegated to this account. Delegated votes pass along a chain of
+    /// delegates for up to `MAX_DELEGATION_DEPTH` hops and stay with the
+    /// account they reach last.
     pub fn voting_power(env: Env, voter: Address) -> i128 {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        let delegation = env
+        Self::_current_votes(&env, &voter)
+    }
+
+    /// Voting power held at the start of `ledger`, i.e. after every change
+    /// made in earlier ledgers. Changes within `ledger` itself do not count,
+    /// so the value can no longer move once that ledger is reached.
+    pub fn voting_power_at(env: Env, voter: Address, ledger: u32) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        if ledger > env.ledger().sequence() {
+            panic!("future ledger");
+        }
+
+        let count: u32 = env
             .storage()
             .persistent()
-            .get::<DataKey, Delegation>(&DataKey::Delegation(voter.clone()));
+            .get(&DataKey::CheckpointCount(voter.clone()))
+            .unwrap_or(0);
 
-        if delegation.is_some() {
-            // Delegated - no direct voting power
+        // Binary search for the first checkpoint written at or after `ledger`
+        let mut lo = 0u32;
+        let mut hi = count;
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            if Self::_get_checkpoint(&env, &voter, mid).ledger < ledger {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+
+        if lo == 0 {
             0
         } else {
-            env.storage()
-                .persistent()
-                .get(&DataKey::Balance(voter))
-                .unwrap_or(0)
+            Self::_get_checkpoint(&env, &voter, lo - 1).votes
         }
     }
 
+    pub fn get_checkpoint_count(env: Env, account: Address) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::CheckpointCount(account))
+            .unwrap_or(0)
+    }
+
+    pub fn get_checkpoint(env: Env, account: Address, index: u32) -> Option<VotingCheckpoint> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .persistent()
+            .get(&DataKey::VotingSnapshot(account, index))
+    }
+
     /// Get delegation info
-    pub fn get_delegation(env: Env, delegator: Address) -> Option<Delegation> {
+    pub fn get_delegations(env: Env, delegator: Address) -> Vec<Delegation> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_delegations_of(&env, &delegator)
+    }
+
+    pub fn get_delegators(env: Env, delegate: Address) -> Vec<Address> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage()
             .persistent()
-            .get(&DataKey::Delegation(delegator))
+            .get(&DataKey::Delegators(delegate))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Votes received by `delegate`, directly or through other delegates
+    pub fn get_delegated_votes(env: Env, delegate: Address) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let mut received = 0;
+        for hop in 1..=MAX_DELEGATION_DEPTH {
+            received += Self::_votes_at_hop(&env, &delegate, hop);
+        }
+        received
+    }
+
+    // ============================================================
+    // Internal Helpers
+    // ============================================================
+
+    fn _balance_of(env: &Env, account: &Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Balance(account.clone()))
+            .unwrap_or(0)
+    }
+
+    fn _delegations_of(env: &Env, delegator: &Address) -> Vec<Delegation> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Delegation(delegator.clone()))
+            .unwrap_or(Vec::new(env))
+    }
+
+    fn _set_delegations(env: &Env, delegator: &Address, delegations: &Vec<Delegation>) {
+        let _ttl_key = DataKey::Delegation(delegator.clone());
+        if delegations.is_empty() {
+            env.storage().persistent().remove(&_ttl_key);
+            return;
+        }
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, delegations);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    fn _set_delegators(env: &Env, delegate: &Address, delegators: &Vec<Address>) {
+        let _ttl_key = DataKey::Delegators(delegate.clone());
+        if delegators.is_empty() {
+            env.storage().persistent().remove(&_ttl_key);
+            return;
+        }
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, delegators);
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    /// Part of `balance` carried by a `bps` delegation. Rounding remainders
+    /// stay with the delegator so no votes are created or lost.
+    fn _portion(balance: i128, bps: u32) -> i128 {
+        balance * bps as i128 / 10_000
+    }
+
+    /// Votes held by `account` that have travelled `hop` delegation hops;
+    /// hop 0 is its own balance.
+    fn _votes_at_hop(env: &Env, account: &Address, hop: u32) -> i128 {
+        if hop == 0 {
+            return Self::_balance_of(env, account);
+        }
+        env.storage()
+            .persistent()
+            .get(&DataKey::DelegatedVotes(account.clone(), hop))
+            .unwrap_or(0)
+    }
+
+    /// Credit `delta` votes arriving at `delegate` after `hop` hops and pass
+    /// the delegated share of them on to its own delegates.
+    fn _add_delegated_votes(env: &Env, delegate: &Address, hop: u32, delta: i128) {
+        if delta == 0 {
+            return;
+        }
+        let received = Self::_votes_at_hop(env, delegate, hop);
+        let _ttl_key = DataKey::DelegatedVotes(delegate.clone(), hop);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &(received + delta));
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        if hop < MAX_DELEGATION_DEPTH {
+            for d in Self::_delegations_of(env, delegate).iter() {
+                let onward = Self::_portion(received + delta, d.bps) - Self::_portion(received, d.bps);
+                Self::_add_delegated_votes(env, &d.delegate, hop + 1, onward);
+            }
+        }
+        Self::_checkpoint(env, delegate);
+    }
+
+    /// Panics if `delegator` can be reached from `delegate_to` within
+    /// `MAX_DELEGATION_DEPTH` hops, so votes never flow back to their owner.
+    fn _check_no_cycle(env: &Env, delegator: &Address, delegate_to: &Address) {
+        let mut frontier: Vec<Address> = Vec::new(env);
+        frontier.push_back(delegate_to.clone());
+        for _ in 0..MAX_DELEGATION_DEPTH {
+            let mut next: Vec<Address> = Vec::new(env);
+            for account in frontier.iter() {
+                for d in Self::_delegations_of(env, &account).iter() {
+                    if d.delegate == *delegator {
+                        panic!("delegation cycle");
+                    }
+                    next.push_back(d.delegate);
+                }
+            }
+            frontier = next;
+        }
+    }
+
+    /// Return the votes a removed delegation carried to the delegator and
+    /// drop the delegator from the delegate's listing.
+    fn _release_delegate(env: &Env, delegator: &Address, removed: &Delegation) {
+        let mut delegators = Self::get_delegators(env.clone(), removed.delegate.clone());
+        if let Some(i) = delegators.iter().position(|a| a == *delegator) {
+            delegators.remove(i as u32);
+        }
+        Self::_set_delegators(env, &removed.delegate, &delegators);
+
+        for hop in 0..MAX_DELEGATION_DEPTH {
+            let votes = Self::_votes_at_hop(env, delegator, hop);
+            Self::_add_delegated_votes(env, &removed.delegate, hop + 1, -Self::_portion(votes, removed.bps));
+        }
+        Self::_checkpoint(env, delegator);
+    }
+
+    /// Move the delegated share of a balance change to the delegates, then
+    /// checkpoint the account itself.
+    fn _sync_votes(env: &Env, account: &Address, old_balance: i128) {
+        let new_balance = Self::_balance_of(env, account);
+        for d in Self::_delegations_of(env, account).iter() {
+            let delta = Self::_portion(new_balance, d.bps) - Self::_portion(old_balance, d.bps);
+            Self::_add_delegated_votes(env, &d.delegate, 1, delta);
+        }
+        Self::_checkpoint(env, account);
+    }
+
+    /// Votes at each hop minus the shares of them delegated onwards. Votes
+    /// at the last hop are never delegated.
+    fn _current_votes(env: &Env, account: &Address) -> i128 {
+        let delegations = Self::_delegations_of(env, account);
+        let mut votes = 0;
+        for hop in 0..=MAX_DELEGATION_DEPTH {
+            let held = Self::_votes_at_hop(env, account, hop);
+            votes += held;
+            if hop < MAX_DELEGATION_DEPTH {
+                for d in delegations.iter() {
+                    votes -= Self::_portion(held, d.bps);
+                }
+            }
+        }
+        votes
+    }
+
+    fn _get_checkpoint(env: &Env, account: &Address, index: u32) -> VotingCheckpoint {
+        env.storage()
+            .persistent()
+            .get(&DataKey::VotingSnapshot(account.clone(), index))
+            .unwrap()
+    }
+
+    /// Record the account's current voting power if it changed. Several
+    /// changes within one ledger share a single checkpoint.
+    fn _checkpoint(env: &Env, account: &Address) {
+        let votes = Self::_current_votes(env, account);
+        let ledger = env.ledger().sequence();
+        let count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CheckpointCount(account.clone()))
+            .unwrap_or(0);
+
+        let index = if count > 0 {
+            let last = Self::_get_checkpoint(env, account, count - 1);
+            if last.ledger == ledger {
+                count - 1
+            } else if last.votes == votes {
+                return;
+            } else {
+                count
+            }
+        } else if votes == 0 {
+            return;
+        } else {
+            0
+        };
+
+        let _ttl_key = DataKey::VotingSnapshot(account.clone(), index);
+        env.storage()
+            .persistent()
+            .set(&_ttl_key, &VotingCheckpoint { ledger, votes });
+        env.storage()
+            .persistent()
+            .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        if index == count {
+            let _ttl_key = DataKey::CheckpointCount(account.clone());
+            env.storage()
+                .persistent()
+                .set(&_ttl_key, &(count + 1));
+            env.storage()
+                .persistent()
+                .extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        }
     }
 }
 
@@ -1,6 +1,6 @@
 #![cfg(test)]
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use soroban_sdk::{testutils::{Address as _, Ledger}, Address, Env, String};
 
 fn setup(env: &Env) -> (GovernanceTokenContractClient, Address) {
     let admin = Address::generate(env);
@@ -84,11 +84,12 @@ fn test_delegate() {
     let delegator = Address::generate(&env);
     let delegate = Address::generate(&env);
     c.mint(&admin, &delegator, &1_000i128);
-    c.delegate(&delegator, &delegate);
-    let d = c.get_delegation(&delegator).unwrap();
+    c.delegate(&delegator, &delegate, &10_000u32);
+    let d = c.get_delegations(&delegator).get(0).unwrap();
     assert_eq!(d.delegate, delegate);
-    // Delegator loses voting power after delegating
+    // Voting power moves from the delegator to the delegate
     assert_eq!(c.voting_power(&delegator), 0);
+    assert_eq!(c.voting_power(&delegate), 1_000);
 }
 
 #[test]
@@ -98,9 +99,12 @@ fn test_revoke_delegation() {
     let delegator = Address::generate(&env);
     let delegate = Address::generate(&env);
     c.mint(&admin, &delegator, &1_000i128);
-    c.delegate(&delegator, &delegate);
+    c.delegate(&delegator, &delegate, &10_000u32);
     c.revoke_delegation(&delegator);
-    assert!(c.get_delegation(&delegator).is_none());
+    assert!(c.get_delegations(&delegator).is_empty());
+    assert!(c.get_delegators(&delegate).is_empty());
+    assert_eq!(c.voting_power(&delegator), 1_000);
+    assert_eq!(c.voting_power(&delegate), 0);
 }
 
 #[test]
@@ -129,3 +133,237 @@ fn test_balance_zero() {
     let (c, _) = setup(&env);
     assert_eq!(c.balance(&Address::generate(&env)), 0);
 }
+
+fn set_ledger(env: &Env, sequence: u32) {
+    env.ledger().with_mut(|li| { li.sequence_number = sequence; });
+}
+
+#[test]
+fn test_voting_power_at_tracks_history() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    set_ledger(&env, 10);
+    c.mint(&admin, &alice, &1_000i128);
+    set_ledger(&env, 20);
+    c.transfer(&alice, &bob, &400i128);
+    set_ledger(&env, 30);
+
+    assert_eq!(c.voting_power_at(&alice, &5u32), 0);
+    assert_eq!(c.voting_power_at(&alice, &10u32), 0);   // minted during ledger 10
+    assert_eq!(c.voting_power_at(&alice, &11u32), 1_000);
+    assert_eq!(c.voting_power_at(&alice, &20u32), 1_000);
+    assert_eq!(c.voting_power_at(&alice, &21u32), 600);
+    assert_eq!(c.voting_power_at(&bob, &20u32), 0);
+    assert_eq!(c.voting_power_at(&bob, &30u32), 400);
+}
+
+#[test]
+fn test_transfer_after_snapshot_does_not_double_count() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    set_ledger(&env, 10);
+    c.mint(&admin, &alice, &1_000i128);
+    set_ledger(&env, 50); // proposal snapshot ledger
+    c.transfer(&alice, &bob, &1_000i128);
+
+    assert_eq!(c.voting_power_at(&alice, &50u32), 1_000);
+    assert_eq!(c.voting_power_at(&bob, &50u32), 0);
+}
+
+#[test]
+fn test_same_ledger_changes_share_checkpoint() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    let alice = Address::generate(&env);
+
+    set_ledger(&env, 10);
+    c.mint(&admin, &alice, &1_000i128);
+    c.burn(&alice, &300i128);
+    set_ledger(&env, 11);
+
+    assert_eq!(c.get_checkpoint_count(&alice), 1);
+    assert_eq!(c.get_checkpoint(&alice, &0u32).unwrap().votes, 700);
+    assert_eq!(c.voting_power_at(&alice, &11u32), 700);
+}
+
+#[test]
+fn test_delegation_checkpoints_delegator() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    set_ledger(&env, 10);
+    c.mint(&admin, &alice, &1_000i128);
+    set_ledger(&env, 20);
+    c.delegate(&alice, &bob, &10_000u32);
+    set_ledger(&env, 30);
+    c.revoke_delegation(&alice);
+    set_ledger(&env, 40);
+
+    assert_eq!(c.voting_power_at(&alice, &15u32), 1_000);
+    assert_eq!(c.voting_power_at(&alice, &25u32), 0);
+    assert_eq!(c.voting_power_at(&alice, &35u32), 1_000);
+    assert_eq!(c.voting_power_at(&bob, &25u32), 1_000);
+    assert_eq!(c.voting_power_at(&bob, &35u32), 0);
+}
+
+#[test]
+#[should_panic(expected = "future ledger")]
+fn test_voting_power_at_future_ledger() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _) = setup(&env);
+    set_ledger(&env, 10);
+    c.voting_power_at(&Address::generate(&env), &11u32);
+}
+
+#[test]
+fn test_partial_delegation_across_delegates() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    let holder = Address::generate(&env);
+    let lead_a = Address::generate(&env);
+    let lead_b = Address::generate(&env);
+    c.mint(&admin, &holder, &1_000i128);
+
+    c.delegate(&holder, &lead_a, &6_000u32);
+    c.delegate(&holder, &lead_b, &2_500u32);
+
+    assert_eq!(c.voting_power(&lead_a), 600);
+    assert_eq!(c.voting_power(&lead_b), 250);
+    assert_eq!(c.voting_power(&holder), 150);
+    assert_eq!(c.get_delegations(&holder).len(), 2);
+
+    // Re-delegating to the same lead replaces its share
+    c.delegate(&holder, &lead_a, &1_000u32);
+    assert_eq!(c.voting_power(&lead_a), 100);
+    assert_eq!(c.voting_power(&holder), 650);
+    assert_eq!(c.get_delegators(&lead_a).len(), 1);
+}
+
+#[test]
+fn test_delegated_votes_follow_balance_changes() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    let holder = Address::generate(&env);
+    let lead = Address::generate(&env);
+    let other = Address::generate(&env);
+    c.mint(&admin, &holder, &1_000i128);
+    c.delegate(&holder, &lead, &5_000u32);
+
+    c.transfer(&holder, &other, &400i128);
+    assert_eq!(c.voting_power(&lead), 300);
+    c.mint(&admin, &holder, &1_000i128);
+    assert_eq!(c.voting_power(&lead), 800);
+    c.burn(&holder, &1_600i128);
+    assert_eq!(c.voting_power(&lead), 0);
+    assert_eq!(c.voting_power(&holder), 0);
+    assert_eq!(c.voting_power(&other), 400);
+}
+
+#[test]
+fn test_delegate_accumulates_from_several_delegators() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    let lead = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    c.mint(&admin, &lead, &100i128);
+    c.mint(&admin, &alice, &1_000i128);
+    c.mint(&admin, &bob, &500i128);
+
+    c.delegate(&alice, &lead, &10_000u32);
+    c.delegate(&bob, &lead, &10_000u32);
+    assert_eq!(c.voting_power(&lead), 1_600);
+    assert_eq!(c.get_delegated_votes(&lead), 1_500);
+
+    let delegators = c.get_delegators(&lead);
+    assert_eq!(delegators.len(), 2);
+    assert!(delegators.contains(&alice) && delegators.contains(&bob));
+
+    c.undelegate(&alice, &lead);
+    assert_eq!(c.voting_power(&lead), 600);
+    assert_eq!(c.voting_power(&alice), 1_000);
+    assert_eq!(c.get_delegators(&lead).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "delegation exceeds 100%")]
+fn test_delegation_over_100_percent_rejected() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _) = setup(&env);
+    let holder = Address::generate(&env);
+    c.delegate(&holder, &Address::generate(&env), &7_000u32);
+    c.delegate(&holder, &Address::generate(&env), &3_001u32);
+}
+
+#[test]
+fn test_delegated_votes_pass_along_chain() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let lead = Address::generate(&env);
+    c.mint(&admin, &a, &1_000i128);
+    c.mint(&admin, &b, &200i128);
+
+    // A -> B first, then B -> C: A's votes follow B's delegation
+    c.delegate(&a, &b, &10_000u32);
+    c.delegate(&b, &lead, &5_000u32);
+    assert_eq!(c.voting_power(&a), 0);
+    assert_eq!(c.voting_power(&b), 600);
+    assert_eq!(c.voting_power(&lead), 600);
+    assert_eq!(c.get_delegated_votes(&lead), 600);
+
+    // Balance changes at the start of the chain reach its end
+    c.mint(&admin, &a, &1_000i128);
+    assert_eq!(c.voting_power(&b), 1_100);
+    assert_eq!(c.voting_power(&lead), 1_100);
+
+    // Breaking the chain in the middle returns the votes to B
+    c.undelegate(&b, &lead);
+    assert_eq!(c.voting_power(&b), 2_200);
+    assert_eq!(c.voting_power(&lead), 0);
+}
+
+#[test]
+fn test_delegated_votes_stop_at_max_depth() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    let chain = [
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    c.mint(&admin, &chain[0], &1_000i128);
+    for pair in chain.windows(2) {
+        c.delegate(&pair[0], &pair[1], &10_000u32);
+    }
+
+    // Three hops take the votes to chain[3], which keeps them
+    assert_eq!(c.voting_power(&chain[3]), 1_000);
+    assert_eq!(c.voting_power(&chain[4]), 0);
+    let total: i128 = chain.iter().map(|a| c.voting_power(a)).sum();
+    assert_eq!(total, 1_000);
+}
+
+#[test]
+#[should_panic(expected = "delegation cycle")]
+fn test_delegation_cycle_rejected() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _) = setup(&env);
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let lead = Address::generate(&env);
+    c.delegate(&a, &b, &10_000u32);
+    c.delegate(&b, &lead, &10_000u32);
+    c.delegate(&lead, &a, &10_000u32);
+}
@@ -1,10 +1,31 @@
 //! PulsarTrack - Liquidity Pool (Soroban)
 //! Ad budget liquidity pool for campaign funding on Stellar.
+//!
+//! Borrows accrue interest every second against a global borrow index. The
+//! annual rate follows a kinked utilization curve: it rises gently up to the
+//! optimal utilization and steeply beyond it. `reserve_factor` percent of the
+//! interest is set aside as reserves, which the admin sweeps to the treasury;
+//! the rest is added to `total_liquidity`, so every LP share is worth more.
+//!
+//! A borrow still open a grace period after `due_at` can be marked in default
+//! by anyone. The campaign is paused through campaign-lifecycle, the
+//! borrower's open escrows and unspent orchestrator budget for the campaign
+//! are claimed as collateral, and any shortfall is written off against
+//! `total_liquidity`, shared by all LPs through the share price. The part of
+//! the shortfall that was booked as reserves is written off the reserves.
+//! When campaign-lifecycle is configured, only a campaign's advertiser may
+//! borrow against it.
+//!
+//! # Events
+//! - ("pool", "deposit"): [provider: Address, amount: i128, shares: i128]
+//! - ("pool", "repaid"): [campaign_id: u64, principal: i128, interest: i128]
+//! - ("pool", "reserves"): [treasury: Address, amount: i128]
+//! - ("pool", "default"): [campaign_id: u64, debt: i128, recovered: i128, loss: i128]
 
 #![no_std]
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short,
-    token, Address, Env,
+    token, vec, Address, Env, IntoVal, Symbol,
 };
 
 #[contracttype]
@@ -16,6 +37,18 @@ pub struct PoolState {
     pub utilization_rate: u32, // percentage borrowed
     pub borrow_rate_bps: u32,  // annual rate in basis points
     pub last_updated: u64,
+    pub borrow_index: i128,    // scaled by INDEX_SCALE
+    pub total_reserves: i128,
+}
+
+/// Kinked utilization curve, all values in basis points.
+#[contracttype]
+#[derive(Clone)]
+pub struct RateModel {
+    pub base_rate_bps: u32,
+    pub slope1_bps: u32,
+    pub slope2_bps: u32,
+    pub optimal_utilization_bps: u32,
 }
 
 #[contracttype]
@@ -37,6 +70,18 @@ pub struct BorrowPosition {
     pub interest_accrued: i128,
     pub borrowed_at: u64,
     pub due_at: u64,
+    pub index_at_borrow: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct DefaultRecord {
+    pub borrower: Address,
+    pub campaign_id: u64,
+    pub debt: i128,
+    pub recovered: i128,
+    pub loss: i128,
+    pub defaulted_at: u64,
 }
 
 #[contracttype]
@@ -48,12 +93,23 @@ pub enum DataKey {
     Provider(Address),
     Borrow(u64),        // campaign_id
     BorrowCount,
+    RateModel,
+    Treasury,
+    DefaultGracePeriod,
+    CampaignLifecycle,
+    EscrowVault,
+    Orchestrator,
+    Default(u64), // campaign_id
 }
 
 const INSTANCE_LIFETIME_THRESHOLD: u32 = 17_280;
 const INSTANCE_BUMP_AMOUNT: u32 = 86_400;
 const PERSISTENT_LIFETIME_THRESHOLD: u32 = 120_960;
 const PERSISTENT_BUMP_AMOUNT: u32 = 1_051_200;
+const INDEX_SCALE: i128 = 1_000_000_000_000;
+const YEAR_SECS: i128 = 365 * 24 * 3600;
+const BPS: i128 = 10_000;
+const DEFAULT_GRACE_PERIOD_SECS: u64 = 604_800; // 7 days
 
 #[contract]
 pub struct LiquidityPoolContract;
@@ -74,9 +130,96 @@ impl LiquidityPoolContract {
             total_borrowed: 0,
             reserve_factor: 10,
             utilization_rate: 0,
-            borrow_rate_bps: 500, // 5% annual
+            borrow_rate_bps: 200, // base rate at zero utilization
             last_updated: env.ledger().timestamp(),
+            borrow_index: INDEX_SCALE,
+            total_reserves: 0,
+        });
+        env.storage().instance().set(&DataKey::RateModel, &RateModel {
+            base_rate_bps: 200,
+            slope1_bps: 400,
+            slope2_bps: 6_000,
+            optimal_utilization_bps: 8_000,
         });
+        env.storage().instance().set(&DataKey::Treasury, &admin);
+    }
+
+    pub fn set_rate_model(env: Env, admin: Address, model: RateModel) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        if model.optimal_utilization_bps == 0 || model.optimal_utilization_bps >= BPS as u32 {
+            panic!("invalid rate model");
+        }
+        // Accrue at the old rate before switching curves
+        let mut pool = Self::_accrue(&env);
+        env.storage().instance().set(&DataKey::RateModel, &model);
+        pool.borrow_rate_bps = Self::_borrow_rate(&env, &pool);
+        env.storage().instance().set(&DataKey::PoolState, &pool);
+    }
+
+    pub fn set_treasury(env: Env, admin: Address, treasury: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::Treasury, &treasury);
+    }
+
+    pub fn set_reserve_factor(env: Env, admin: Address, reserve_factor: u32) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        if reserve_factor > 100 {
+            panic!("invalid reserve factor");
+        }
+        let mut pool = Self::_accrue(&env);
+        pool.reserve_factor = reserve_factor;
+        env.storage().instance().set(&DataKey::PoolState, &pool);
+    }
+
+    pub fn set_default_grace_period(env: Env, admin: Address, grace_secs: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::DefaultGracePeriod, &grace_secs);
+    }
+
+    pub fn set_campaign_lifecycle(env: Env, admin: Address, lifecycle: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::CampaignLifecycle, &lifecycle);
+    }
+
+    pub fn set_escrow_vault(env: Env, admin: Address, escrow_vault: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::EscrowVault, &escrow_vault);
+    }
+
+    pub fn set_orchestrator(env: Env, admin: Address, orchestrator: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::Orchestrator, &orchestrator);
+    }
+
+    /// Sweep accrued reserves to the treasury, as far as idle cash allows.
+    pub fn collect_reserves(env: Env) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let mut pool = Self::_accrue(&env);
+
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        let token_client = token::Client::new(&env, &token_addr);
+        let amount = pool.total_reserves.min(token_client.balance(&env.current_contract_address()));
+        if amount <= 0 {
+            panic!("no reserves");
+        }
+        pool.total_reserves -= amount;
+        env.storage().instance().set(&DataKey::PoolState, &pool);
+
+        let treasury: Address = env.storage().instance().get(&DataKey::Treasury).unwrap();
+        token_client.transfer(&env.current_contract_address(), &treasury, &amount);
+
+        env.events().publish(
+            (symbol_short!("pool"), symbol_short!("reserves")),
+            (treasury, amount),
+        );
+        amount
     }
 
     pub fn deposit(env: Env, provider: Address, amount: i128) -> i128 {
@@ -91,7 +234,7 @@ impl LiquidityPoolContract {
         let token_client = token::Client::new(&env, &token_addr);
         token_client.transfer(&provider, &env.current_contract_address(), &amount);
 
-        let mut pool: PoolState = env.storage().instance().get(&DataKey::PoolState).unwrap();
+        let mut pool = Self::_accrue(&env);
         let total_shares: i128 = env.storage().instance().get(&DataKey::TotalShares).unwrap_or(0);
 
         // Calculate shares (1:1 for first deposit)
@@ -102,7 +245,8 @@ impl LiquidityPoolContract {
         };
 
         pool.total_liquidity += amount;
-        pool.last_updated = env.ledger().timestamp();
+        pool.utilization_rate = Self::_utilization_pct(&pool);
+        pool.borrow_rate_bps = Self::_borrow_rate(&env, &pool);
         env.storage().instance().set(&DataKey::PoolState, &pool);
         env.storage().instance().set(&DataKey::TotalShares, &(total_shares + shares));
 
@@ -146,7 +290,7 @@ impl LiquidityPoolContract {
             panic!("insufficient shares");
         }
 
-        let mut pool: PoolState = env.storage().instance().get(&DataKey::PoolState).unwrap();
+        let mut pool = Self::_accrue(&env);
         let total_shares: i128 = env.storage().instance().get(&DataKey::TotalShares).unwrap_or(0);
 
         let amount = (shares * pool.total_liquidity) / total_shares;
@@ -157,7 +301,8 @@ impl LiquidityPoolContract {
         }
 
         pool.total_liquidity -= amount;
-        pool.last_updated = env.ledger().timestamp();
+        pool.utilization_rate = Self::_utilization_pct(&pool);
+        pool.borrow_rate_bps = Self::_borrow_rate(&env, &pool);
         env.storage().instance().set(&DataKey::PoolState, &pool);
         env.storage().instance().set(&DataKey::TotalShares, &(total_shares - shares));
 
@@ -178,7 +323,11 @@ impl LiquidityPoolContract {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         borrower.require_auth();
 
-        let mut pool: PoolState = env.storage().instance().get(&DataKey::PoolState).unwrap();
+        if amount <= 0 {
+            panic!("invalid amount");
+        }
+
+        let mut pool = Self::_accrue(&env);
         let available = pool.total_liquidity - pool.total_borrowed;
 
         if amount > available {
@@ -189,9 +338,21 @@ impl LiquidityPoolContract {
             panic!("already has borrow");
         }
 
+        // Only a campaign's advertiser may borrow against it
+        if let Some(lifecycle) = env.storage().instance().get::<_, Address>(&DataKey::CampaignLifecycle) {
+            let advertiser: Option<Address> = env.invoke_contract(
+                &lifecycle,
+                &Symbol::new(&env, "get_advertiser"),
+                vec![&env, campaign_id.into_val(&env)],
+            );
+            if advertiser != Some(borrower.clone()) {
+                panic!("not campaign advertiser");
+            }
+        }
+
         pool.total_borrowed += amount;
-        pool.utilization_rate = ((pool.total_borrowed * 100) / pool.total_liquidity) as u32;
-        pool.last_updated = env.ledger().timestamp();
+        pool.utilization_rate = Self::_utilization_pct(&pool);
+        pool.borrow_rate_bps = Self::_borrow_rate(&env, &pool);
         env.storage().instance().set(&DataKey::PoolState, &pool);
 
         let now = env.ledger().timestamp();
@@ -202,6 +363,7 @@ impl LiquidityPoolContract {
             interest_accrued: 0,
             borrowed_at: now,
             due_at: now + duration_secs,
+            index_at_borrow: pool.borrow_index,
         };
 
         let _ttl_key = DataKey::Borrow(campaign_id);
@@ -213,7 +375,9 @@ impl LiquidityPoolContract {
         token_client.transfer(&env.current_contract_address(), &borrower, &amount);
     }
 
-    pub fn repay(env: Env, borrower: Address, campaign_id: u64, amount: i128) {
+    /// Repay the full debt. `amount` must cover principal plus accrued
+    /// interest; only the debt is taken. Returns the amount repaid.
+    pub fn repay(env: Env, borrower: Address, campaign_id: u64, amount: i128) -> i128 {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         borrower.require_auth();
 
@@ -227,20 +391,131 @@ impl LiquidityPoolContract {
             panic!("unauthorized");
         }
 
+        let mut pool = Self::_accrue(&env);
+        let debt = Self::_debt(&borrow, pool.borrow_index);
+        if amount < debt {
+            panic!("insufficient repayment");
+        }
+
         let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
         let token_client = token::Client::new(&env, &token_addr);
-        token_client.transfer(&borrower, &env.current_contract_address(), &amount);
+        token_client.transfer(&borrower, &env.current_contract_address(), &debt);
+
+        // Interest already moved into total_liquidity and reserves on accrual
+        pool.total_borrowed -= debt.min(pool.total_borrowed);
+        pool.utilization_rate = Self::_utilization_pct(&pool);
+        pool.borrow_rate_bps = Self::_borrow_rate(&env, &pool);
+        env.storage().instance().set(&DataKey::PoolState, &pool);
+
+        env.storage().persistent().remove(&DataKey::Borrow(campaign_id));
+
+        env.events().publish(
+            (symbol_short!("pool"), symbol_short!("repaid")),
+            (campaign_id, borrow.borrowed, debt - borrow.borrowed),
+        );
+        debt
+    }
+
+    /// Declare an overdue borrow in default, claim collateral and write off
+    /// the shortfall. Callable by anyone once the grace period has passed.
+    pub fn mark_default(env: Env, campaign_id: u64) -> DefaultRecord {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
 
-        let mut pool: PoolState = env.storage().instance().get(&DataKey::PoolState).unwrap();
-        pool.total_borrowed -= amount.min(borrow.borrowed);
-        if pool.total_liquidity > 0 {
-            pool.utilization_rate = ((pool.total_borrowed * 100) / pool.total_liquidity) as u32;
+        let borrow: BorrowPosition = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Borrow(campaign_id))
+            .expect("borrow not found");
+        let grace: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::DefaultGracePeriod)
+            .unwrap_or(DEFAULT_GRACE_PERIOD_SECS);
+        let now = env.ledger().timestamp();
+        if now <= borrow.due_at + grace {
+            panic!("not in default");
         }
-        pool.total_liquidity += amount;
-        pool.last_updated = env.ledger().timestamp();
+
+        let mut pool = Self::_accrue(&env);
+        // Capped at the book so rounding never writes off more than was lent
+        let debt = Self::_debt(&borrow, pool.borrow_index).min(pool.total_borrowed);
+        let this = env.current_contract_address();
+
+        if let Some(lifecycle) = env.storage().instance().get::<_, Address>(&DataKey::CampaignLifecycle) {
+            env.invoke_contract::<()>(
+                &lifecycle,
+                &Symbol::new(&env, "pause_for_default"),
+                vec![
+                    &env,
+                    this.into_val(&env),
+                    campaign_id.into_val(&env),
+                    borrow.borrower.into_val(&env),
+                ],
+            );
+        }
+
+        let mut claimed = 0i128;
+        for key in [DataKey::EscrowVault, DataKey::Orchestrator] {
+            if let Some(source) = env.storage().instance().get::<_, Address>(&key) {
+                claimed += env.invoke_contract::<i128>(
+                    &source,
+                    &Symbol::new(&env, "claim_for_default"),
+                    vec![
+                        &env,
+                        this.into_val(&env),
+                        campaign_id.into_val(&env),
+                        borrow.borrower.into_val(&env),
+                    ],
+                );
+            }
+        }
+
+        // Collateral beyond the debt belongs to the borrower
+        let recovered = claimed.min(debt);
+        if claimed > recovered {
+            let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+            token::Client::new(&env, &token_addr).transfer(&this, &borrow.borrower, &(claimed - recovered));
+        }
+
+        let loss = debt - recovered;
+        // Part of the unpaid interest was booked as reserves; write that
+        // share off with the loss instead of charging it all to providers
+        let reserve_share = (debt - borrow.borrowed).max(0) * pool.reserve_factor as i128 / 100;
+        let reserve_loss = if debt > 0 {
+            (reserve_share * loss / debt).min(pool.total_reserves)
+        } else {
+            0
+        };
+        pool.total_borrowed -= debt;
+        pool.total_reserves -= reserve_loss;
+        pool.total_liquidity -= (loss - reserve_loss).min(pool.total_liquidity);
+        pool.utilization_rate = Self::_utilization_pct(&pool);
+        pool.borrow_rate_bps = Self::_borrow_rate(&env, &pool);
         env.storage().instance().set(&DataKey::PoolState, &pool);
 
         env.storage().persistent().remove(&DataKey::Borrow(campaign_id));
+        let record = DefaultRecord {
+            borrower: borrow.borrower,
+            campaign_id,
+            debt,
+            recovered,
+            loss,
+            defaulted_at: now,
+        };
+        let _ttl_key = DataKey::Default(campaign_id);
+        env.storage().persistent().set(&_ttl_key, &record);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("pool"), symbol_short!("default")),
+            (campaign_id, debt, recovered, loss),
+        );
+        record
+    }
+
+    pub fn get_default(env: Env, campaign_id: u64) -> Option<DefaultRecord> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::Default(campaign_id))
     }
 
     pub fn get_pool_state(env: Env) -> PoolState {
@@ -253,9 +528,95 @@ impl LiquidityPoolContract {
         env.storage().persistent().get(&DataKey::Provider(provider))
     }
 
+    pub fn get_rate_model(env: Env) -> RateModel {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::RateModel).expect("not initialized")
+    }
+
+    /// Borrow position with `interest_accrued` brought up to date.
     pub fn get_borrow(env: Env, campaign_id: u64) -> Option<BorrowPosition> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        env.storage().persistent().get(&DataKey::Borrow(campaign_id))
+        let mut borrow: BorrowPosition = env.storage().persistent().get(&DataKey::Borrow(campaign_id))?;
+        let (index, _) = Self::_current_index(&env);
+        borrow.interest_accrued = Self::_debt(&borrow, index) - borrow.borrowed;
+        Some(borrow)
+    }
+
+    /// Principal plus interest owed right now.
+    pub fn get_debt(env: Env, campaign_id: u64) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let borrow: BorrowPosition = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Borrow(campaign_id))
+            .expect("borrow not found");
+        let (index, _) = Self::_current_index(&env);
+        Self::_debt(&borrow, index)
+    }
+
+    // ── internal helpers ─────────────────────────────────────────────────────
+
+    fn _require_admin(env: &Env, admin: &Address) {
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if *admin != stored_admin {
+            panic!("unauthorized");
+        }
+    }
+
+    /// Borrow index projected to now, alongside the stored pool state
+    fn _current_index(env: &Env) -> (i128, PoolState) {
+        let pool: PoolState = env.storage().instance().get(&DataKey::PoolState).unwrap();
+        let elapsed = env.ledger().timestamp().saturating_sub(pool.last_updated) as i128;
+        let growth = pool.borrow_index * pool.borrow_rate_bps as i128 * elapsed / (BPS * YEAR_SECS);
+        (pool.borrow_index + growth, pool)
+    }
+
+    /// Move the index forward and book interest on the outstanding borrows.
+    /// The caller persists the returned state.
+    fn _accrue(env: &Env) -> PoolState {
+        let (index, mut pool) = Self::_current_index(env);
+        if index > pool.borrow_index && pool.total_borrowed > 0 {
+            let interest = pool.total_borrowed * (index - pool.borrow_index) / pool.borrow_index;
+            let reserve = interest * pool.reserve_factor as i128 / 100;
+            pool.total_borrowed += interest;
+            pool.total_liquidity += interest - reserve;
+            pool.total_reserves += reserve;
+        }
+        pool.borrow_index = index;
+        pool.last_updated = env.ledger().timestamp();
+        pool
+    }
+
+    fn _debt(borrow: &BorrowPosition, index: i128) -> i128 {
+        // Round up so the pool is never short-changed
+        let scaled = borrow.borrowed * index;
+        (scaled + borrow.index_at_borrow - 1) / borrow.index_at_borrow
+    }
+
+    fn _utilization_pct(pool: &PoolState) -> u32 {
+        (Self::_utilization_bps(pool) / 100) as u32
+    }
+
+    fn _utilization_bps(pool: &PoolState) -> i128 {
+        if pool.total_liquidity <= 0 {
+            return 0;
+        }
+        (pool.total_borrowed * BPS / pool.total_liquidity).min(BPS)
+    }
+
+    fn _borrow_rate(env: &Env, pool: &PoolState) -> u32 {
+        let model: RateModel = env.storage().instance().get(&DataKey::RateModel).unwrap();
+        let utilization = Self::_utilization_bps(pool);
+        let kink = model.optimal_utilization_bps as i128;
+        let rate = if utilization <= kink {
+            model.base_rate_bps as i128 + model.slope1_bps as i128 * utilization / kink
+        } else {
+            model.base_rate_bps as i128
+                + model.slope1_bps as i128
+                + model.slope2_bps as i128 * (utilization - kink) / (BPS - kink)
+        };
+        rate as u32
     }
 }
 
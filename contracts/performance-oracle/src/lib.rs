@@ -1,12 +1,38 @@
 //! PulsarTrack - Performance Oracle (Soroban)
 //! Validates and attests to campaign performance metrics on Stellar.
+//!
+//! Attestations are scoped to reporting periods (epochs) of `PeriodSecs`.
+//! An attester may revise its attestation for a campaign until the epoch
+//! ends. Once `MinAttesters` have attested, consensus is the per-metric
+//! median across all attestations of the epoch, and attesters deviating
+//! from it by more than `ToleranceBps` on any metric are flagged as
+//! outliers. Each metric also has an absolute floor (`DeviationFloors`)
+//! below which a difference is never flagged, so small honest readings
+//! against a median near zero are not punished. Consensus is reached when
+//! at least `ConsensusThresholdPct` of the attesters are not outliers.
+//!
+//! Once staking is configured, attesters must keep at least `min_stake` of
+//! the platform token bonded to attest. Unbonding takes `unbonding_secs`, and
+//! stake stays slashable meanwhile. After an epoch closes with consensus,
+//! anyone may slash an outlier by `slash_bps`; the slashed stake joins that
+//! epoch's reward pool. The dispute-resolution contract may also slash an
+//! attester over a specific attestation after a ruling against it; those
+//! proceeds join the same epoch's pool and the attester forfeits its reward.
+//! Campaign fees funded into an epoch's pool (campaign-orchestrator routes a
+//! share of each campaign's platform fee here as its views are paid) are
+//! shared equally by the attesters that agreed with consensus.
+//!
+//! Events:
+//! - ("oracle", "attested"): [campaign_id: u64, epoch: u64]
+//! - ("oracle", "outlier"): [campaign_id: u64, epoch: u64, attester: Address]
+//! - ("oracle", "slashed"): [attester: Address, amount: i128]
+//! - ("oracle", "reward"): [campaign_id: u64, epoch: u64, attester: Address, amount: i128]
 
 #![no_std]
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short,
-    Address, BytesN, Env,
+    token, Address, BytesN, Env, Vec,
 };
-
 #[contracttype]
 #[derive(Clone)]
 pub struct PerformanceAttestation {
@@ -25,30 +51,75 @@ pub struct PerformanceAttestation {
 #[derive(Clone)]
 pub struct OracleConsensus {
     pub campaign_id: u64,
+    pub epoch: u64,
     pub total_attesters: u32,
-    pub avg_impressions: u64,
-    pub avg_clicks: u64,
-    pub avg_fraud_rate: u32,
-    pub avg_quality_score: u32,
+    pub median_impressions: u64,
+    pub median_clicks: u64,
+    pub median_fraud_rate: u32,
+    pub median_quality_score: u32,
+    pub outliers: Vec<Address>,
     pub consensus_reached: bool,
     pub last_updated: u64,
 }
 
+#[contracttype]
+#[derive(Clone)]
+pub struct StakingConfig {
+    pub token: Address,
+    pub min_stake: i128,
+    pub slash_bps: u32,
+    pub unbonding_secs: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct AttesterStake {
+    pub bonded: i128,
+    pub unbonding: i128,
+    pub unbond_at: u64,
+}
+
+/// Per-metric absolute differences that are always tolerated
+#[contracttype]
+#[derive(Clone)]
+pub struct DeviationFloors {
+    pub impressions: u64,
+    pub clicks: u64,
+    pub fraud_rate: u64,    // basis points
+    pub quality_score: u64,
+}
+
 #[contracttype]
 pub enum DataKey {
     Admin,
     MinAttesters,
     ConsensusThresholdPct,
+    PeriodSecs,
+    ToleranceBps,
+    DeviationFloors,
     Attester(Address),
-    Attestation(u64, Address),  // campaign_id, attester
-    AttestationCount(u64),      // campaign_id
-    Consensus(u64),             // campaign_id
+    Attestation(u64, u64, Address), // campaign_id, epoch, attester
+    EpochAttesters(u64, u64),       // campaign_id, epoch
+    EpochConsensus(u64, u64),       // campaign_id, epoch
+    Consensus(u64),                 // campaign_id, latest epoch
+    Staking,
+    DisputeContract,
+    Stake(Address),
+    Slashed(u64, u64, Address),     // campaign_id, epoch, attester
+    RewardPool(u64, u64),           // campaign_id, epoch
+    RewardClaimed(u64, u64, Address),
 }
 
 const INSTANCE_LIFETIME_THRESHOLD: u32 = 17_280;
 const INSTANCE_BUMP_AMOUNT: u32 = 86_400;
 const PERSISTENT_LIFETIME_THRESHOLD: u32 = 120_960;
 const PERSISTENT_BUMP_AMOUNT: u32 = 1_051_200;
+const DEFAULT_PERIOD_SECS: u64 = 86_400;
+const DEFAULT_TOLERANCE_BPS: u32 = 1_000; // 10%
+const DEFAULT_IMPRESSIONS_FLOOR: u64 = 10;
+const DEFAULT_CLICKS_FLOOR: u64 = 5;
+const DEFAULT_FRAUD_RATE_FLOOR: u64 = 50; // 0.5%
+const DEFAULT_QUALITY_SCORE_FLOOR: u64 = 2;
 
 #[contract]
 pub struct PerformanceOracleContract;
@@ -64,20 +135,238 @@ impl PerformanceOracleContract {
         env.storage().instance().set(&DataKey::Admin, &admin);
         env.storage().instance().set(&DataKey::MinAttesters, &min_attesters);
         env.storage().instance().set(&DataKey::ConsensusThresholdPct, &67u32); // 2/3 majority
+        env.storage().instance().set(&DataKey::PeriodSecs, &DEFAULT_PERIOD_SECS);
+        env.storage().instance().set(&DataKey::ToleranceBps, &DEFAULT_TOLERANCE_BPS);
     }
 
     pub fn authorize_attester(env: Env, admin: Address, attester: Address) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        admin.require_auth();
-        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        if admin != stored_admin {
-            panic!("unauthorized");
-        }
+        Self::_require_admin(&env, &admin);
         let _ttl_key = DataKey::Attester(attester);
         env.storage().persistent().set(&_ttl_key, &true);
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
     }
 
+    /// Admin: set the reporting period length. Epoch numbers are derived
+    /// from the ledger timestamp, so changing this renumbers epochs.
+    pub fn set_reporting_period(env: Env, admin: Address, period_secs: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        if period_secs == 0 {
+            panic!("invalid period");
+        }
+        env.storage().instance().set(&DataKey::PeriodSecs, &period_secs);
+    }
+
+    /// Admin: how far, in basis points of the median, an attestation may
+    /// stray before its attester is flagged
+    pub fn set_deviation_tolerance(env: Env, admin: Address, tolerance_bps: u32) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::ToleranceBps, &tolerance_bps);
+    }
+
+    pub fn set_deviation_floors(env: Env, admin: Address, floors: DeviationFloors) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::DeviationFloors, &floors);
+    }
+
+    pub fn set_staking(env: Env, admin: Address, config: StakingConfig) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        if config.min_stake < 0 || config.slash_bps > 10_000 {
+            panic!("invalid config");
+        }
+        env.storage().instance().set(&DataKey::Staking, &config);
+    }
+
+    pub fn set_dispute_contract(env: Env, admin: Address, dispute_contract: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::DisputeContract, &dispute_contract);
+    }
+
+    pub fn stake(env: Env, attester: Address, amount: i128) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        attester.require_auth();
+        if amount <= 0 {
+            panic!("invalid amount");
+        }
+        let config = Self::_staking(&env).expect("staking not configured");
+        token::Client::new(&env, &config.token).transfer(&attester, &env.current_contract_address(), &amount);
+
+        let mut stake = Self::_stake(&env, &attester);
+        stake.bonded += amount;
+        Self::_save_stake(&env, &attester, &stake);
+    }
+
+    /// Start unbonding `amount`. Unbonding stake can still be slashed and
+    /// is withdrawable after the unbonding period.
+    pub fn unstake(env: Env, attester: Address, amount: i128) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        attester.require_auth();
+        let config = Self::_staking(&env).expect("staking not configured");
+        let mut stake = Self::_stake(&env, &attester);
+        if amount <= 0 || amount > stake.bonded {
+            panic!("insufficient stake");
+        }
+        stake.bonded -= amount;
+        stake.unbonding += amount;
+        stake.unbond_at = env.ledger().timestamp() + config.unbonding_secs;
+        Self::_save_stake(&env, &attester, &stake);
+    }
+
+    pub fn withdraw_stake(env: Env, attester: Address) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        attester.require_auth();
+        let config = Self::_staking(&env).expect("staking not configured");
+        let mut stake = Self::_stake(&env, &attester);
+        if stake.unbonding <= 0 {
+            panic!("nothing unbonding");
+        }
+        if env.ledger().timestamp() < stake.unbond_at {
+            panic!("stake still unbonding");
+        }
+        let amount = stake.unbonding;
+        stake.unbonding = 0;
+        Self::_save_stake(&env, &attester, &stake);
+        token::Client::new(&env, &config.token).transfer(&env.current_contract_address(), &attester, &amount);
+        amount
+    }
+
+    /// Slash an attester flagged as an outlier in a closed epoch whose
+    /// consensus was reached. Callable by anyone, once per attestation.
+    pub fn slash_outlier(env: Env, campaign_id: u64, epoch: u64, attester: Address) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let config = Self::_staking(&env).expect("staking not configured");
+        if Self::_current_epoch(&env) <= epoch {
+            panic!("epoch not closed");
+        }
+        let consensus: OracleConsensus = env
+            .storage()
+            .persistent()
+            .get(&DataKey::EpochConsensus(campaign_id, epoch))
+            .expect("no consensus");
+        if !consensus.consensus_reached || !consensus.outliers.contains(&attester) {
+            panic!("not an outlier");
+        }
+        let slashed_key = DataKey::Slashed(campaign_id, epoch, attester.clone());
+        if env.storage().persistent().has(&slashed_key) {
+            panic!("already slashed");
+        }
+        env.storage().persistent().set(&slashed_key, &true);
+        env.storage().persistent().extend_ttl(&slashed_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        let amount = Self::_slash(&env, &attester, config.slash_bps);
+        Self::_add_rewards(&env, campaign_id, epoch, amount);
+        amount
+    }
+
+    /// Dispute contract: slash `attester` over its attestation for
+    /// (`campaign_id`, `epoch`) after a ruling against it. `ruling_bps` is the
+    /// share of the claim upheld and scales `slash_bps`; the proceeds join
+    /// that epoch's reward pool. Returns 0 when there is no such attestation
+    /// or it was already slashed, so a ruling always executes.
+    pub fn slash_by_ruling(
+        env: Env,
+        dispute_contract: Address,
+        campaign_id: u64,
+        epoch: u64,
+        attester: Address,
+        ruling_bps: u32,
+    ) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        dispute_contract.require_auth();
+        let stored: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::DisputeContract)
+            .expect("dispute contract not set");
+        if dispute_contract != stored {
+            panic!("unauthorized dispute contract");
+        }
+        if ruling_bps > 10_000 {
+            panic!("invalid amount");
+        }
+        let config = Self::_staking(&env).expect("staking not configured");
+
+        let attested = env
+            .storage()
+            .persistent()
+            .has(&DataKey::Attestation(campaign_id, epoch, attester.clone()));
+        let slashed_key = DataKey::Slashed(campaign_id, epoch, attester.clone());
+        if !attested || env.storage().persistent().has(&slashed_key) {
+            return 0;
+        }
+        env.storage().persistent().set(&slashed_key, &true);
+        env.storage().persistent().extend_ttl(&slashed_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        let bps = config.slash_bps as i128 * ruling_bps as i128 / 10_000;
+        let amount = Self::_slash(&env, &attester, bps as u32);
+        Self::_add_rewards(&env, campaign_id, epoch, amount);
+        amount
+    }
+
+    /// Add campaign fees to an epoch's reward pool
+    pub fn fund_rewards(env: Env, funder: Address, campaign_id: u64, epoch: u64, amount: i128) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        funder.require_auth();
+        if amount <= 0 {
+            panic!("invalid amount");
+        }
+        let config = Self::_staking(&env).expect("staking not configured");
+        token::Client::new(&env, &config.token).transfer(&funder, &env.current_contract_address(), &amount);
+        Self::_add_rewards(&env, campaign_id, epoch, amount);
+    }
+
+    /// Pay an attester that agreed with a closed epoch's consensus its
+    /// equal share of the pool. Funds added later can be claimed again.
+    pub fn claim_reward(env: Env, attester: Address, campaign_id: u64, epoch: u64) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        attester.require_auth();
+        let config = Self::_staking(&env).expect("staking not configured");
+        if Self::_current_epoch(&env) <= epoch {
+            panic!("epoch not closed");
+        }
+        let consensus: OracleConsensus = env
+            .storage()
+            .persistent()
+            .get(&DataKey::EpochConsensus(campaign_id, epoch))
+            .expect("no consensus");
+        let attested = env
+            .storage()
+            .persistent()
+            .has(&DataKey::Attestation(campaign_id, epoch, attester.clone()));
+        let slashed = env
+            .storage()
+            .persistent()
+            .has(&DataKey::Slashed(campaign_id, epoch, attester.clone()));
+        if !consensus.consensus_reached || !attested || slashed || consensus.outliers.contains(&attester) {
+            panic!("not in consensus");
+        }
+
+        let pool: i128 = env.storage().persistent().get(&DataKey::RewardPool(campaign_id, epoch)).unwrap_or(0);
+        let agreeing = (consensus.total_attesters - consensus.outliers.len()) as i128;
+        let claimed_key = DataKey::RewardClaimed(campaign_id, epoch, attester.clone());
+        let claimed: i128 = env.storage().persistent().get(&claimed_key).unwrap_or(0);
+        let amount = pool / agreeing - claimed;
+        if amount <= 0 {
+            panic!("nothing to claim");
+        }
+        env.storage().persistent().set(&claimed_key, &(claimed + amount));
+        env.storage().persistent().extend_ttl(&claimed_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        token::Client::new(&env, &config.token).transfer(&env.current_contract_address(), &attester, &amount);
+
+        env.events().publish(
+            (symbol_short!("oracle"), symbol_short!("reward")),
+            (campaign_id, epoch, attester, amount),
+        );
+        amount
+    }
+
+    /// Attest to a campaign's metrics for the current epoch. Submitting
+    /// again in the same epoch replaces the earlier attestation.
     pub fn submit_attestation(
         env: Env,
         attester: Address,
@@ -100,11 +389,16 @@ impl PerformanceOracleContract {
         if !is_auth {
             panic!("not authorized attester");
         }
-
-        if env.storage().persistent().has(&DataKey::Attestation(campaign_id, attester.clone())) {
-            panic!("already attested");
+        if let Some(config) = Self::_staking(&env) {
+            if Self::_stake(&env, &attester).bonded < config.min_stake {
+                panic!("insufficient stake");
+            }
+        }
+        if fraud_rate > 10_000 || quality_score > 100 {
+            panic!("invalid metrics");
         }
 
+        let epoch = Self::_current_epoch(&env);
         let attestation = PerformanceAttestation {
             campaign_id,
             attester: attester.clone(),
@@ -117,74 +411,230 @@ impl PerformanceOracleContract {
             ledger_sequence: env.ledger().sequence(),
         };
 
-        let _ttl_key = DataKey::Attestation(campaign_id, attester);
-        env.storage().persistent().set(&_ttl_key, &attestation);
-        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let key = DataKey::Attestation(campaign_id, epoch, attester.clone());
+        let revision = env.storage().persistent().has(&key);
+        env.storage().persistent().set(&key, &attestation);
+        env.storage().persistent().extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
 
-        let count: u32 = env
-            .storage()
-            .persistent()
-            .get(&DataKey::AttestationCount(campaign_id))
-            .unwrap_or(0);
-        let _ttl_key = DataKey::AttestationCount(campaign_id);
-        env.storage().persistent().set(&_ttl_key, &(count + 1));
-        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let mut attesters = Self::_epoch_attesters(&env, campaign_id, epoch);
+        if !revision {
+            attesters.push_back(attester);
+            let _ttl_key = DataKey::EpochAttesters(campaign_id, epoch);
+            env.storage().persistent().set(&_ttl_key, &attesters);
+            env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        }
 
-        // Attempt to build consensus
-        Self::_try_build_consensus(&env, campaign_id, impressions, clicks, fraud_rate, quality_score, count + 1);
+        Self::_try_build_consensus(&env, campaign_id, epoch, &attesters);
 
         env.events().publish(
             (symbol_short!("oracle"), symbol_short!("attested")),
-            campaign_id,
+            (campaign_id, epoch),
         );
     }
 
-    pub fn get_attestation(env: Env, campaign_id: u64, attester: Address) -> Option<PerformanceAttestation> {
+    pub fn get_current_epoch(env: Env) -> u64 {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        env.storage().persistent().get(&DataKey::Attestation(campaign_id, attester))
+        Self::_current_epoch(&env)
     }
 
+    pub fn get_attestation(env: Env, campaign_id: u64, epoch: u64, attester: Address) -> Option<PerformanceAttestation> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::Attestation(campaign_id, epoch, attester))
+    }
+
+    /// Consensus of the most recent epoch that reached `MinAttesters`
     pub fn get_consensus(env: Env, campaign_id: u64) -> Option<OracleConsensus> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage().persistent().get(&DataKey::Consensus(campaign_id))
     }
 
-    pub fn get_attestation_count(env: Env, campaign_id: u64) -> u32 {
+    pub fn get_epoch_consensus(env: Env, campaign_id: u64, epoch: u64) -> Option<OracleConsensus> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        env.storage().persistent().get(&DataKey::AttestationCount(campaign_id)).unwrap_or(0)
+        env.storage().persistent().get(&DataKey::EpochConsensus(campaign_id, epoch))
     }
 
-    fn _try_build_consensus(
-        env: &Env,
-        campaign_id: u64,
-        impressions: u64,
-        clicks: u64,
-        fraud_rate: u32,
-        quality_score: u32,
-        total_attesters: u32,
-    ) {
-        let min_attesters: u32 = env.storage().instance().get(&DataKey::MinAttesters).unwrap_or(3);
+    pub fn get_attestation_count(env: Env, campaign_id: u64, epoch: u64) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_epoch_attesters(&env, campaign_id, epoch).len()
+    }
+
+    pub fn get_stake(env: Env, attester: Address) -> AttesterStake {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_stake(&env, &attester)
+    }
 
-        if total_attesters < min_attesters {
+    pub fn get_reward_pool(env: Env, campaign_id: u64, epoch: u64) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::RewardPool(campaign_id, epoch)).unwrap_or(0)
+    }
+
+    // ── internal helpers ─────────────────────────────────────────────────────
+
+    fn _require_admin(env: &Env, admin: &Address) {
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if *admin != stored_admin {
+            panic!("unauthorized");
+        }
+    }
+
+    fn _staking(env: &Env) -> Option<StakingConfig> {
+        env.storage().instance().get(&DataKey::Staking)
+    }
+
+    fn _stake(env: &Env, attester: &Address) -> AttesterStake {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Stake(attester.clone()))
+            .unwrap_or(AttesterStake { bonded: 0, unbonding: 0, unbond_at: 0 })
+    }
+
+    fn _save_stake(env: &Env, attester: &Address, stake: &AttesterStake) {
+        let _ttl_key = DataKey::Stake(attester.clone());
+        env.storage().persistent().set(&_ttl_key, stake);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    /// Take `bps` of bonded and unbonding stake; the tokens stay in the contract
+    fn _slash(env: &Env, attester: &Address, bps: u32) -> i128 {
+        let mut stake = Self::_stake(env, attester);
+        let from_bonded = stake.bonded * bps as i128 / 10_000;
+        let from_unbonding = stake.unbonding * bps as i128 / 10_000;
+        stake.bonded -= from_bonded;
+        stake.unbonding -= from_unbonding;
+        Self::_save_stake(env, attester, &stake);
+
+        let amount = from_bonded + from_unbonding;
+        env.events().publish(
+            (symbol_short!("oracle"), symbol_short!("slashed")),
+            (attester.clone(), amount),
+        );
+        amount
+    }
+
+    fn _add_rewards(env: &Env, campaign_id: u64, epoch: u64, amount: i128) {
+        let key = DataKey::RewardPool(campaign_id, epoch);
+        let pool: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(pool + amount));
+        env.storage().persistent().extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    fn _current_epoch(env: &Env) -> u64 {
+        let period: u64 = env.storage().instance().get(&DataKey::PeriodSecs).unwrap_or(DEFAULT_PERIOD_SECS);
+        env.ledger().timestamp() / period
+    }
+
+    fn _epoch_attesters(env: &Env, campaign_id: u64, epoch: u64) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::EpochAttesters(campaign_id, epoch))
+            .unwrap_or(Vec::new(env))
+    }
+
+    fn _try_build_consensus(env: &Env, campaign_id: u64, epoch: u64, attesters: &Vec<Address>) {
+        let min_attesters: u32 = env.storage().instance().get(&DataKey::MinAttesters).unwrap_or(3);
+        let total_attesters = attesters.len();
+        if total_attesters < min_attesters || total_attesters == 0 {
             return;
         }
 
-        // Build simple average consensus
+        let mut attestations: Vec<PerformanceAttestation> = Vec::new(env);
+        let mut impressions = Vec::new(env);
+        let mut clicks = Vec::new(env);
+        let mut fraud_rates = Vec::new(env);
+        let mut quality_scores = Vec::new(env);
+        for attester in attesters.iter() {
+            let a: PerformanceAttestation = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Attestation(campaign_id, epoch, attester))
+                .unwrap();
+            impressions.push_back(a.impressions_verified);
+            clicks.push_back(a.clicks_verified);
+            fraud_rates.push_back(a.fraud_rate as u64);
+            quality_scores.push_back(a.quality_score as u64);
+            attestations.push_back(a);
+        }
+        let median_impressions = Self::_median(env, &impressions);
+        let median_clicks = Self::_median(env, &clicks);
+        let median_fraud_rate = Self::_median(env, &fraud_rates);
+        let median_quality_score = Self::_median(env, &quality_scores);
+
+        let tolerance: u32 = env.storage().instance().get(&DataKey::ToleranceBps).unwrap_or(DEFAULT_TOLERANCE_BPS);
+        let floors = Self::_deviation_floors(env);
+        let mut outliers = Vec::new(env);
+        for a in attestations.iter() {
+            if Self::_deviates(a.impressions_verified, median_impressions, tolerance, floors.impressions)
+                || Self::_deviates(a.clicks_verified, median_clicks, tolerance, floors.clicks)
+                || Self::_deviates(a.fraud_rate as u64, median_fraud_rate, tolerance, floors.fraud_rate)
+                || Self::_deviates(a.quality_score as u64, median_quality_score, tolerance, floors.quality_score)
+            {
+                env.events().publish(
+                    (symbol_short!("oracle"), symbol_short!("outlier")),
+                    (campaign_id, epoch, a.attester.clone()),
+                );
+                outliers.push_back(a.attester);
+            }
+        }
+
+        let threshold_pct: u32 = env.storage().instance().get(&DataKey::ConsensusThresholdPct).unwrap_or(67);
+        // Agreement rounded to the nearest percent, so 2 of 3 meets 67%
+        let agreeing = total_attesters - outliers.len();
+        let agreement_pct = (agreeing * 100 + total_attesters / 2) / total_attesters;
         let consensus = OracleConsensus {
             campaign_id,
+            epoch,
             total_attesters,
-            avg_impressions: impressions,
-            avg_clicks: clicks,
-            avg_fraud_rate: fraud_rate,
-            avg_quality_score: quality_score,
-            consensus_reached: true,
+            median_impressions,
+            median_clicks,
+            median_fraud_rate: median_fraud_rate as u32,
+            median_quality_score: median_quality_score as u32,
+            outliers,
+            consensus_reached: agreement_pct >= threshold_pct,
             last_updated: env.ledger().timestamp(),
         };
 
+        let _ttl_key = DataKey::EpochConsensus(campaign_id, epoch);
+        env.storage().persistent().set(&_ttl_key, &consensus);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
         let _ttl_key = DataKey::Consensus(campaign_id);
         env.storage().persistent().set(&_ttl_key, &consensus);
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
     }
+
+    /// Median of `values`; the mean of the two middle values for even counts
+    fn _median(env: &Env, values: &Vec<u64>) -> u64 {
+        let mut sorted: Vec<u64> = Vec::new(env);
+        for value in values.iter() {
+            let mut i = sorted.len();
+            while i > 0 && sorted.get(i - 1).unwrap() > value {
+                i -= 1;
+            }
+            sorted.insert(i, value);
+        }
+        let n = sorted.len();
+        if n.is_multiple_of(2) {
+            let (lo, hi) = (sorted.get(n / 2 - 1).unwrap(), sorted.get(n / 2).unwrap());
+            lo / 2 + hi / 2 + (lo % 2 + hi % 2) / 2
+        } else {
+            sorted.get(n / 2).unwrap()
+        }
+    }
+
+    /// Off by more than `tolerance_bps` of the median and by more than `floor`
+    fn _deviates(value: u64, median: u64, tolerance_bps: u32, floor: u64) -> bool {
+        let diff = value.abs_diff(median);
+        diff > floor && diff as u128 * 10_000 > tolerance_bps as u128 * median as u128
+    }
+
+    fn _deviation_floors(env: &Env) -> DeviationFloors {
+        env.storage().instance().get(&DataKey::DeviationFloors).unwrap_or(DeviationFloors {
+            impressions: DEFAULT_IMPRESSIONS_FLOOR,
+            clicks: DEFAULT_CLICKS_FLOOR,
+            fraud_rate: DEFAULT_FRAUD_RATE_FLOOR,
+            quality_score: DEFAULT_QUALITY_SCORE_FLOOR,
+        })
+    }
 }
 
 mod test;
@@ -1,6 +1,10 @@
 #![cfg(test)]
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, BytesN, Env};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, BytesN, Env, Vec,
+};
 
 fn setup(env: &Env) -> (PerformanceOracleContractClient, Address) {
     let admin = Address::generate(env);
@@ -48,7 +52,7 @@ fn test_submit_attestation() {
     let data_hash = BytesN::from_array(&env, &[1u8; 32]);
     c.authorize_attester(&admin, &att);
     c.submit_attestation(&att, &1u64, &1000u64, &50u64, &5u32, &90u32, &data_hash);
-    let a = c.get_attestation(&1u64, &att).unwrap();
+    let a = c.get_attestation(&1u64, &0u64, &att).unwrap();
     assert_eq!(a.impressions_verified, 1000);
 }
 
@@ -60,7 +64,7 @@ fn test_get_attestation_count() {
     let data_hash = BytesN::from_array(&env, &[1u8; 32]);
     c.authorize_attester(&admin, &att);
     c.submit_attestation(&att, &1u64, &1000u64, &50u64, &5u32, &90u32, &data_hash);
-    assert_eq!(c.get_attestation_count(&1u64), 1);
+    assert_eq!(c.get_attestation_count(&1u64, &0u64), 1);
 }
 
 #[test]
@@ -69,3 +73,251 @@ fn test_get_consensus_nonexistent() {
     let (c, _) = setup(&env);
     assert!(c.get_consensus(&999u64).is_none());
 }
+
+// ─── epochs and median consensus ─────────────────────────────────────────────
+
+fn attesters(env: &Env, c: &PerformanceOracleContractClient, admin: &Address, n: u32) -> Vec<Address> {
+    let mut list = Vec::new(env);
+    for _ in 0..n {
+        let att = Address::generate(env);
+        c.authorize_attester(admin, &att);
+        list.push_back(att);
+    }
+    list
+}
+
+fn attest(env: &Env, c: &PerformanceOracleContractClient, att: &Address, impressions: u64) {
+    let data_hash = BytesN::from_array(env, &[1u8; 32]);
+    c.submit_attestation(att, &1u64, &impressions, &50u64, &500u32, &90u32, &data_hash);
+}
+
+#[test]
+fn test_median_ignores_dishonest_attester() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    let atts = attesters(&env, &c, &admin, 3);
+    attest(&env, &c, &atts.get(0).unwrap(), 1_000);
+    attest(&env, &c, &atts.get(1).unwrap(), 1_020);
+    attest(&env, &c, &atts.get(2).unwrap(), 1_000_000);
+
+    let consensus = c.get_consensus(&1u64).unwrap();
+    assert_eq!(consensus.median_impressions, 1_020);
+    assert_eq!(consensus.total_attesters, 3);
+    assert_eq!(consensus.outliers, Vec::from_array(&env, [atts.get(2).unwrap()]));
+    // 2 of 3 agree, which meets the 67% threshold
+    assert!(consensus.consensus_reached);
+}
+
+#[test]
+fn test_even_count_median_and_no_consensus() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    let atts = attesters(&env, &c, &admin, 2);
+    attest(&env, &c, &atts.get(0).unwrap(), 1_000);
+    attest(&env, &c, &atts.get(1).unwrap(), 3_000);
+
+    let consensus = c.get_consensus(&1u64).unwrap();
+    assert_eq!(consensus.median_impressions, 2_000);
+    assert_eq!(consensus.outliers.len(), 2);
+    assert!(!consensus.consensus_reached);
+}
+
+#[test]
+fn test_small_difference_against_zero_median_tolerated() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    let atts = attesters(&env, &c, &admin, 3);
+    let data_hash = BytesN::from_array(&env, &[1u8; 32]);
+    c.submit_attestation(&atts.get(0).unwrap(), &1u64, &1_000u64, &0u64, &0u32, &90u32, &data_hash);
+    c.submit_attestation(&atts.get(1).unwrap(), &1u64, &1_000u64, &0u64, &0u32, &90u32, &data_hash);
+    c.submit_attestation(&atts.get(2).unwrap(), &1u64, &1_000u64, &1u64, &1u32, &90u32, &data_hash);
+
+    let consensus = c.get_consensus(&1u64).unwrap();
+    assert_eq!(consensus.median_fraud_rate, 0);
+    assert!(consensus.outliers.is_empty());
+
+    // Beyond the floor, a zero median still flags the attester
+    c.set_deviation_floors(&admin, &DeviationFloors { impressions: 10, clicks: 0, fraud_rate: 0, quality_score: 2 });
+    c.submit_attestation(&atts.get(2).unwrap(), &1u64, &1_000u64, &1u64, &1u32, &90u32, &data_hash);
+    assert_eq!(c.get_consensus(&1u64).unwrap().outliers, Vec::from_array(&env, [atts.get(2).unwrap()]));
+}
+
+#[test]
+fn test_attestation_revised_within_epoch() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    let atts = attesters(&env, &c, &admin, 2);
+    attest(&env, &c, &atts.get(0).unwrap(), 1_000);
+    attest(&env, &c, &atts.get(1).unwrap(), 3_000);
+    attest(&env, &c, &atts.get(1).unwrap(), 1_000);
+
+    assert_eq!(c.get_attestation_count(&1u64, &0u64), 2);
+    let consensus = c.get_consensus(&1u64).unwrap();
+    assert_eq!(consensus.median_impressions, 1_000);
+    assert!(consensus.outliers.is_empty());
+    assert!(consensus.consensus_reached);
+}
+
+#[test]
+fn test_new_epoch_builds_fresh_consensus() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    c.set_reporting_period(&admin, &3_600u64);
+    let atts = attesters(&env, &c, &admin, 2);
+    attest(&env, &c, &atts.get(0).unwrap(), 1_000);
+    attest(&env, &c, &atts.get(1).unwrap(), 1_000);
+
+    env.ledger().with_mut(|li| li.timestamp = 3_600);
+    assert_eq!(c.get_current_epoch(), 1);
+    attest(&env, &c, &atts.get(0).unwrap(), 5_000);
+    // One attestation is below the minimum, so the latest consensus is still epoch 0
+    assert_eq!(c.get_consensus(&1u64).unwrap().epoch, 0);
+    attest(&env, &c, &atts.get(1).unwrap(), 5_000);
+
+    let consensus = c.get_consensus(&1u64).unwrap();
+    assert_eq!(consensus.epoch, 1);
+    assert_eq!(consensus.median_impressions, 5_000);
+    assert_eq!(c.get_epoch_consensus(&1u64, &0u64).unwrap().median_impressions, 1_000);
+}
+
+// ─── staking, slashing and rewards ───────────────────────────────────────────
+
+const STAKE: i128 = 10_000;
+
+/// Three staked attesters; the third reports wildly in epoch 0 (1 hour long)
+fn staked_epoch(env: &Env) -> (PerformanceOracleContractClient<'_>, Address, Address, Vec<Address>) {
+    let (c, admin) = setup(env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    c.set_staking(&admin, &StakingConfig { token: token.clone(), min_stake: STAKE, slash_bps: 5_000, unbonding_secs: 86_400 });
+    c.set_reporting_period(&admin, &3_600u64);
+    let atts = attesters(env, &c, &admin, 3);
+    for att in atts.iter() {
+        StellarAssetClient::new(env, &token).mint(&att, &STAKE);
+        c.stake(&att, &STAKE);
+    }
+    attest(env, &c, &atts.get(0).unwrap(), 1_000);
+    attest(env, &c, &atts.get(1).unwrap(), 1_000);
+    attest(env, &c, &atts.get(2).unwrap(), 9_000);
+    (c, admin, token, atts)
+}
+
+#[test]
+#[should_panic(expected = "insufficient stake")]
+fn test_unstaked_attester_cannot_attest() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    c.set_staking(&admin, &StakingConfig { token, min_stake: STAKE, slash_bps: 5_000, unbonding_secs: 0 });
+    let att = attesters(&env, &c, &admin, 1).get(0).unwrap();
+    attest(&env, &c, &att, 1_000);
+}
+
+#[test]
+fn test_outlier_slashed_and_consensus_rewarded() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, token, atts) = staked_epoch(&env);
+    let funder = Address::generate(&env);
+    StellarAssetClient::new(&env, &token).mint(&funder, &1_000);
+    c.fund_rewards(&funder, &1u64, &0u64, &1_000i128);
+
+    env.ledger().with_mut(|li| li.timestamp = 3_600);
+    assert_eq!(c.slash_outlier(&1u64, &0u64, &atts.get(2).unwrap()), STAKE / 2);
+    assert_eq!(c.get_stake(&atts.get(2).unwrap()).bonded, STAKE / 2);
+    assert_eq!(c.get_reward_pool(&1u64, &0u64), 1_000 + STAKE / 2);
+
+    let share = (1_000 + STAKE / 2) / 2;
+    assert_eq!(c.claim_reward(&atts.get(0).unwrap(), &1u64, &0u64), share);
+    assert_eq!(TokenClient::new(&env, &token).balance(&atts.get(0).unwrap()), share);
+}
+
+#[test]
+#[should_panic(expected = "not in consensus")]
+fn test_outlier_cannot_claim_reward() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, _, atts) = staked_epoch(&env);
+    env.ledger().with_mut(|li| li.timestamp = 3_600);
+    c.claim_reward(&atts.get(2).unwrap(), &1u64, &0u64);
+}
+
+#[test]
+#[should_panic(expected = "epoch not closed")]
+fn test_slash_outlier_during_epoch() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, _, atts) = staked_epoch(&env);
+    c.slash_outlier(&1u64, &0u64, &atts.get(2).unwrap());
+}
+
+#[test]
+#[should_panic(expected = "already slashed")]
+fn test_slash_outlier_twice() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, _, atts) = staked_epoch(&env);
+    env.ledger().with_mut(|li| li.timestamp = 3_600);
+    c.slash_outlier(&1u64, &0u64, &atts.get(2).unwrap());
+    c.slash_outlier(&1u64, &0u64, &atts.get(2).unwrap());
+}
+
+#[test]
+#[should_panic(expected = "not an outlier")]
+fn test_slash_honest_attester() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, _, atts) = staked_epoch(&env);
+    env.ledger().with_mut(|li| li.timestamp = 3_600);
+    c.slash_outlier(&1u64, &0u64, &atts.get(0).unwrap());
+}
+
+#[test]
+fn test_slash_by_dispute_ruling_reaches_unbonding_stake() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, token, atts) = staked_epoch(&env);
+    let dispute = Address::generate(&env);
+    c.set_dispute_contract(&admin, &dispute);
+    let att = atts.get(0).unwrap();
+    c.unstake(&att, &STAKE);
+
+    // slash_bps 5_000 scaled by a 40% ruling
+    assert_eq!(c.slash_by_ruling(&dispute, &1u64, &0u64, &att, &4_000u32), STAKE / 5);
+    assert_eq!(c.get_reward_pool(&1u64, &0u64), STAKE / 5);
+    assert_eq!(TokenClient::new(&env, &token).balance(&admin), 0);
+    // a second ruling over the same attestation slashes nothing
+    assert_eq!(c.slash_by_ruling(&dispute, &1u64, &0u64, &att, &4_000u32), 0);
+
+    env.ledger().with_mut(|li| li.timestamp = 86_400);
+    assert_eq!(c.withdraw_stake(&att), STAKE - STAKE / 5);
+}
+
+#[test]
+fn test_slash_by_ruling_requires_matching_attestation() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, _, atts) = staked_epoch(&env);
+    let dispute = Address::generate(&env);
+    c.set_dispute_contract(&admin, &dispute);
+    let att = atts.get(0).unwrap();
+
+    assert_eq!(c.slash_by_ruling(&dispute, &2u64, &0u64, &att, &10_000u32), 0);
+    assert_eq!(c.slash_by_ruling(&dispute, &1u64, &5u64, &att, &10_000u32), 0);
+    assert_eq!(c.get_stake(&att).bonded, STAKE);
+}
+
+#[test]
+#[should_panic(expected = "not in consensus")]
+fn test_attester_slashed_by_ruling_forfeits_reward() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, _, atts) = staked_epoch(&env);
+    let dispute = Address::generate(&env);
+    c.set_dispute_contract(&admin, &dispute);
+    let att = atts.get(0).unwrap();
+    c.slash_by_ruling(&dispute, &1u64, &0u64, &att, &10_000u32);
+
+    env.ledger().with_mut(|li| li.timestamp = 3_600);
+    c.claim_reward(&att, &1u64, &0u64);
+}
+
+#[test]
+#[should_panic(expected = "stake still unbonding")]
+fn test_withdraw_stake_before_unbonding() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, _, atts) = staked_epoch(&env);
+    c.unstake(&atts.get(0).unwrap(), &STAKE);
+    c.withdraw_stake(&atts.get(0).unwrap());
+}
@@ -46,6 +46,7 @@ pub enum DataKey {
     NodeCount,
     NetworkStats,
     Node(Address),
+    Suspended(Address),
 }
 
 const INSTANCE_LIFETIME_THRESHOLD: u32 = 17_280;
@@ -164,9 +165,65 @@ impl PublisherNetworkContract {
             panic!("unauthorized fraud contract");
         }
 
+        let node: NetworkNode = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Node(publisher.clone()))
+            .expect("not in network");
+        // Only nodes taken offline by the suspension come back on reinstatement
+        if node.is_active {
+            let _ttl_key = DataKey::Suspended(publisher.clone());
+            env.storage().persistent().set(&_ttl_key, &true);
+            env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        }
+
         Self::_deactivate_node(&env, publisher);
     }
 
+    /// Fraud contract: bring a node suspended for fraud back online
+    pub fn reinstate_publisher(env: Env, fraud_contract: Address, publisher: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        fraud_contract.require_auth();
+
+        let stored_fraud: Address = env.storage().instance().get(&DataKey::FraudContract).expect("fraud contract not set");
+        if fraud_contract != stored_fraud {
+            panic!("unauthorized fraud contract");
+        }
+
+        if !env.storage().persistent().has(&DataKey::Suspended(publisher.clone())) {
+            return;
+        }
+        env.storage().persistent().remove(&DataKey::Suspended(publisher.clone()));
+
+        let mut node: NetworkNode = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Node(publisher.clone()))
+            .expect("not in network");
+        if node.is_active {
+            return;
+        }
+
+        node.is_active = true;
+        let _ttl_key = DataKey::Node(publisher.clone());
+        env.storage().persistent().set(&_ttl_key, &node);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        let mut stats: NetworkStats = env.storage().instance().get(&DataKey::NetworkStats).unwrap();
+        stats.active_nodes += 1;
+        env.storage().instance().set(&DataKey::NetworkStats, &stats);
+
+        env.events().publish(
+            (symbol_short!("network"), symbol_short!("reinstate")),
+            publisher,
+        );
+    }
+
+    pub fn is_suspended(env: Env, publisher: Address) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().has(&DataKey::Suspended(publisher))
+    }
+
     fn _deactivate_node(env: &Env, publisher: Address) {
         let mut node: NetworkNode = env
             .storage()
@@ -115,6 +115,41 @@ fn test_suspend_publisher() {
     assert!(!node.is_active);
 }
 
+#[test]
+fn test_reinstate_suspended_publisher() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    let fraud = Address::generate(&env);
+    c.set_fraud_contract(&admin, &fraud);
+    let pub1 = Address::generate(&env);
+    let cats = vec![&env, s(&env, "tech")];
+    c.join_network(&pub1, &NodeType::Standard, &10_000u64, &100i128, &s(&env, "US"), &cats);
+    c.suspend_publisher(&fraud, &pub1);
+    assert!(c.is_suspended(&pub1));
+
+    c.reinstate_publisher(&fraud, &pub1);
+    assert!(c.get_node(&pub1).unwrap().is_active);
+    assert!(!c.is_suspended(&pub1));
+    assert_eq!(c.get_network_stats().active_nodes, 1);
+}
+
+#[test]
+fn test_reinstate_keeps_voluntarily_deactivated_node_offline() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    let fraud = Address::generate(&env);
+    c.set_fraud_contract(&admin, &fraud);
+    let pub1 = Address::generate(&env);
+    let cats = vec![&env, s(&env, "tech")];
+    c.join_network(&pub1, &NodeType::Standard, &10_000u64, &100i128, &s(&env, "US"), &cats);
+    c.deactivate(&pub1);
+    c.suspend_publisher(&fraud, &pub1);
+
+    c.reinstate_publisher(&fraud, &pub1);
+    assert!(!c.get_node(&pub1).unwrap().is_active);
+    assert_eq!(c.get_network_stats().active_nodes, 0);
+}
+
 #[test]
 #[should_panic(expected = "unauthorized fraud contract")]
 fn test_suspend_publisher_wrong_fraud() {
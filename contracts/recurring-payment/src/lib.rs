@@ -1,5 +1,17 @@
 //! PulsarTrack - Recurring Payment (Soroban)
 //! Automated recurring payment subscriptions for ad campaigns on Stellar.
+//!
+//! Payments are pulled with `transfer_from`: the payer grants this contract
+//! a token allowance once, and anyone (typically a keeper) may call
+//! `execute_payment` when a payment is due. If the allowance or balance does
+//! not cover a charge the payment moves to `Failed` instead of reverting, and
+//! can be retried until the grace period runs out, after which it lapses to
+//! `Cancelled`.
+//!
+//! Events:
+//! - ("recurring", "paid"): [payment_id: u64, amount: i128]
+//! - ("recurring", "failed"): [payment_id: u64, attempts: u32]
+//! - ("recurring", "lapsed"): payment_id: u64
 
 #![no_std]
 use soroban_sdk::{
@@ -31,12 +43,15 @@ pub struct RecurringPayment {
     pub created_at: u64,
     pub last_payment: u64,
     pub next_payment: u64,
+    pub failed_at: Option<u64>,
+    pub failed_attempts: u32,
 }
 
 #[contracttype]
 pub enum DataKey {
     Admin,
     PaymentCounter,
+    GracePeriod,
     Payment(u64),
 }
 
@@ -44,6 +59,7 @@ const INSTANCE_LIFETIME_THRESHOLD: u32 = 17_280;
 const INSTANCE_BUMP_AMOUNT: u32 = 86_400;
 const PERSISTENT_LIFETIME_THRESHOLD: u32 = 120_960;
 const PERSISTENT_BUMP_AMOUNT: u32 = 1_051_200;
+const DEFAULT_GRACE_PERIOD_SECS: u64 = 259_200; // 3 days
 
 #[contract]
 pub struct RecurringPaymentContract;
@@ -96,6 +112,8 @@ impl RecurringPaymentContract {
             created_at: now,
             last_payment: now,
             next_payment: now + interval_secs,
+            failed_at: None,
+            failed_attempts: 0,
         };
 
         let _ttl_key = DataKey::Payment(payment_id);
@@ -106,7 +124,20 @@ impl RecurringPaymentContract {
         payment_id
     }
 
-    pub fn execute_payment(env: Env, payment_id: u64) {
+    pub fn set_grace_period(env: Env, admin: Address, grace_secs: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        env.storage().instance().set(&DataKey::GracePeriod, &grace_secs);
+    }
+
+    /// Keeper-callable. Pulls a due payment from the payer's allowance and
+    /// returns whether it was paid; a charge that cannot be covered marks
+    /// the payment `Failed` (or `Cancelled` once the grace period is over).
+    pub fn execute_payment(env: Env, payment_id: u64) -> bool {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         let mut recurring: RecurringPayment = env
             .storage()
@@ -114,7 +145,7 @@ impl RecurringPaymentContract {
             .get(&DataKey::Payment(payment_id))
             .expect("payment not found");
 
-        if recurring.status != RecurringStatus::Active {
+        if recurring.status != RecurringStatus::Active && recurring.status != RecurringStatus::Failed {
             panic!("payment not active");
         }
 
@@ -126,28 +157,56 @@ impl RecurringPaymentContract {
         if let Some(max) = recurring.max_payments {
             if recurring.total_payments >= max {
                 recurring.status = RecurringStatus::Cancelled;
-                let _ttl_key = DataKey::Payment(payment_id);
-                env.storage().persistent().set(&_ttl_key, &recurring);
-                env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
-                panic!("max payments reached");
+                Self::_save(&env, &recurring);
+                return false;
             }
         }
 
         let token_client = token::Client::new(&env, &recurring.token);
-        token_client.transfer(&recurring.payer, &recurring.recipient, &recurring.amount);
+        let spender = env.current_contract_address();
+        let covered = token_client.allowance(&recurring.payer, &spender) >= recurring.amount
+            && token_client.balance(&recurring.payer) >= recurring.amount;
+
+        if !covered {
+            let grace: u64 = env
+                .storage()
+                .instance()
+                .get(&DataKey::GracePeriod)
+                .unwrap_or(DEFAULT_GRACE_PERIOD_SECS);
+            let failed_at = *recurring.failed_at.get_or_insert(now);
+            recurring.failed_attempts += 1;
+            if now > failed_at + grace {
+                recurring.status = RecurringStatus::Cancelled;
+                env.events().publish(
+                    (symbol_short!("recurring"), symbol_short!("lapsed")),
+                    payment_id,
+                );
+            } else {
+                recurring.status = RecurringStatus::Failed;
+                env.events().publish(
+                    (symbol_short!("recurring"), symbol_short!("failed")),
+                    (payment_id, recurring.failed_attempts),
+                );
+            }
+            Self::_save(&env, &recurring);
+            return false;
+        }
+
+        token_client.transfer_from(&spender, &recurring.payer, &recurring.recipient, &recurring.amount);
 
         recurring.total_payments += 1;
         recurring.last_payment = now;
         recurring.next_payment = now + recurring.interval_secs;
-
-        let _ttl_key = DataKey::Payment(payment_id);
-        env.storage().persistent().set(&_ttl_key, &recurring);
-        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        recurring.status = RecurringStatus::Active;
+        recurring.failed_at = None;
+        recurring.failed_attempts = 0;
+        Self::_save(&env, &recurring);
 
         env.events().publish(
             (symbol_short!("recurring"), symbol_short!("paid")),
             (payment_id, recurring.amount),
         );
+        true
     }
 
     pub fn pause_payment(env: Env, payer: Address, payment_id: u64) {
@@ -186,6 +245,8 @@ impl RecurringPaymentContract {
 
         recurring.status = RecurringStatus::Active;
         recurring.next_payment = env.ledger().timestamp() + recurring.interval_secs;
+        recurring.failed_at = None;
+        recurring.failed_attempts = 0;
         let _ttl_key = DataKey::Payment(payment_id);
         env.storage().persistent().set(&_ttl_key, &recurring);
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
@@ -215,6 +276,12 @@ impl RecurringPaymentContract {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage().persistent().get(&DataKey::Payment(payment_id))
     }
+
+    fn _save(env: &Env, recurring: &RecurringPayment) {
+        let _ttl_key = DataKey::Payment(recurring.payment_id);
+        env.storage().persistent().set(&_ttl_key, recurring);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
 }
 
 mod test;
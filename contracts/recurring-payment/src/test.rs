@@ -1,6 +1,10 @@
 #![cfg(test)]
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env, String,
+};
 
 fn setup(env: &Env) -> (RecurringPaymentContractClient, Address) {
     let admin = Address::generate(env);
@@ -87,6 +91,103 @@ fn test_cancel_payment() {
     assert!(matches!(payment.status, RecurringStatus::Cancelled));
 }
 
+// ─── execute_payment ─────────────────────────────────────────────────────────
+
+/// Daily 1000-token payment; the payer holds `balance` and has approved `allowance`
+fn setup_pull(env: &Env, balance: i128, allowance: i128) -> (RecurringPaymentContractClient<'_>, Address, Address, Address, u64) {
+    let (c, admin) = setup(env);
+    let token = env.register_stellar_asset_contract_v2(admin).address();
+    let payer = Address::generate(env);
+    let payee = Address::generate(env);
+    StellarAssetClient::new(env, &token).mint(&payer, &balance);
+    TokenClient::new(env, &token).approve(&payer, &c.address, &allowance, &100_000u32);
+    let id = c.create_recurring(&payer, &payee, &token, &1000i128, &86_400u64, &Some(2u32));
+    (c, token, payer, payee, id)
+}
+
+#[test]
+fn test_execute_payment_pulls_from_allowance() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, token, payer, payee, id) = setup_pull(&env, 10_000, 3_000);
+
+    // keeper call: no payer signature involved
+    env.set_auths(&[]);
+    env.ledger().with_mut(|li| li.timestamp = 86_400);
+    assert!(c.execute_payment(&id));
+
+    let tc = TokenClient::new(&env, &token);
+    assert_eq!(tc.balance(&payee), 1000);
+    assert_eq!(tc.balance(&payer), 9_000);
+    assert_eq!(tc.allowance(&payer, &c.address), 2_000);
+    let payment = c.get_payment(&id).unwrap();
+    assert_eq!(payment.total_payments, 1);
+    assert_eq!(payment.next_payment, 2 * 86_400);
+}
+
+#[test]
+#[should_panic(expected = "too early")]
+fn test_execute_payment_too_early() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, _, _, id) = setup_pull(&env, 10_000, 3_000);
+    c.execute_payment(&id);
+}
+
+#[test]
+fn test_uncovered_charge_fails_then_recovers() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, token, payer, payee, id) = setup_pull(&env, 10_000, 500);
+
+    env.ledger().with_mut(|li| li.timestamp = 86_400);
+    assert!(!c.execute_payment(&id));
+    let payment = c.get_payment(&id).unwrap();
+    assert!(matches!(payment.status, RecurringStatus::Failed));
+    assert_eq!(payment.failed_at, Some(86_400));
+    assert_eq!(payment.failed_attempts, 1);
+
+    // payer tops up the allowance within the grace period; keeper retries
+    TokenClient::new(&env, &token).approve(&payer, &c.address, &5_000i128, &100_000u32);
+    env.ledger().with_mut(|li| li.timestamp = 86_400 + 3_600);
+    assert!(c.execute_payment(&id));
+
+    let payment = c.get_payment(&id).unwrap();
+    assert!(matches!(payment.status, RecurringStatus::Active));
+    assert_eq!(payment.failed_at, None);
+    assert_eq!(TokenClient::new(&env, &token).balance(&payee), 1000);
+}
+
+#[test]
+fn test_failed_payment_lapses_after_grace() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin) = setup(&env);
+    c.set_grace_period(&admin, &3_600u64);
+    let token = env.register_stellar_asset_contract_v2(admin).address();
+    let payer = Address::generate(&env);
+    let id = c.create_recurring(&payer, &Address::generate(&env), &token, &1000i128, &86_400u64, &None);
+
+    env.ledger().with_mut(|li| li.timestamp = 86_400);
+    assert!(!c.execute_payment(&id));
+    env.ledger().with_mut(|li| li.timestamp = 86_400 + 3_601);
+    assert!(!c.execute_payment(&id));
+
+    let payment = c.get_payment(&id).unwrap();
+    assert!(matches!(payment.status, RecurringStatus::Cancelled));
+    assert_eq!(payment.failed_attempts, 2);
+}
+
+#[test]
+fn test_max_payments_cancels() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, _, _, id) = setup_pull(&env, 10_000, 10_000);
+
+    for day in 1..=2u64 {
+        env.ledger().with_mut(|li| li.timestamp = day * 86_400);
+        assert!(c.execute_payment(&id));
+    }
+    env.ledger().with_mut(|li| li.timestamp = 3 * 86_400);
+    assert!(!c.execute_payment(&id));
+    assert!(matches!(c.get_payment(&id).unwrap().status, RecurringStatus::Cancelled));
+}
+
 #[test]
 fn test_get_payment_nonexistent() {
     let env = Env::default(); env.mock_all_auths();
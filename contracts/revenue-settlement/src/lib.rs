@@ -1,24 +1,53 @@
 //! PulsarTrack - Revenue Settlement (Soroban)
 //! Automated revenue distribution and settlement for the PulsarTrack ecosystem on Stellar.
+//!
+//! Revenue is divided according to a governable list of (target, bps) splits
+//! summing to 10,000. A split targets the settlement's publisher, a fixed
+//! account, or the burn. Account shares accrue until
+//! `distribute_platform_revenue` pays them out and burns the burn share.
+//!
+//! Revenue is only credited against a real inflow: `record_revenue` pulls
+//! the amount from the source (the admin or an authorized contract such as
+//! campaign-orchestrator or escrow-vault) and credits what the contract's
+//! balance actually grew by, so one source can never claim tokens another
+//! sent. Contract sources authorize that transfer for the call.
+//! `reconcile` compares the balance with the outstanding liabilities.
+//!
+//! Events:
+//! - ("revenue", "recorded"): [settlement_id: u64, source: Address, amount: i128]
+//! - ("revenue", "splits"): splits: Vec<RevenueSplit>
+//! - ("revenue", "burned"): amount: i128
+//! - ("revenue", "claimed"): [publisher: Address, amount: i128]
 
 #![no_std]
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short,
-    token, Address, Env,
+    token, vec, Address, Env, Vec,
 };
 
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum SplitTarget {
+    Publisher,        // publisher named in the settlement
+    Burn,             // destroyed on distribution
+    Account(Address), // fixed recipient, e.g. platform or treasury
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RevenueSplit {
+    pub target: SplitTarget,
+    pub bps: u32,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct RevenuePool {
     pub total_revenue: i128,
-    pub platform_share: i128,      // platform fee portion
-    pub publisher_share: i128,     // publisher earnings portion
-    pub treasury_share: i128,      // DAO treasury portion
+    pub publisher_share: i128,     // owed to publishers, not yet claimed
+    pub recipient_share: i128,     // owed to account splits, not yet distributed
     pub burn_amount: i128,         // tokens to burn
-    pub platform_pct: u32,         // basis points
-    pub publisher_pct: u32,
-    pub treasury_pct: u32,
-    pub burn_pct: u32,
+    pub total_burned: i128,
     pub last_settlement: u64,
 }
 
@@ -28,11 +57,22 @@ pub struct SettlementRecord {
     pub settlement_id: u64,
     pub campaign_id: u64,
     pub total_amount: i128,
-    pub platform_fee: i128,
     pub publisher_amount: i128,
+    pub burn_amount: i128,
+    pub splits: Vec<RevenueSplit>, // split in force at settlement time
     pub settled_at: u64,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reconciliation {
+    pub token_balance: i128,
+    pub publisher_liabilities: i128,
+    pub recipient_liabilities: i128,
+    pub pending_burn: i128,
+    pub surplus: i128, // negative when liabilities exceed the balance
+}
+
 #[contracttype]
 pub enum DataKey {
     Admin,
@@ -40,15 +80,21 @@ pub enum DataKey {
     TreasuryAddress,
     PlatformAddress,
     RevenuePool,
+    RevenueSplits,
+    PendingRecipients,
     SettlementCounter,
     Settlement(u64),
     PublisherBalance(Address),
+    RecipientBalance(Address),
+    RevenueSource(Address),
 }
 
 const INSTANCE_LIFETIME_THRESHOLD: u32 = 17_280;
 const INSTANCE_BUMP_AMOUNT: u32 = 86_400;
 const PERSISTENT_LIFETIME_THRESHOLD: u32 = 120_960;
 const PERSISTENT_BUMP_AMOUNT: u32 = 1_051_200;
+const BPS_TOTAL: u32 = 10_000;
+const MAX_SPLITS: u32 = 10;
 
 #[contract]
 pub struct RevenueSettlementContract;
@@ -74,19 +120,25 @@ impl RevenueSettlementContract {
         env.storage().instance().set(&DataKey::SettlementCounter, &0u64);
         env.storage().instance().set(&DataKey::RevenuePool, &RevenuePool {
             total_revenue: 0,
-            platform_share: 0,
             publisher_share: 0,
-            treasury_share: 0,
+            recipient_share: 0,
             burn_amount: 0,
-            platform_pct: 250,    // 2.5%
-            publisher_pct: 9_000, // 90%
-            treasury_pct: 500,    // 5%
-            burn_pct: 250,        // 2.5%
+            total_burned: 0,
             last_settlement: 0,
         });
+        let splits = vec![
+            &env,
+            RevenueSplit { target: SplitTarget::Account(platform), bps: 250 }, // 2.5%
+            RevenueSplit { target: SplitTarget::Publisher, bps: 9_000 },       // 90%
+            RevenueSplit { target: SplitTarget::Account(treasury), bps: 500 }, // 5%
+            RevenueSplit { target: SplitTarget::Burn, bps: 250 },              // 2.5%
+        ];
+        env.storage().instance().set(&DataKey::RevenueSplits, &splits);
     }
 
-    pub fn record_revenue(env: Env, admin: Address, campaign_id: u64, amount: i128, publisher: Address) -> u64 {
+    /// Replace the revenue split. Exactly one entry must target the publisher,
+    /// and the basis points must sum to 10,000.
+    pub fn set_revenue_splits(env: Env, admin: Address, splits: Vec<RevenueSplit>) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         admin.require_auth();
         let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
@@ -94,17 +146,94 @@ impl RevenueSettlementContract {
             panic!("unauthorized");
         }
 
-        let mut pool: RevenuePool = env.storage().instance().get(&DataKey::RevenuePool).unwrap();
+        if splits.is_empty() || splits.len() > MAX_SPLITS {
+            panic!("invalid split count");
+        }
+        let mut total: u32 = 0;
+        let mut publisher_entries = 0u32;
+        for split in splits.iter() {
+            if split.bps == 0 {
+                panic!("zero split");
+            }
+            total = total.checked_add(split.bps).expect("splits must sum to 10000");
+            if split.target == SplitTarget::Publisher {
+                publisher_entries += 1;
+            }
+        }
+        if total != BPS_TOTAL {
+            panic!("splits must sum to 10000");
+        }
+        if publisher_entries != 1 {
+            panic!("exactly one publisher split required");
+        }
+
+        env.storage().instance().set(&DataKey::RevenueSplits, &splits);
+        env.events().publish(
+            (symbol_short!("revenue"), symbol_short!("splits")),
+            splits,
+        );
+    }
+
+    pub fn set_revenue_source(env: Env, admin: Address, source: Address, allowed: bool) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        let _ttl_key = DataKey::RevenueSource(source);
+        if allowed {
+            env.storage().persistent().set(&_ttl_key, &true);
+            env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        } else {
+            env.storage().persistent().remove(&_ttl_key);
+        }
+    }
+
+    /// Pull `amount` from the source into this contract and credit it. The
+    /// source must authorize the transfer; panics unless the full amount
+    /// arrives.
+    pub fn record_revenue(env: Env, source: Address, campaign_id: u64, amount: i128, publisher: Address) -> u64 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        source.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if source != stored_admin && !Self::is_revenue_source(env.clone(), source.clone()) {
+            panic!("unauthorized");
+        }
+        if amount <= 0 {
+            panic!("invalid amount");
+        }
+
+        // Pull the revenue in and credit only what actually arrived
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        let token_client = token::Client::new(&env, &token_addr);
+        let balance_before = token_client.balance(&env.current_contract_address());
+        token_client.transfer(&source, &env.current_contract_address(), &amount);
+        if token_client.balance(&env.current_contract_address()) - balance_before != amount {
+            panic!("revenue not received");
+        }
 
-        let platform_fee = (amount * pool.platform_pct as i128) / 10_000;
-        let treasury_fee = (amount * pool.treasury_pct as i128) / 10_000;
-        let burn_fee = (amount * pool.burn_pct as i128) / 10_000;
-        let publisher_amount = amount - platform_fee - treasury_fee - burn_fee;
+        let mut pool: RevenuePool = env.storage().instance().get(&DataKey::RevenuePool).unwrap();
+        let splits: Vec<RevenueSplit> = env.storage().instance().get(&DataKey::RevenueSplits).unwrap();
+
+        // Non-publisher shares round down; the publisher receives the remainder
+        let mut burn_fee: i128 = 0;
+        let mut publisher_amount = amount;
+        for split in splits.iter() {
+            let share = (amount * split.bps as i128) / BPS_TOTAL as i128;
+            match split.target {
+                SplitTarget::Publisher => continue,
+                SplitTarget::Burn => burn_fee += share,
+                SplitTarget::Account(recipient) => {
+                    Self::_credit_recipient(&env, &recipient, share);
+                    pool.recipient_share += share;
+                }
+            }
+            publisher_amount -= share;
+        }
 
         pool.total_revenue += amount;
-        pool.platform_share += platform_fee;
         pool.publisher_share += publisher_amount;
-        pool.treasury_share += treasury_fee;
         pool.burn_amount += burn_fee;
 
         env.storage().instance().set(&DataKey::RevenuePool, &pool);
@@ -123,8 +252,9 @@ impl RevenueSettlementContract {
             settlement_id,
             campaign_id,
             total_amount: amount,
-            platform_fee,
             publisher_amount,
+            burn_amount: burn_fee,
+            splits,
             settled_at: env.ledger().timestamp(),
         };
 
@@ -133,9 +263,15 @@ impl RevenueSettlementContract {
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
         env.storage().instance().set(&DataKey::SettlementCounter, &settlement_id);
 
+        env.events().publish(
+            (symbol_short!("revenue"), symbol_short!("recorded")),
+            (settlement_id, source, amount),
+        );
+
         settlement_id
     }
 
+    /// Pay out every account split's accrued balance and burn the burn share
     pub fn distribute_platform_revenue(env: Env, admin: Address) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         admin.require_auth();
@@ -148,16 +284,30 @@ impl RevenueSettlementContract {
         let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
         let token_client = token::Client::new(&env, &token_addr);
 
-        if pool.platform_share > 0 {
-            let platform: Address = env.storage().instance().get(&DataKey::PlatformAddress).unwrap();
-            token_client.transfer(&env.current_contract_address(), &platform, &pool.platform_share);
-            pool.platform_share = 0;
+        let recipients: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingRecipients)
+            .unwrap_or(Vec::new(&env));
+        for recipient in recipients.iter() {
+            let key = DataKey::RecipientBalance(recipient.clone());
+            let owed: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+            if owed > 0 {
+                token_client.transfer(&env.current_contract_address(), &recipient, &owed);
+                pool.recipient_share -= owed;
+            }
+            env.storage().persistent().remove(&key);
         }
-
-        if pool.treasury_share > 0 {
-            let treasury: Address = env.storage().instance().get(&DataKey::TreasuryAddress).unwrap();
-            token_client.transfer(&env.current_contract_address(), &treasury, &pool.treasury_share);
-            pool.treasury_share = 0;
+        env.storage().instance().remove(&DataKey::PendingRecipients);
+
+        if pool.burn_amount > 0 {
+            token_client.burn(&env.current_contract_address(), &pool.burn_amount);
+            pool.total_burned += pool.burn_amount;
+            env.events().publish(
+                (symbol_short!("revenue"), symbol_short!("burned")),
+                pool.burn_amount,
+            );
+            pool.burn_amount = 0;
         }
 
         pool.last_settlement = env.ledger().timestamp();
@@ -182,6 +332,10 @@ impl RevenueSettlementContract {
         env.storage().persistent().set(&pub_key, &0i128);
         env.storage().persistent().extend_ttl(&pub_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
 
+        let mut pool: RevenuePool = env.storage().instance().get(&DataKey::RevenuePool).unwrap();
+        pool.publisher_share -= balance;
+        env.storage().instance().set(&DataKey::RevenuePool, &pool);
+
         env.events().publish(
             (symbol_short!("revenue"), symbol_short!("claimed")),
             (publisher, balance),
@@ -193,15 +347,66 @@ impl RevenueSettlementContract {
         env.storage().instance().get(&DataKey::RevenuePool).expect("not initialized")
     }
 
+    /// Token balance held against everything still owed out of it
+    pub fn reconcile(env: Env) -> Reconciliation {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let pool: RevenuePool = env.storage().instance().get(&DataKey::RevenuePool).expect("not initialized");
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        let token_balance = token::Client::new(&env, &token_addr).balance(&env.current_contract_address());
+
+        let liabilities = pool.publisher_share + pool.recipient_share + pool.burn_amount;
+        Reconciliation {
+            token_balance,
+            publisher_liabilities: pool.publisher_share,
+            recipient_liabilities: pool.recipient_share,
+            pending_burn: pool.burn_amount,
+            surplus: token_balance - liabilities,
+        }
+    }
+
+    pub fn is_revenue_source(env: Env, source: Address) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::RevenueSource(source)).unwrap_or(false)
+    }
+
+    pub fn get_revenue_splits(env: Env) -> Vec<RevenueSplit> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().instance().get(&DataKey::RevenueSplits).expect("not initialized")
+    }
+
     pub fn get_publisher_balance(env: Env, publisher: Address) -> i128 {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage().persistent().get(&DataKey::PublisherBalance(publisher)).unwrap_or(0)
     }
 
+    pub fn get_recipient_balance(env: Env, recipient: Address) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::RecipientBalance(recipient)).unwrap_or(0)
+    }
+
     pub fn get_settlement(env: Env, settlement_id: u64) -> Option<SettlementRecord> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage().persistent().get(&DataKey::Settlement(settlement_id))
     }
+
+    fn _credit_recipient(env: &Env, recipient: &Address, amount: i128) {
+        if amount <= 0 {
+            return;
+        }
+        let key = DataKey::RecipientBalance(recipient.clone());
+        let owed: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        if owed == 0 {
+            let mut recipients: Vec<Address> = env
+                .storage()
+                .instance()
+                .get(&DataKey::PendingRecipients)
+                .unwrap_or(Vec::new(env));
+            recipients.push_back(recipient.clone());
+            env.storage().instance().set(&DataKey::PendingRecipients, &recipients);
+        }
+        env.storage().persistent().set(&key, &(owed + amount));
+        env.storage().persistent().extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
 }
 
 mod test;
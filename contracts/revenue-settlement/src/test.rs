@@ -1,11 +1,45 @@
 #![cfg(test)]
 use super::*;
 use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract as soroban_contract, contractimpl as soroban_contractimpl,
     testutils::{Address as _, Ledger},
     token::{Client as TokenClient, StellarAssetClient},
-    Address, Env,
+    vec, Address, Env, IntoVal,
 };
 
+/// A contract revenue source, such as escrow-vault, paying through settlement
+#[soroban_contract]
+pub struct MockSource;
+
+#[soroban_contractimpl]
+impl MockSource {
+    pub fn pay(env: Env, settlement: Address, token: Address, amount: i128, publisher: Address) -> u64 {
+        env.authorize_as_current_contract(vec![
+            &env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token,
+                    fn_name: symbol_short!("transfer"),
+                    args: vec![
+                        &env,
+                        env.current_contract_address().into_val(&env),
+                        settlement.into_val(&env),
+                        amount.into_val(&env),
+                    ],
+                },
+                sub_invocations: Vec::new(&env),
+            }),
+        ]);
+        RevenueSettlementContractClient::new(&env, &settlement).record_revenue(
+            &env.current_contract_address(),
+            &1u64,
+            &amount,
+            &publisher,
+        )
+    }
+}
+
 // ─── helpers ─────────────────────────────────────────────────────────────────
 
 fn deploy_token(env: &Env, admin: &Address) -> Address {
@@ -50,10 +84,16 @@ fn test_initialize() {
 
     let pool = client.get_revenue_pool();
     assert_eq!(pool.total_revenue, 0);
-    assert_eq!(pool.platform_pct, 250);
-    assert_eq!(pool.publisher_pct, 9_000);
-    assert_eq!(pool.treasury_pct, 500);
-    assert_eq!(pool.burn_pct, 250);
+    assert_eq!(
+        client.get_revenue_splits(),
+        vec![
+            &env,
+            RevenueSplit { target: SplitTarget::Account(platform), bps: 250 },
+            RevenueSplit { target: SplitTarget::Publisher, bps: 9_000 },
+            RevenueSplit { target: SplitTarget::Account(treasury), bps: 500 },
+            RevenueSplit { target: SplitTarget::Burn, bps: 250 },
+        ]
+    );
 }
 
 #[test]
@@ -96,10 +136,11 @@ fn test_initialize_non_admin_fails() {
 fn test_record_revenue_fee_split() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, admin, _, _, _, _) = setup(&env);
+    let (client, admin, _, token_addr, treasury, platform) = setup(&env);
     let publisher = Address::generate(&env);
 
     // Record 100_000 revenue
+    mint(&env, &token_addr, &admin, 100_000);
     let settlement_id = client.record_revenue(&admin, &1u64, &100_000i128, &publisher);
     assert_eq!(settlement_id, 1);
 
@@ -110,8 +151,9 @@ fn test_record_revenue_fee_split() {
     // publisher: 100_000 - 2_500 - 5_000 - 2_500 = 90_000
     let pool = client.get_revenue_pool();
     assert_eq!(pool.total_revenue, 100_000);
-    assert_eq!(pool.platform_share, 2_500);
-    assert_eq!(pool.treasury_share, 5_000);
+    assert_eq!(client.get_recipient_balance(&platform), 2_500);
+    assert_eq!(client.get_recipient_balance(&treasury), 5_000);
+    assert_eq!(pool.recipient_share, 7_500);
     assert_eq!(pool.burn_amount, 2_500);
     assert_eq!(pool.publisher_share, 90_000);
 
@@ -122,17 +164,19 @@ fn test_record_revenue_fee_split() {
     let record = client.get_settlement(&settlement_id).unwrap();
     assert_eq!(record.campaign_id, 1);
     assert_eq!(record.total_amount, 100_000);
-    assert_eq!(record.platform_fee, 2_500);
+    assert_eq!(record.burn_amount, 2_500);
     assert_eq!(record.publisher_amount, 90_000);
+    assert_eq!(record.splits, client.get_revenue_splits());
 }
 
 #[test]
 fn test_record_revenue_accumulates() {
     let env = Env::default();
     env.mock_all_auths();
-    let (client, admin, _, _, _, _) = setup(&env);
+    let (client, admin, _, token_addr, _, _) = setup(&env);
     let publisher = Address::generate(&env);
 
+    mint(&env, &token_addr, &admin, 150_000);
     client.record_revenue(&admin, &1u64, &100_000i128, &publisher);
     client.record_revenue(&admin, &2u64, &50_000i128, &publisher);
 
@@ -155,6 +199,115 @@ fn test_record_revenue_unauthorized() {
     client.record_revenue(&stranger, &1u64, &100_000i128, &publisher);
 }
 
+#[test]
+#[should_panic]
+fn test_record_revenue_cannot_claim_surplus() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr, _, _) = setup(&env);
+    let publisher = Address::generate(&env);
+
+    // Tokens already sitting in the contract do not back a new credit
+    mint(&env, &token_addr, &client.address, 100_000);
+    client.record_revenue(&admin, &1u64, &100_000i128, &publisher);
+}
+
+#[test]
+fn test_record_revenue_pulls_from_source() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr, _, _) = setup(&env);
+    let publisher = Address::generate(&env);
+
+    mint(&env, &token_addr, &admin, 100_000);
+    client.record_revenue(&admin, &1u64, &60_000i128, &publisher);
+
+    let tc = TokenClient::new(&env, &token_addr);
+    assert_eq!(tc.balance(&admin), 40_000);
+    assert_eq!(client.reconcile().surplus, 0);
+}
+
+#[test]
+fn test_authorized_source_records_transferred_revenue() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr, _, _) = setup(&env);
+    let orchestrator = Address::generate(&env);
+    let publisher = Address::generate(&env);
+    client.set_revenue_source(&admin, &orchestrator, &true);
+    assert!(client.is_revenue_source(&orchestrator));
+
+    mint(&env, &token_addr, &orchestrator, 40_000);
+    client.record_revenue(&orchestrator, &9u64, &40_000i128, &publisher);
+
+    assert_eq!(client.get_publisher_balance(&publisher), 36_000);
+}
+
+#[test]
+fn test_contract_source_authorizes_pull() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr, _, _) = setup(&env);
+    let source = env.register_contract(None, MockSource);
+    let publisher = Address::generate(&env);
+    client.set_revenue_source(&admin, &source, &true);
+    mint(&env, &token_addr, &source, 50_000);
+
+    // No mocked auths: the source contract authorizes its own transfer
+    env.set_auths(&[]);
+    MockSourceClient::new(&env, &source).pay(&client.address, &token_addr, &50_000i128, &publisher);
+
+    assert_eq!(client.get_publisher_balance(&publisher), 45_000);
+    assert_eq!(TokenClient::new(&env, &token_addr).balance(&client.address), 50_000);
+}
+
+#[test]
+#[should_panic(expected = "unauthorized")]
+fn test_revoked_source_cannot_record() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr, _, _) = setup(&env);
+    let source = Address::generate(&env);
+    client.set_revenue_source(&admin, &source, &true);
+    client.set_revenue_source(&admin, &source, &false);
+
+    mint(&env, &token_addr, &source, 10_000);
+    client.record_revenue(&source, &1u64, &10_000i128, &Address::generate(&env));
+}
+
+// ─── reconcile ───────────────────────────────────────────────────────────────
+
+#[test]
+fn test_reconcile_tracks_liabilities() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr, _, _) = setup(&env);
+    let publisher = Address::generate(&env);
+
+    mint(&env, &token_addr, &admin, 100_000);
+    mint(&env, &token_addr, &client.address, 20_000);
+    client.record_revenue(&admin, &1u64, &100_000i128, &publisher);
+
+    assert_eq!(
+        client.reconcile(),
+        Reconciliation {
+            token_balance: 120_000,
+            publisher_liabilities: 90_000,
+            recipient_liabilities: 7_500,
+            pending_burn: 2_500,
+            surplus: 20_000,
+        }
+    );
+
+    client.claim_publisher_balance(&publisher);
+    client.distribute_platform_revenue(&admin);
+
+    let rec = client.reconcile();
+    assert_eq!(rec.token_balance, 20_000);
+    assert_eq!(rec.publisher_liabilities + rec.recipient_liabilities + rec.pending_burn, 0);
+    assert_eq!(rec.surplus, 20_000);
+}
+
 // ─── distribute_platform_revenue ─────────────────────────────────────────────
 
 #[test]
@@ -173,7 +326,7 @@ fn test_distribute_platform_revenue() {
     client.initialize(&admin, &token_addr, &treasury, &platform);
 
     // Fund the contract
-    mint(&env, &token_addr, &contract_id, 10_000_000);
+    mint(&env, &token_addr, &admin, 10_000_000);
 
     let publisher = Address::generate(&env);
     client.record_revenue(&admin, &1u64, &100_000i128, &publisher);
@@ -188,9 +341,15 @@ fn test_distribute_platform_revenue() {
 
     // Pool should be reset for platform and treasury
     let pool = client.get_revenue_pool();
-    assert_eq!(pool.platform_share, 0);
-    assert_eq!(pool.treasury_share, 0);
+    assert_eq!(pool.recipient_share, 0);
+    assert_eq!(client.get_recipient_balance(&platform), 0);
+    assert_eq!(client.get_recipient_balance(&treasury), 0);
     assert_eq!(pool.last_settlement, 1000);
+
+    // Burn share is destroyed, publisher share stays for claiming
+    assert_eq!(pool.burn_amount, 0);
+    assert_eq!(pool.total_burned, 2_500);
+    assert_eq!(tc.balance(&contract_id), 100_000 - 2_500 - 5_000 - 2_500);
 }
 
 #[test]
@@ -204,6 +363,87 @@ fn test_distribute_unauthorized() {
     client.distribute_platform_revenue(&stranger);
 }
 
+// ─── set_revenue_splits ──────────────────────────────────────────────────────
+
+#[test]
+fn test_custom_splits_apply_to_new_settlements() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, token_addr, _, _) = setup(&env);
+    mint(&env, &token_addr, &admin, 1_000_000);
+    let publisher = Address::generate(&env);
+    let referrer = Address::generate(&env);
+    let dao = Address::generate(&env);
+
+    let first = client.record_revenue(&admin, &1u64, &100_000i128, &publisher);
+
+    let splits = vec![
+        &env,
+        RevenueSplit { target: SplitTarget::Publisher, bps: 8_000 },
+        RevenueSplit { target: SplitTarget::Account(referrer.clone()), bps: 1_000 },
+        RevenueSplit { target: SplitTarget::Account(dao.clone()), bps: 500 },
+        RevenueSplit { target: SplitTarget::Burn, bps: 500 },
+    ];
+    client.set_revenue_splits(&admin, &splits);
+    let second = client.record_revenue(&admin, &2u64, &100_000i128, &publisher);
+
+    assert_eq!(client.get_publisher_balance(&publisher), 90_000 + 80_000);
+    assert_eq!(client.get_recipient_balance(&referrer), 10_000);
+    assert_eq!(client.get_recipient_balance(&dao), 5_000);
+    assert_eq!(client.get_revenue_pool().burn_amount, 2_500 + 5_000);
+
+    // each record keeps the split that applied when it settled
+    assert_eq!(client.get_settlement(&first).unwrap().splits.len(), 4);
+    assert_eq!(client.get_settlement(&first).unwrap().publisher_amount, 90_000);
+    assert_eq!(client.get_settlement(&second).unwrap().splits, splits);
+
+    client.distribute_platform_revenue(&admin);
+    let tc = TokenClient::new(&env, &token_addr);
+    assert_eq!(tc.balance(&referrer), 10_000);
+    assert_eq!(tc.balance(&dao), 5_000);
+    assert_eq!(client.get_revenue_pool().total_burned, 7_500);
+}
+
+#[test]
+#[should_panic(expected = "splits must sum to 10000")]
+fn test_set_splits_bad_total() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, _, _, _) = setup(&env);
+
+    client.set_revenue_splits(&admin, &vec![
+        &env,
+        RevenueSplit { target: SplitTarget::Publisher, bps: 9_000 },
+        RevenueSplit { target: SplitTarget::Burn, bps: 500 },
+    ]);
+}
+
+#[test]
+#[should_panic(expected = "exactly one publisher split required")]
+fn test_set_splits_without_publisher() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _, _, treasury, _) = setup(&env);
+
+    client.set_revenue_splits(&admin, &vec![
+        &env,
+        RevenueSplit { target: SplitTarget::Account(treasury), bps: 10_000 },
+    ]);
+}
+
+#[test]
+#[should_panic(expected = "unauthorized")]
+fn test_set_splits_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, _, _, _) = setup(&env);
+
+    client.set_revenue_splits(&Address::generate(&env), &vec![
+        &env,
+        RevenueSplit { target: SplitTarget::Publisher, bps: 10_000 },
+    ]);
+}
+
 // ─── claim_publisher_balance ─────────────────────────────────────────────────
 
 #[test]
@@ -221,7 +461,7 @@ fn test_claim_publisher_balance() {
     let client = RevenueSettlementContractClient::new(&env, &contract_id);
     client.initialize(&admin, &token_addr, &treasury, &platform);
 
-    mint(&env, &token_addr, &contract_id, 10_000_000);
+    mint(&env, &token_addr, &admin, 10_000_000);
 
     let publisher = Address::generate(&env);
     client.record_revenue(&admin, &1u64, &100_000i128, &publisher);
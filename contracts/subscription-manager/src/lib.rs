@@ -1,10 +1,37 @@
 //! PulsarTrack - Subscription Manager (Soroban)
 //! Manages platform subscription plans and billing on Stellar.
+//!
+//! Auto-renewal pulls the plan price with `transfer_from`, so a subscriber
+//! approves this contract once and any keeper may call `renew` after
+//! `expires_at`. A renewal the allowance or balance cannot cover marks the
+//! subscription `Failed`; it can be retried during the grace period and
+//! lapses (auto-renew off) after it. A renewal called more than the grace
+//! period after `expires_at` starts the new period at the time of the call.
+//!
+//! `change_plan` starts a new period on the new tier immediately. The unused
+//! part of the current period is credited pro rata against the new price;
+//! any shortfall is charged and any excess is kept as account credit, which
+//! is applied to later renewals and plan changes. Every charge is appended to
+//! the subscriber's billing history.
+//!
+//! Plan quotas are enforced through `consume_campaign`,
+//! `consume_impressions` and `consume_publisher`, called by authorized usage
+//! reporters such as the campaign orchestrator, which hands back impressions
+//! it metered but did not deliver with `release_impressions`. Usage
+//! counters reset every 30 days from the start of the subscription, and
+//! whenever a lapsed renewal restarts the period.
+//!
+//! Events:
+//! - ("sub", "subbed"): [subscriber: Address, amount: i128]
+//! - ("sub", "renewed"): [subscriber: Address, amount: i128]
+//! - ("sub", "changed"): [subscriber: Address, charged: i128, credit: i128]
+//! - ("sub", "failed"): subscriber: Address
+//! - ("sub", "lapsed"): subscriber: Address
 
 #![no_std]
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short,
-    token, Address, Env, String,
+    token, Address, Env, String, Vec,
 };
 
 #[contracttype]
@@ -30,6 +57,36 @@ pub struct SubscriptionPlan {
     pub api_access: bool,
 }
 
+#[contracttype]
+#[derive(Clone, PartialEq)]
+pub enum SubscriptionStatus {
+    Active,
+    Failed, // renewal charge failed, inside the grace period
+    Lapsed,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum BillingKind {
+    Subscribe,
+    Renewal,
+    PlanChange,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct BillingEntry {
+    pub kind: BillingKind,
+    pub tier: SubscriptionTier,
+    pub is_annual: bool,
+    pub list_price: i128,
+    pub credit_applied: i128,
+    pub charged: i128,
+    pub period_start: u64,
+    pub period_end: u64,
+    pub billed_at: u64,
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct Subscription {
@@ -42,6 +99,11 @@ pub struct Subscription {
     pub auto_renew: bool,
     pub campaigns_used: u32,
     pub impressions_used: u64,
+    pub publishers_used: u32,
+    pub status: SubscriptionStatus,
+    pub failed_at: Option<u64>,
+    pub credit_balance: i128,
+    pub usage_reset_at: u64,
 }
 
 #[contracttype]
@@ -49,14 +111,22 @@ pub enum DataKey {
     Admin,
     TokenAddress,
     TreasuryAddress,
+    GracePeriod,
     Plan(SubscriptionTier),
     Subscription(Address),
+    BillingCount(Address),
+    Billing(Address, u32),
+    UsageReporter(Address),
+    PublisherUsed(Address, Address), // subscriber, publisher -> usage period it was counted in
 }
 
 const INSTANCE_LIFETIME_THRESHOLD: u32 = 17_280;
 const INSTANCE_BUMP_AMOUNT: u32 = 86_400;
 const PERSISTENT_LIFETIME_THRESHOLD: u32 = 120_960;
 const PERSISTENT_BUMP_AMOUNT: u32 = 1_051_200;
+const MONTH_SECS: u64 = 30 * 24 * 3600;
+const YEAR_SECS: u64 = 365 * 24 * 3600;
+const DEFAULT_GRACE_PERIOD_SECS: u64 = 259_200; // 3 days
 
 #[contract]
 pub struct SubscriptionManagerContract;
@@ -87,23 +157,24 @@ impl SubscriptionManagerContract {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         subscriber.require_auth();
 
+        let existing: Option<Subscription> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Subscription(subscriber.clone()));
+        if let Some(ref current) = existing {
+            if current.expires_at > env.ledger().timestamp() {
+                panic!("already subscribed");
+            }
+        }
+
         let plan: SubscriptionPlan = env
             .storage()
             .persistent()
             .get(&DataKey::Plan(tier.clone()))
             .expect("plan not found");
 
-        let amount = if is_annual {
-            plan.price_annual
-        } else {
-            plan.price_monthly
-        };
-
-        let duration_secs = if is_annual {
-            365 * 24 * 3600u64
-        } else {
-            30 * 24 * 3600u64
-        };
+        let amount = Self::_price(&plan, is_annual);
+        let duration_secs = Self::_period_secs(is_annual);
 
         let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
         let token_client = token::Client::new(&env, &token_addr);
@@ -121,11 +192,14 @@ impl SubscriptionManagerContract {
             auto_renew,
             campaigns_used: 0,
             impressions_used: 0,
+            publishers_used: 0,
+            status: SubscriptionStatus::Active,
+            failed_at: None,
+            credit_balance: existing.map(|old| old.credit_balance).unwrap_or(0),
+            usage_reset_at: now,
         };
-
-        let _ttl_key = DataKey::Subscription(subscriber.clone());
-        env.storage().persistent().set(&_ttl_key, &sub);
-        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        Self::_save(&env, &sub);
+        Self::_record_billing(&env, &sub, BillingKind::Subscribe, amount, 0, amount, now);
 
         env.events().publish(
             (symbol_short!("sub"), symbol_short!("subbed")),
@@ -133,6 +207,239 @@ impl SubscriptionManagerContract {
         );
     }
 
+    pub fn set_grace_period(env: Env, admin: Address, grace_secs: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        env.storage().instance().set(&DataKey::GracePeriod, &grace_secs);
+    }
+
+    /// Keeper-callable. Charges the next period from the subscriber's
+    /// allowance once the current one has expired; returns whether it renewed.
+    pub fn renew(env: Env, subscriber: Address) -> bool {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        let mut sub: Subscription = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Subscription(subscriber.clone()))
+            .expect("subscription not found");
+        if !sub.auto_renew || sub.status == SubscriptionStatus::Lapsed {
+            panic!("auto-renew disabled");
+        }
+        let now = env.ledger().timestamp();
+        if now < sub.expires_at {
+            panic!("not due");
+        }
+
+        let plan: SubscriptionPlan = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Plan(sub.tier.clone()))
+            .expect("plan not found");
+        let price = Self::_price(&plan, sub.is_annual);
+        let credit_applied = sub.credit_balance.min(price);
+        let amount = price - credit_applied;
+
+        let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+        let token_client = token::Client::new(&env, &token_addr);
+        let spender = env.current_contract_address();
+        let covered = amount == 0
+            || (token_client.allowance(&subscriber, &spender) >= amount
+                && token_client.balance(&subscriber) >= amount);
+
+        let grace: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::GracePeriod)
+            .unwrap_or(DEFAULT_GRACE_PERIOD_SECS);
+
+        if !covered {
+            let failed_at = *sub.failed_at.get_or_insert(now);
+            if now > failed_at + grace {
+                sub.status = SubscriptionStatus::Lapsed;
+                sub.auto_renew = false;
+                env.events().publish(
+                    (symbol_short!("sub"), symbol_short!("lapsed")),
+                    subscriber.clone(),
+                );
+            } else {
+                sub.status = SubscriptionStatus::Failed;
+                env.events().publish(
+                    (symbol_short!("sub"), symbol_short!("failed")),
+                    subscriber.clone(),
+                );
+            }
+            Self::_save(&env, &sub);
+            return false;
+        }
+
+        if amount > 0 {
+            let treasury: Address = env.storage().instance().get(&DataKey::TreasuryAddress).unwrap();
+            token_client.transfer_from(&spender, &subscriber, &treasury, &amount);
+        }
+
+        // On-time renewals continue from the old expiry; recovered ones and
+        // renewals called later than the grace period restart now
+        let restart = sub.status == SubscriptionStatus::Failed || now > sub.expires_at + grace;
+        let period_start = if restart { now } else { sub.expires_at };
+        if restart {
+            sub.campaigns_used = 0;
+            sub.impressions_used = 0;
+            sub.publishers_used = 0;
+            sub.usage_reset_at = now;
+        }
+        sub.expires_at = period_start + Self::_period_secs(sub.is_annual);
+        sub.amount_paid += amount;
+        sub.credit_balance -= credit_applied;
+        sub.status = SubscriptionStatus::Active;
+        sub.failed_at = None;
+        Self::_save(&env, &sub);
+        Self::_record_billing(&env, &sub, BillingKind::Renewal, price, credit_applied, amount, period_start);
+
+        env.events().publish(
+            (symbol_short!("sub"), symbol_short!("renewed")),
+            (subscriber, amount),
+        );
+        true
+    }
+
+    /// Switch tier and/or billing cycle now. The unused share of the current
+    /// period's price is credited against the new plan's price.
+    pub fn change_plan(env: Env, subscriber: Address, new_tier: SubscriptionTier, is_annual: bool) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        subscriber.require_auth();
+
+        let mut sub: Subscription = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Subscription(subscriber.clone()))
+            .expect("subscription not found");
+        let now = env.ledger().timestamp();
+        if sub.expires_at <= now {
+            panic!("subscription expired");
+        }
+        if sub.tier == new_tier && sub.is_annual == is_annual {
+            panic!("same plan");
+        }
+
+        let current_plan: SubscriptionPlan = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Plan(sub.tier.clone()))
+            .expect("plan not found");
+        let new_plan: SubscriptionPlan = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Plan(new_tier.clone()))
+            .expect("plan not found");
+
+        let period = Self::_period_secs(sub.is_annual);
+        let remaining = (sub.expires_at - now).min(period);
+        let unused = Self::_price(&current_plan, sub.is_annual) * remaining as i128 / period as i128;
+
+        let price = Self::_price(&new_plan, is_annual);
+        let available = sub.credit_balance + unused;
+        let credit_applied = available.min(price);
+        let charged = price - credit_applied;
+
+        if charged > 0 {
+            let token_addr: Address = env.storage().instance().get(&DataKey::TokenAddress).unwrap();
+            let treasury: Address = env.storage().instance().get(&DataKey::TreasuryAddress).unwrap();
+            token::Client::new(&env, &token_addr).transfer(&subscriber, &treasury, &charged);
+        }
+
+        sub.tier = new_tier;
+        sub.is_annual = is_annual;
+        sub.started_at = now;
+        sub.expires_at = now + Self::_period_secs(is_annual);
+        sub.amount_paid += charged;
+        sub.credit_balance = available - credit_applied;
+        sub.status = SubscriptionStatus::Active;
+        sub.failed_at = None;
+        Self::_save(&env, &sub);
+        Self::_record_billing(&env, &sub, BillingKind::PlanChange, price, credit_applied, charged, now);
+
+        env.events().publish(
+            (symbol_short!("sub"), symbol_short!("changed")),
+            (subscriber, charged, sub.credit_balance),
+        );
+        charged
+    }
+
+    pub fn set_usage_reporter(env: Env, admin: Address, reporter: Address, allowed: bool) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        let _ttl_key = DataKey::UsageReporter(reporter);
+        env.storage().persistent().set(&_ttl_key, &allowed);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    /// Count one new campaign against the subscriber's plan.
+    pub fn consume_campaign(env: Env, reporter: Address, subscriber: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let (mut sub, plan) = Self::_metered(&env, &reporter, &subscriber);
+        if sub.campaigns_used >= plan.max_campaigns {
+            panic!("campaign quota exceeded");
+        }
+        sub.campaigns_used += 1;
+        Self::_save(&env, &sub);
+    }
+
+    /// Count `count` impressions against the subscriber's monthly allowance.
+    pub fn consume_impressions(env: Env, reporter: Address, subscriber: Address, count: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let (mut sub, plan) = Self::_metered(&env, &reporter, &subscriber);
+        if sub.impressions_used + count > plan.max_impressions_per_month {
+            panic!("impression quota exceeded");
+        }
+        sub.impressions_used += count;
+        Self::_save(&env, &sub);
+    }
+
+    /// Give back `count` impressions metered at `metered_at` that were not
+    /// delivered, e.g. views rejected after a batch was committed. Usage from
+    /// an earlier period is not returned to the current one.
+    pub fn release_impressions(env: Env, reporter: Address, subscriber: Address, count: u64, metered_at: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_reporter(&env, &reporter);
+        let mut sub: Subscription = match env.storage().persistent().get(&DataKey::Subscription(subscriber)) {
+            Some(sub) => sub,
+            None => return,
+        };
+        if metered_at < sub.usage_reset_at {
+            return;
+        }
+        sub.impressions_used = sub.impressions_used.saturating_sub(count);
+        Self::_save(&env, &sub);
+    }
+
+    /// Count `publisher` against the subscriber's publisher allowance the
+    /// first time it is used in the current period.
+    pub fn consume_publisher(env: Env, reporter: Address, subscriber: Address, publisher: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let (mut sub, plan) = Self::_metered(&env, &reporter, &subscriber);
+        let key = DataKey::PublisherUsed(subscriber, publisher);
+        let counted_in: Option<u64> = env.storage().persistent().get(&key);
+        if counted_in == Some(sub.usage_reset_at) {
+            return;
+        }
+        if sub.publishers_used >= plan.max_publishers {
+            panic!("publisher quota exceeded");
+        }
+        sub.publishers_used += 1;
+        Self::_save(&env, &sub);
+        env.storage().persistent().set(&key, &sub.usage_reset_at);
+        env.storage().persistent().extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
     pub fn cancel_subscription(env: Env, subscriber: Address) {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         subscriber.require_auth();
@@ -167,11 +474,128 @@ impl SubscriptionManagerContract {
         env.storage().persistent().get(&DataKey::Subscription(subscriber))
     }
 
+    pub fn get_billing_count(env: Env, subscriber: Address) -> u32 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::BillingCount(subscriber)).unwrap_or(0)
+    }
+
+    pub fn get_billing_history(env: Env, subscriber: Address) -> Vec<BillingEntry> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let count: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::BillingCount(subscriber.clone()))
+            .unwrap_or(0);
+        let mut history = Vec::new(&env);
+        for index in 0..count {
+            if let Some(entry) = env.storage().persistent().get(&DataKey::Billing(subscriber.clone(), index)) {
+                history.push_back(entry);
+            }
+        }
+        history
+    }
+
     pub fn get_plan(env: Env, tier: SubscriptionTier) -> Option<SubscriptionPlan> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage().persistent().get(&DataKey::Plan(tier))
     }
 
+    fn _require_reporter(env: &Env, reporter: &Address) {
+        reporter.require_auth();
+        let allowed: bool = env
+            .storage()
+            .persistent()
+            .get(&DataKey::UsageReporter(reporter.clone()))
+            .unwrap_or(false);
+        if !allowed {
+            panic!("unauthorized reporter");
+        }
+    }
+
+    /// Load an active subscription for metering, rolling its usage window forward
+    fn _metered(env: &Env, reporter: &Address, subscriber: &Address) -> (Subscription, SubscriptionPlan) {
+        Self::_require_reporter(env, reporter);
+
+        let mut sub: Subscription = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Subscription(subscriber.clone()))
+            .expect("subscription not found");
+        let now = env.ledger().timestamp();
+        if sub.expires_at <= now {
+            panic!("subscription inactive");
+        }
+        if now >= sub.usage_reset_at + MONTH_SECS {
+            let elapsed = (now - sub.usage_reset_at) / MONTH_SECS;
+            sub.usage_reset_at += elapsed * MONTH_SECS;
+            sub.campaigns_used = 0;
+            sub.impressions_used = 0;
+            sub.publishers_used = 0;
+        }
+
+        let plan: SubscriptionPlan = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Plan(sub.tier.clone()))
+            .expect("plan not found");
+        (sub, plan)
+    }
+
+    fn _price(plan: &SubscriptionPlan, is_annual: bool) -> i128 {
+        if is_annual {
+            plan.price_annual
+        } else {
+            plan.price_monthly
+        }
+    }
+
+    fn _period_secs(is_annual: bool) -> u64 {
+        if is_annual {
+            YEAR_SECS
+        } else {
+            MONTH_SECS
+        }
+    }
+
+    fn _record_billing(
+        env: &Env,
+        sub: &Subscription,
+        kind: BillingKind,
+        list_price: i128,
+        credit_applied: i128,
+        charged: i128,
+        period_start: u64,
+    ) {
+        let index: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::BillingCount(sub.subscriber.clone()))
+            .unwrap_or(0);
+        let entry = BillingEntry {
+            kind,
+            tier: sub.tier.clone(),
+            is_annual: sub.is_annual,
+            list_price,
+            credit_applied,
+            charged,
+            period_start,
+            period_end: sub.expires_at,
+            billed_at: env.ledger().timestamp(),
+        };
+        let _ttl_key = DataKey::Billing(sub.subscriber.clone(), index);
+        env.storage().persistent().set(&_ttl_key, &entry);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        let _ttl_key = DataKey::BillingCount(sub.subscriber.clone());
+        env.storage().persistent().set(&_ttl_key, &(index + 1));
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    fn _save(env: &Env, sub: &Subscription) {
+        let _ttl_key = DataKey::Subscription(sub.subscriber.clone());
+        env.storage().persistent().set(&_ttl_key, sub);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
     fn _init_plans(env: &Env) {
         let plans = [
             (SubscriptionTier::Starter, "Starter", 99_000_000i128, 990_000_000i128, 5u32, 100_000u64, 10u32, false, false),
@@ -1,6 +1,10 @@
 #![cfg(test)]
 use super::*;
-use soroban_sdk::{testutils::Address as _, token::StellarAssetClient, Address, Env};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env,
+};
 
 fn deploy_token(env: &Env, admin: &Address) -> Address {
     env.register_stellar_asset_contract_v2(admin.clone()).address()
@@ -69,3 +73,304 @@ fn test_get_subscription_nonexistent() {
     let (c, _, _, _) = setup(&env);
     assert!(c.get_subscription(&Address::generate(&env)).is_none());
 }
+
+// ─── renew ───────────────────────────────────────────────────────────────────
+
+const STARTER_MONTHLY: i128 = 99_000_000;
+const MONTH: u64 = 30 * 24 * 3600;
+
+/// Monthly Starter subscriber who approved `allowance` for renewals
+fn subscribed(env: &Env, c: &SubscriptionManagerContractClient, token: &Address, allowance: i128) -> Address {
+    let subscriber = Address::generate(env);
+    mint(env, token, &subscriber, 500_000_000);
+    c.subscribe(&subscriber, &SubscriptionTier::Starter, &false, &true);
+    TokenClient::new(env, token).approve(&subscriber, &c.address, &allowance, &1_000_000u32);
+    subscriber
+}
+
+#[test]
+fn test_renew_pulls_from_allowance() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, _, token) = setup(&env);
+    let subscriber = subscribed(&env, &c, &token, STARTER_MONTHLY * 3);
+
+    env.set_auths(&[]);
+    env.ledger().with_mut(|li| li.timestamp = MONTH + 10);
+    assert!(c.renew(&subscriber));
+
+    let sub = c.get_subscription(&subscriber).unwrap();
+    assert_eq!(sub.expires_at, 2 * MONTH);
+    assert_eq!(sub.amount_paid, 2 * STARTER_MONTHLY);
+    assert_eq!(TokenClient::new(&env, &token).balance(&subscriber), 500_000_000 - 2 * STARTER_MONTHLY);
+}
+
+#[test]
+fn test_late_renewal_restarts_period() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, _, token) = setup(&env);
+    c.set_grace_period(&admin, &3_600u64);
+    let subscriber = subscribed(&env, &c, &token, STARTER_MONTHLY * 3);
+
+    let late = 3 * MONTH;
+    env.ledger().with_mut(|li| li.timestamp = late);
+    assert!(c.renew(&subscriber));
+
+    let sub = c.get_subscription(&subscriber).unwrap();
+    assert_eq!(sub.expires_at, late + MONTH);
+    assert_eq!(sub.usage_reset_at, late);
+    assert_eq!(sub.amount_paid, 2 * STARTER_MONTHLY);
+}
+
+#[test]
+fn test_renewal_within_grace_continues_from_expiry() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, _, token) = setup(&env);
+    c.set_grace_period(&admin, &3_600u64);
+    let subscriber = subscribed(&env, &c, &token, STARTER_MONTHLY);
+
+    env.ledger().with_mut(|li| li.timestamp = MONTH + 3_600);
+    assert!(c.renew(&subscriber));
+    assert_eq!(c.get_subscription(&subscriber).unwrap().expires_at, 2 * MONTH);
+}
+
+#[test]
+#[should_panic(expected = "not due")]
+fn test_renew_before_expiry() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, _, token) = setup(&env);
+    let subscriber = subscribed(&env, &c, &token, STARTER_MONTHLY);
+    c.renew(&subscriber);
+}
+
+#[test]
+#[should_panic(expected = "auto-renew disabled")]
+fn test_renew_after_cancel() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, _, token) = setup(&env);
+    let subscriber = subscribed(&env, &c, &token, STARTER_MONTHLY);
+    c.cancel_subscription(&subscriber);
+    env.ledger().with_mut(|li| li.timestamp = MONTH);
+    c.renew(&subscriber);
+}
+
+#[test]
+fn test_failed_renewal_retried_in_grace() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, _, token) = setup(&env);
+    let subscriber = subscribed(&env, &c, &token, 0);
+
+    env.ledger().with_mut(|li| li.timestamp = MONTH);
+    assert!(!c.renew(&subscriber));
+    let sub = c.get_subscription(&subscriber).unwrap();
+    assert!(matches!(sub.status, SubscriptionStatus::Failed));
+    assert!(!c.is_active(&subscriber));
+
+    TokenClient::new(&env, &token).approve(&subscriber, &c.address, &STARTER_MONTHLY, &1_000_000u32);
+    env.ledger().with_mut(|li| li.timestamp = MONTH + 100);
+    assert!(c.renew(&subscriber));
+    let sub = c.get_subscription(&subscriber).unwrap();
+    assert!(matches!(sub.status, SubscriptionStatus::Active));
+    assert_eq!(sub.expires_at, MONTH + 100 + MONTH);
+}
+
+#[test]
+fn test_failed_renewal_lapses_after_grace() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, _, token) = setup(&env);
+    c.set_grace_period(&admin, &3_600u64);
+    let subscriber = subscribed(&env, &c, &token, 0);
+
+    env.ledger().with_mut(|li| li.timestamp = MONTH);
+    assert!(!c.renew(&subscriber));
+    env.ledger().with_mut(|li| li.timestamp = MONTH + 3_601);
+    assert!(!c.renew(&subscriber));
+
+    let sub = c.get_subscription(&subscriber).unwrap();
+    assert!(matches!(sub.status, SubscriptionStatus::Lapsed));
+    assert!(!sub.auto_renew);
+}
+
+#[test]
+#[should_panic(expected = "already subscribed")]
+fn test_subscribe_while_active() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, _, token) = setup(&env);
+    let subscriber = subscribed(&env, &c, &token, 0);
+    c.subscribe(&subscriber, &SubscriptionTier::Growth, &false, &true);
+}
+
+#[test]
+fn test_upgrade_prorates_remaining_period() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, _, token) = setup(&env);
+    let subscriber = subscribed(&env, &c, &token, 0);
+
+    env.ledger().with_mut(|li| li.timestamp = MONTH / 2);
+    let charged = c.change_plan(&subscriber, &SubscriptionTier::Growth, &false);
+    assert_eq!(charged, 299_000_000 - STARTER_MONTHLY / 2);
+
+    let sub = c.get_subscription(&subscriber).unwrap();
+    assert!(matches!(sub.tier, SubscriptionTier::Growth));
+    assert_eq!(sub.expires_at, MONTH / 2 + MONTH);
+    assert_eq!(sub.credit_balance, 0);
+    assert_eq!(TokenClient::new(&env, &token).balance(&subscriber), 500_000_000 - STARTER_MONTHLY - charged);
+}
+
+#[test]
+fn test_downgrade_credit_applied_on_renewal() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, _, token) = setup(&env);
+    let subscriber = subscribed(&env, &c, &token, STARTER_MONTHLY);
+
+    env.ledger().with_mut(|li| li.timestamp = MONTH / 2);
+    c.change_plan(&subscriber, &SubscriptionTier::Growth, &false);
+    env.ledger().with_mut(|li| li.timestamp = MONTH);
+    assert_eq!(c.change_plan(&subscriber, &SubscriptionTier::Starter, &false), 0);
+
+    // Half of Growth's 299 unused, minus Starter's 99
+    let credit = 299_000_000 / 2 - STARTER_MONTHLY;
+    assert_eq!(c.get_subscription(&subscriber).unwrap().credit_balance, credit);
+
+    let before = TokenClient::new(&env, &token).balance(&subscriber);
+    env.ledger().with_mut(|li| li.timestamp = 2 * MONTH);
+    assert!(c.renew(&subscriber));
+    assert_eq!(TokenClient::new(&env, &token).balance(&subscriber), before - (STARTER_MONTHLY - credit));
+    assert_eq!(c.get_subscription(&subscriber).unwrap().credit_balance, 0);
+
+    let history = c.get_billing_history(&subscriber);
+    assert_eq!(history.len(), 4);
+    let renewal = history.get(3).unwrap();
+    assert_eq!(renewal.kind, BillingKind::Renewal);
+    assert_eq!(renewal.list_price, STARTER_MONTHLY);
+    assert_eq!(renewal.credit_applied, credit);
+    assert_eq!(renewal.charged, STARTER_MONTHLY - credit);
+    assert_eq!(history.get(2).unwrap().kind, BillingKind::PlanChange);
+}
+
+#[test]
+#[should_panic(expected = "same plan")]
+fn test_change_to_same_plan() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, _, token) = setup(&env);
+    let subscriber = subscribed(&env, &c, &token, 0);
+    c.change_plan(&subscriber, &SubscriptionTier::Starter, &false);
+}
+
+fn metered(env: &Env) -> (SubscriptionManagerContractClient<'_>, Address, Address) {
+    let (c, admin, _, token) = setup(env);
+    let reporter = Address::generate(env);
+    c.set_usage_reporter(&admin, &reporter, &true);
+    let subscriber = subscribed(env, &c, &token, 0);
+    (c, reporter, subscriber)
+}
+
+#[test]
+#[should_panic(expected = "campaign quota exceeded")]
+fn test_campaign_quota_enforced() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, reporter, subscriber) = metered(&env);
+    // Starter allows 5 campaigns
+    for _ in 0..6 {
+        c.consume_campaign(&reporter, &subscriber);
+    }
+}
+
+#[test]
+#[should_panic(expected = "impression quota exceeded")]
+fn test_impression_quota_enforced() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, reporter, subscriber) = metered(&env);
+    c.consume_impressions(&reporter, &subscriber, &100_000u64);
+    c.consume_impressions(&reporter, &subscriber, &1u64);
+}
+
+#[test]
+fn test_usage_resets_each_period() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, _, token) = setup(&env);
+    let reporter = Address::generate(&env);
+    c.set_usage_reporter(&admin, &reporter, &true);
+    let subscriber = subscribed(&env, &c, &token, STARTER_MONTHLY);
+    c.consume_impressions(&reporter, &subscriber, &100_000u64);
+    c.consume_campaign(&reporter, &subscriber);
+
+    env.ledger().with_mut(|li| li.timestamp = MONTH);
+    assert!(c.renew(&subscriber));
+    c.consume_impressions(&reporter, &subscriber, &10u64);
+
+    let sub = c.get_subscription(&subscriber).unwrap();
+    assert_eq!(sub.impressions_used, 10);
+    assert_eq!(sub.campaigns_used, 0);
+    assert_eq!(sub.usage_reset_at, MONTH);
+}
+
+#[test]
+#[should_panic(expected = "publisher quota exceeded")]
+fn test_publisher_quota_enforced() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, reporter, subscriber) = metered(&env);
+    // Starter allows 10 publishers
+    for _ in 0..11 {
+        c.consume_publisher(&reporter, &subscriber, &Address::generate(&env));
+    }
+}
+
+#[test]
+fn test_publisher_counted_once_per_period() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, _, token) = setup(&env);
+    let reporter = Address::generate(&env);
+    c.set_usage_reporter(&admin, &reporter, &true);
+    let subscriber = subscribed(&env, &c, &token, STARTER_MONTHLY);
+    let publisher = Address::generate(&env);
+    c.consume_publisher(&reporter, &subscriber, &publisher);
+    c.consume_publisher(&reporter, &subscriber, &publisher);
+    assert_eq!(c.get_subscription(&subscriber).unwrap().publishers_used, 1);
+
+    env.ledger().with_mut(|li| li.timestamp = MONTH);
+    assert!(c.renew(&subscriber));
+    c.consume_publisher(&reporter, &subscriber, &publisher);
+    assert_eq!(c.get_subscription(&subscriber).unwrap().publishers_used, 1);
+}
+
+#[test]
+fn test_release_impressions_within_period() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, reporter, subscriber) = metered(&env);
+    c.consume_impressions(&reporter, &subscriber, &100_000u64);
+    c.release_impressions(&reporter, &subscriber, &40u64, &0u64);
+    assert_eq!(c.get_subscription(&subscriber).unwrap().impressions_used, 100_000 - 40);
+    c.consume_impressions(&reporter, &subscriber, &40u64);
+}
+
+#[test]
+fn test_release_impressions_from_earlier_period_ignored() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, _, token) = setup(&env);
+    let reporter = Address::generate(&env);
+    c.set_usage_reporter(&admin, &reporter, &true);
+    let subscriber = subscribed(&env, &c, &token, STARTER_MONTHLY);
+
+    env.ledger().with_mut(|li| li.timestamp = MONTH);
+    assert!(c.renew(&subscriber));
+    c.consume_impressions(&reporter, &subscriber, &10u64);
+    c.release_impressions(&reporter, &subscriber, &10u64, &(MONTH - 1));
+    assert_eq!(c.get_subscription(&subscriber).unwrap().impressions_used, 10);
+}
+
+#[test]
+#[should_panic(expected = "unauthorized reporter")]
+fn test_consume_by_unknown_reporter() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, subscriber) = metered(&env);
+    c.consume_campaign(&Address::generate(&env), &subscriber);
+}
+
+#[test]
+#[should_panic(expected = "subscription inactive")]
+fn test_consume_after_expiry() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, reporter, subscriber) = metered(&env);
+    env.ledger().with_mut(|li| li.timestamp = MONTH);
+    c.consume_campaign(&reporter, &subscriber);
+}
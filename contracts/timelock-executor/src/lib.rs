@@ -1,10 +1,15 @@
 //! PulsarTrack - Timelock Executor (Soroban)
 //! Time-locked execution of governance decisions on Stellar.
+//!
+//! Events:
+//! - ("timelock", "queued"): [entry_id: u64, proposer: Address]
+//! - ("timelock", "executed"): [entry_id: u64, result: Val]
+//! - ("timelock", "failed"): [entry_id: u64, error_code: u32]
 
 #![no_std]
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short,
-    Address, Env, String,
+    Address, Env, Error, InvokeError, String, Symbol, Val, Vec,
 };
 
 #[contracttype]
@@ -12,6 +17,7 @@ use soroban_sdk::{
 pub enum TimelockStatus {
     Queued,
     Executed,
+    Failed,
     Cancelled,
     Expired,
 }
@@ -22,13 +28,15 @@ pub struct TimelockEntry {
     pub entry_id: u64,
     pub proposer: Address,
     pub target_contract: Address,
-    pub function_name: String,
+    pub function_name: Symbol,
+    pub args: Vec<Val>,
     pub description: String,
     pub eta: u64,              // Earliest time of execution (timestamp)
     pub grace_period: u64,     // How long after ETA it can still be executed
     pub status: TimelockStatus,
     pub queued_at: u64,
     pub executed_at: Option<u64>,
+    pub error_code: Option<u32>, // set when the call failed; 0 if it aborted
 }
 
 #[contracttype]
@@ -40,6 +48,8 @@ pub enum DataKey {
     GracePeriod,
     EntryCounter,
     Entry(u64),
+    Result(u64),
+    Proposer(Address),
 }
 
 const INSTANCE_LIFETIME_THRESHOLD: u32 = 17_280;
@@ -76,7 +86,8 @@ impl TimelockExecutorContract {
         env: Env,
         proposer: Address,
         target_contract: Address,
-        function_name: String,
+        function_name: Symbol,
+        args: Vec<Val>,
         description: String,
         delay_secs: u64,
     ) -> u64 {
@@ -84,7 +95,12 @@ impl TimelockExecutorContract {
         proposer.require_auth();
 
         let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        if proposer != admin {
+        let is_proposer: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Proposer(proposer.clone()))
+            .unwrap_or(false);
+        if proposer != admin && !is_proposer {
             panic!("unauthorized");
         }
 
@@ -106,12 +122,14 @@ impl TimelockExecutorContract {
             proposer: proposer.clone(),
             target_contract,
             function_name,
+            args,
             description,
             eta: now + delay_secs,
             grace_period: grace,
             status: TimelockStatus::Queued,
             queued_at: now,
             executed_at: None,
+            error_code: None,
         };
 
         let _ttl_key = DataKey::Entry(entry_id);
@@ -152,24 +170,49 @@ impl TimelockExecutorContract {
             panic!("timelock not expired");
         }
 
+        // The entry reads as Expired from here on (see get_status)
         if now > entry.eta + entry.grace_period {
-            entry.status = TimelockStatus::Expired;
-            let _ttl_key = DataKey::Entry(entry_id);
-            env.storage().persistent().set(&_ttl_key, &entry);
-            env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
             panic!("grace period expired");
         }
 
-        entry.status = TimelockStatus::Executed;
+        // A failing target must not revert the timelock; record the failure instead
+        let outcome: Result<Val, u32> = match env.try_invoke_contract::<Val, Error>(
+            &entry.target_contract,
+            &entry.function_name,
+            entry.args.clone(),
+        ) {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(_)) => Err(0),
+            Err(Ok(err)) => Err(err.get_code()),
+            Err(Err(InvokeError::Contract(code))) => Err(code),
+            Err(Err(InvokeError::Abort)) => Err(0),
+        };
+
         entry.executed_at = Some(now);
+        match outcome {
+            Ok(result) => {
+                entry.status = TimelockStatus::Executed;
+                let _ttl_key = DataKey::Result(entry_id);
+                env.storage().persistent().set(&_ttl_key, &result);
+                env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+                env.events().publish(
+                    (symbol_short!("timelock"), symbol_short!("executed")),
+                    (entry_id, result),
+                );
+            }
+            Err(code) => {
+                entry.status = TimelockStatus::Failed;
+                entry.error_code = Some(code);
+                env.events().publish(
+                    (symbol_short!("timelock"), symbol_short!("failed")),
+                    (entry_id, code),
+                );
+            }
+        }
+
         let _ttl_key = DataKey::Entry(entry_id);
         env.storage().persistent().set(&_ttl_key, &entry);
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
-
-        env.events().publish(
-            (symbol_short!("timelock"), symbol_short!("executed")),
-            entry_id,
-        );
     }
 
     pub fn cancel(env: Env, admin: Address, entry_id: u64) {
@@ -196,11 +239,49 @@ impl TimelockExecutorContract {
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
     }
 
+    /// Admin: allow or revoke a contract (e.g. governance-dao) to queue entries.
+    pub fn set_proposer(env: Env, admin: Address, proposer: Address, allowed: bool) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+
+        if allowed {
+            env.storage().instance().set(&DataKey::Proposer(proposer), &true);
+        } else {
+            env.storage().instance().remove(&DataKey::Proposer(proposer));
+        }
+    }
+
     pub fn get_entry(env: Env, entry_id: u64) -> Option<TimelockEntry> {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage().persistent().get(&DataKey::Entry(entry_id))
     }
 
+    /// An entry still queued after its grace period reports `Expired`.
+    pub fn get_status(env: Env, entry_id: u64) -> Option<TimelockStatus> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let now = env.ledger().timestamp();
+        env.storage()
+            .persistent()
+            .get::<DataKey, TimelockEntry>(&DataKey::Entry(entry_id))
+            .map(|entry| {
+                if entry.status == TimelockStatus::Queued && now > entry.eta + entry.grace_period {
+                    TimelockStatus::Expired
+                } else {
+                    entry.status
+                }
+            })
+    }
+
+    /// Return value of a successfully executed entry.
+    pub fn get_result(env: Env, entry_id: u64) -> Option<Val> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::Result(entry_id))
+    }
+
     pub fn is_ready(env: Env, entry_id: u64) -> bool {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         if let Some(entry) = env.storage().persistent().get::<DataKey, TimelockEntry>(&DataKey::Entry(entry_id)) {
@@ -1,10 +1,34 @@
 #![cfg(test)]
 use super::*;
 use soroban_sdk::{
+    contract as soroban_contract, contractimpl as soroban_contractimpl,
     testutils::{Address as _, Ledger},
-    Address, Env, String,
+    vec, Address, Env, IntoVal, String, Symbol, Val, Vec,
 };
 
+// ─── Mock governed contract ──────────────────────────────────────────────────
+// Target for queued calls: `set_value` records its argument and returns it
+// doubled, `fail` always panics.
+
+#[soroban_contract]
+pub struct MockTarget;
+
+#[soroban_contractimpl]
+impl MockTarget {
+    pub fn set_value(env: Env, value: u32) -> u32 {
+        env.storage().instance().set(&0u32, &value);
+        value * 2
+    }
+
+    pub fn get_value(env: Env) -> Option<u32> {
+        env.storage().instance().get(&0u32)
+    }
+
+    pub fn fail(_env: Env) {
+        panic!("target failed");
+    }
+}
+
 // ─── helpers ─────────────────────────────────────────────────────────────────
 
 fn setup(env: &Env) -> (TimelockExecutorContractClient, Address, Address) {
@@ -19,8 +43,12 @@ fn setup(env: &Env) -> (TimelockExecutorContractClient, Address, Address) {
     (client, admin, executor)
 }
 
-fn make_fn(env: &Env) -> String {
-    String::from_str(env, "upgrade")
+fn make_fn(env: &Env) -> Symbol {
+    Symbol::new(env, "upgrade")
+}
+
+fn no_args(env: &Env) -> Vec<Val> {
+    Vec::new(env)
 }
 
 fn make_desc(env: &Env) -> String {
@@ -76,7 +104,7 @@ fn test_queue_entry() {
     let (client, admin, _) = setup(&env);
     let target = Address::generate(&env);
 
-    let entry_id = client.queue(&admin, &target, &make_fn(&env), &make_desc(&env), &500u64);
+    let entry_id = client.queue(&admin, &target, &make_fn(&env), &no_args(&env), &make_desc(&env), &500u64);
     assert_eq!(entry_id, 1);
 
     let entry = client.get_entry(&entry_id).unwrap();
@@ -94,7 +122,7 @@ fn test_queue_by_non_admin() {
     let stranger = Address::generate(&env);
     let target = Address::generate(&env);
 
-    client.queue(&stranger, &target, &make_fn(&env), &make_desc(&env), &500u64);
+    client.queue(&stranger, &target, &make_fn(&env), &no_args(&env), &make_desc(&env), &500u64);
 }
 
 #[test]
@@ -106,7 +134,7 @@ fn test_queue_delay_too_short() {
     let target = Address::generate(&env);
 
     // min_delay=100, so 50 is too short
-    client.queue(&admin, &target, &make_fn(&env), &make_desc(&env), &50u64);
+    client.queue(&admin, &target, &make_fn(&env), &no_args(&env), &make_desc(&env), &50u64);
 }
 
 #[test]
@@ -118,7 +146,36 @@ fn test_queue_delay_too_long() {
     let target = Address::generate(&env);
 
     // max_delay=86_400, so 100_000 is too long
-    client.queue(&admin, &target, &make_fn(&env), &make_desc(&env), &100_000u64);
+    client.queue(&admin, &target, &make_fn(&env), &no_args(&env), &make_desc(&env), &100_000u64);
+}
+
+#[test]
+fn test_authorized_proposer_can_queue() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _) = setup(&env);
+    let dao = Address::generate(&env);
+    let target = Address::generate(&env);
+
+    client.set_proposer(&admin, &dao, &true);
+    let entry_id = client.queue(&dao, &target, &make_fn(&env), &no_args(&env), &make_desc(&env), &500u64);
+
+    assert_eq!(client.get_entry(&entry_id).unwrap().proposer, dao);
+    assert!(matches!(client.get_status(&entry_id), Some(TimelockStatus::Queued)));
+}
+
+#[test]
+#[should_panic(expected = "unauthorized")]
+fn test_revoked_proposer_cannot_queue() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _) = setup(&env);
+    let dao = Address::generate(&env);
+    let target = Address::generate(&env);
+
+    client.set_proposer(&admin, &dao, &true);
+    client.set_proposer(&admin, &dao, &false);
+    client.queue(&dao, &target, &make_fn(&env), &no_args(&env), &make_desc(&env), &500u64);
 }
 
 // ─── execute ─────────────────────────────────────────────────────────────────
@@ -128,9 +185,12 @@ fn test_execute_entry() {
     let env = Env::default();
     env.mock_all_auths();
     let (client, admin, executor) = setup(&env);
-    let target = Address::generate(&env);
+    let target = env.register_contract(None, MockTarget);
+    let args: Vec<Val> = vec![&env, 21u32.into_val(&env)];
 
-    let entry_id = client.queue(&admin, &target, &make_fn(&env), &make_desc(&env), &500u64);
+    let entry_id = client.queue(
+        &admin, &target, &Symbol::new(&env, "set_value"), &args, &make_desc(&env), &500u64,
+    );
 
     // Advance past ETA but within grace period
     env.ledger().with_mut(|li| { li.timestamp = 600; });
@@ -140,6 +200,52 @@ fn test_execute_entry() {
     let entry = client.get_entry(&entry_id).unwrap();
     assert!(matches!(entry.status, TimelockStatus::Executed));
     assert_eq!(entry.executed_at, Some(600));
+
+    // The queued call actually ran against the target
+    assert_eq!(MockTargetClient::new(&env, &target).get_value(), Some(21));
+    assert_eq!(entry.error_code, None);
+    let doubled: u32 = client.get_result(&entry_id).unwrap().into_val(&env);
+    assert_eq!(doubled, 42);
+}
+
+#[test]
+fn test_execute_records_target_failure() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, executor) = setup(&env);
+    let target = env.register_contract(None, MockTarget);
+
+    let entry_id = client.queue(
+        &admin, &target, &Symbol::new(&env, "fail"), &no_args(&env), &make_desc(&env), &500u64,
+    );
+    env.ledger().with_mut(|li| { li.timestamp = 600; });
+
+    // Does not revert: the failure is stored on the entry
+    client.execute(&executor, &entry_id);
+
+    let entry = client.get_entry(&entry_id).unwrap();
+    assert!(matches!(entry.status, TimelockStatus::Failed));
+    assert!(entry.error_code.is_some());
+    assert!(client.get_result(&entry_id).is_none());
+    assert!(!client.is_ready(&entry_id));
+}
+
+#[test]
+#[should_panic(expected = "entry not queued")]
+fn test_execute_twice_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, executor) = setup(&env);
+    let target = env.register_contract(None, MockTarget);
+    let args: Vec<Val> = vec![&env, 1u32.into_val(&env)];
+
+    let entry_id = client.queue(
+        &admin, &target, &Symbol::new(&env, "set_value"), &args, &make_desc(&env), &500u64,
+    );
+    env.ledger().with_mut(|li| { li.timestamp = 600; });
+
+    client.execute(&executor, &entry_id);
+    client.execute(&executor, &entry_id);
 }
 
 #[test]
@@ -150,7 +256,7 @@ fn test_execute_too_early() {
     let (client, admin, executor) = setup(&env);
     let target = Address::generate(&env);
 
-    let entry_id = client.queue(&admin, &target, &make_fn(&env), &make_desc(&env), &500u64);
+    let entry_id = client.queue(&admin, &target, &make_fn(&env), &no_args(&env), &make_desc(&env), &500u64);
 
     // timestamp is still 0, ETA is 500
     client.execute(&executor, &entry_id);
@@ -165,7 +271,7 @@ fn test_execute_wrong_executor() {
     let target = Address::generate(&env);
     let stranger = Address::generate(&env);
 
-    let entry_id = client.queue(&admin, &target, &make_fn(&env), &make_desc(&env), &500u64);
+    let entry_id = client.queue(&admin, &target, &make_fn(&env), &no_args(&env), &make_desc(&env), &500u64);
     env.ledger().with_mut(|li| { li.timestamp = 600; });
 
     client.execute(&stranger, &entry_id);
@@ -179,7 +285,7 @@ fn test_execute_after_grace_period() {
     let (client, admin, executor) = setup(&env);
     let target = Address::generate(&env);
 
-    let entry_id = client.queue(&admin, &target, &make_fn(&env), &make_desc(&env), &500u64);
+    let entry_id = client.queue(&admin, &target, &make_fn(&env), &no_args(&env), &make_desc(&env), &500u64);
 
     // grace_period = 172_800 (2 days), ETA = 500
     // Advance way beyond grace period
@@ -188,6 +294,21 @@ fn test_execute_after_grace_period() {
     client.execute(&executor, &entry_id);
 }
 
+#[test]
+fn test_status_expired_after_grace_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, _) = setup(&env);
+    let target = Address::generate(&env);
+
+    let entry_id = client.queue(&admin, &target, &make_fn(&env), &no_args(&env), &make_desc(&env), &500u64);
+    env.ledger().with_mut(|li| { li.timestamp = 500 + 172_800; });
+    assert!(matches!(client.get_status(&entry_id), Some(TimelockStatus::Queued)));
+
+    env.ledger().with_mut(|li| { li.timestamp = 500 + 172_800 + 1; });
+    assert!(matches!(client.get_status(&entry_id), Some(TimelockStatus::Expired)));
+}
+
 // ─── cancel ──────────────────────────────────────────────────────────────────
 
 #[test]
@@ -197,7 +318,7 @@ fn test_cancel_entry() {
     let (client, admin, _) = setup(&env);
     let target = Address::generate(&env);
 
-    let entry_id = client.queue(&admin, &target, &make_fn(&env), &make_desc(&env), &500u64);
+    let entry_id = client.queue(&admin, &target, &make_fn(&env), &no_args(&env), &make_desc(&env), &500u64);
     client.cancel(&admin, &entry_id);
 
     let entry = client.get_entry(&entry_id).unwrap();
@@ -213,7 +334,7 @@ fn test_cancel_by_non_admin() {
     let target = Address::generate(&env);
     let stranger = Address::generate(&env);
 
-    let entry_id = client.queue(&admin, &target, &make_fn(&env), &make_desc(&env), &500u64);
+    let entry_id = client.queue(&admin, &target, &make_fn(&env), &no_args(&env), &make_desc(&env), &500u64);
     client.cancel(&stranger, &entry_id);
 }
 
@@ -225,7 +346,7 @@ fn test_cancel_already_cancelled() {
     let (client, admin, _) = setup(&env);
     let target = Address::generate(&env);
 
-    let entry_id = client.queue(&admin, &target, &make_fn(&env), &make_desc(&env), &500u64);
+    let entry_id = client.queue(&admin, &target, &make_fn(&env), &no_args(&env), &make_desc(&env), &500u64);
     client.cancel(&admin, &entry_id);
     client.cancel(&admin, &entry_id); // already cancelled
 }
@@ -239,7 +360,7 @@ fn test_is_ready_before_eta() {
     let (client, admin, _) = setup(&env);
     let target = Address::generate(&env);
 
-    let entry_id = client.queue(&admin, &target, &make_fn(&env), &make_desc(&env), &500u64);
+    let entry_id = client.queue(&admin, &target, &make_fn(&env), &no_args(&env), &make_desc(&env), &500u64);
     assert!(!client.is_ready(&entry_id)); // timestamp=0 < eta=500
 }
 
@@ -250,7 +371,7 @@ fn test_is_ready_at_eta() {
     let (client, admin, _) = setup(&env);
     let target = Address::generate(&env);
 
-    let entry_id = client.queue(&admin, &target, &make_fn(&env), &make_desc(&env), &500u64);
+    let entry_id = client.queue(&admin, &target, &make_fn(&env), &no_args(&env), &make_desc(&env), &500u64);
     env.ledger().with_mut(|li| { li.timestamp = 500; });
 
     assert!(client.is_ready(&entry_id));
@@ -1,10 +1,27 @@
 //! PulsarTrack - Token Bridge (Soroban)
 //! Cross-chain token bridge for multi-network ad campaign funding on Stellar.
+//!
+//! Every destination chain and token carries transfer limits: a per-transfer
+//! minimum and maximum, and a cap on what may leave in any rolling 24 hours.
+//! A transfer that would exceed a cap is accepted but `Queued`; it can only
+//! be relayed once `release_queued` is called after the release delay, which
+//! gives the admin time to refund it. Releasing it still has to fit within
+//! the caps unless the admin or the guardian approves it. Chains added
+//! before per-chain configs existed are read as a config with their old
+//! daily limit as the cap. The admin or the guardian can `pause`
+//! a chain, which stops deposits, releases and confirmations for it.
+//!
+//! Events:
+//! - ("bridge", "deposit"): [deposit_id: u64, sender: Address, net_amount: i128]
+//! - ("bridge", "queued"): [deposit_id: u64, release_after: u64]
+//! - ("bridge", "released"): deposit_id: u64
+//! - ("bridge", "confirmed"): deposit_id: u64
+//! - ("bridge", "paused"): [chain: String, paused: bool]
 
 #![no_std]
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short,
-    token, Address, Bytes, BytesN, Env, String,
+    token, Address, BytesN, Env, String, TryFromVal, Val, Vec,
 };
 
 #[contracttype]
@@ -15,6 +32,7 @@ pub enum BridgeStatus {
     Completed,
     Failed,
     Refunded,
+    Queued,
 }
 
 #[contracttype]
@@ -31,6 +49,37 @@ pub struct BridgeDeposit {
     pub created_at: u64,
     pub completed_at: Option<u64>,
     pub tx_hash: Option<BytesN<32>>,
+    pub release_after: Option<u64>,
+}
+
+/// Limits for one destination chain. Zero means "no limit" for
+/// `max_transfer` and `daily_cap`; `fee_bps` overrides the global fee.
+#[contracttype]
+#[derive(Clone)]
+pub struct ChainConfig {
+    pub min_transfer: i128,
+    pub max_transfer: i128,
+    pub daily_cap: i128,
+    pub fee_bps: Option<u32>,
+    pub paused: bool,
+}
+
+/// Limits for one bridged token, across all chains. Zero means "no limit"
+/// for `max_transfer` and `daily_cap`.
+#[contracttype]
+#[derive(Clone)]
+pub struct TokenConfig {
+    pub min_transfer: i128,
+    pub max_transfer: i128,
+    pub daily_cap: i128,
+}
+
+/// Amount bridged during one hour, for the rolling 24h window
+#[contracttype]
+#[derive(Clone)]
+pub struct OutflowBucket {
+    pub hour: u64,
+    pub amount: i128,
 }
 
 #[contracttype]
@@ -38,15 +87,23 @@ pub enum DataKey {
     Admin,
     DepositCounter,
     BridgeFeesBps,
-    SupportedChain(String),
+    SupportedChain(String), // ChainConfig; an i128 daily limit before chain configs
     Deposit(u64),
     RelayerAddress,
+    Guardian,
+    TokenConfig(Address),
+    ChainOutflow(String),
+    TokenOutflow(Address),
+    ReleaseDelay,
 }
 
 const INSTANCE_LIFETIME_THRESHOLD: u32 = 17_280;
 const INSTANCE_BUMP_AMOUNT: u32 = 86_400;
 const PERSISTENT_LIFETIME_THRESHOLD: u32 = 120_960;
 const PERSISTENT_BUMP_AMOUNT: u32 = 1_051_200;
+const HOUR_SECS: u64 = 3_600;
+const WINDOW_HOURS: u64 = 24;
+const DEFAULT_RELEASE_DELAY_SECS: u64 = 86_400;
 
 #[contract]
 pub struct TokenBridgeContract;
@@ -72,11 +129,67 @@ impl TokenBridgeContract {
         if admin != stored_admin {
             panic!("unauthorized");
         }
-        let _ttl_key = DataKey::SupportedChain(chain);
-        env.storage().persistent().set(&_ttl_key, &max_daily_limit);
+        // Re-adding a chain only updates its cap
+        let config = match Self::_chain_config(&env, &chain) {
+            Some(existing) => ChainConfig { daily_cap: max_daily_limit, ..existing },
+            None => Self::_legacy_config(max_daily_limit),
+        };
+        Self::_save_chain(&env, &chain, &config);
+    }
+
+    pub fn set_chain_config(env: Env, admin: Address, chain: String, config: ChainConfig) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        if !env.storage().persistent().has(&DataKey::SupportedChain(chain.clone())) {
+            panic!("chain not supported");
+        }
+        Self::_validate_limits(config.min_transfer, config.max_transfer, config.daily_cap);
+        if config.fee_bps.unwrap_or(0) > 10_000 {
+            panic!("invalid fee");
+        }
+        Self::_save_chain(&env, &chain, &config);
+    }
+
+    pub fn set_token_config(env: Env, admin: Address, token: Address, config: TokenConfig) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        Self::_validate_limits(config.min_transfer, config.max_transfer, config.daily_cap);
+        let _ttl_key = DataKey::TokenConfig(token);
+        env.storage().persistent().set(&_ttl_key, &config);
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
     }
 
+    pub fn set_guardian(env: Env, admin: Address, guardian: Address) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::Guardian, &guardian);
+    }
+
+    pub fn set_release_delay(env: Env, admin: Address, delay_secs: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::ReleaseDelay, &delay_secs);
+    }
+
+    /// Circuit breaker: admin or guardian halts all traffic to `chain`.
+    pub fn pause(env: Env, caller: Address, chain: String) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        caller.require_auth();
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        let guardian: Option<Address> = env.storage().instance().get(&DataKey::Guardian);
+        if caller != admin && guardian != Some(caller.clone()) {
+            panic!("unauthorized");
+        }
+        Self::_set_paused(&env, chain, true);
+    }
+
+    /// Only the admin can reopen a paused chain.
+    pub fn unpause(env: Env, admin: Address, chain: String) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_require_admin(&env, &admin);
+        Self::_set_paused(&env, chain, false);
+    }
+
     pub fn deposit_for_bridge(
         env: Env,
         sender: Address,
@@ -89,15 +202,32 @@ impl TokenBridgeContract {
         sender.require_auth();
 
         // Verify chain is supported
-        if !env.storage().persistent().has(&DataKey::SupportedChain(recipient_chain.clone())) {
-            panic!("chain not supported");
+        let chain_config = Self::_chain_config(&env, &recipient_chain).expect("chain not supported");
+        if chain_config.paused {
+            panic!("chain paused");
         }
 
         if amount <= 0 {
             panic!("invalid amount");
         }
+        let token_config: Option<TokenConfig> = env.storage().persistent().get(&DataKey::TokenConfig(token.clone()));
+        Self::_check_transfer(amount, chain_config.min_transfer, chain_config.max_transfer);
+        if let Some(ref config) = token_config {
+            Self::_check_transfer(amount, config.min_transfer, config.max_transfer);
+        }
+
+        // Over either rolling cap: accept, but hold for delayed release
+        let chain_key = DataKey::ChainOutflow(recipient_chain.clone());
+        let token_key = DataKey::TokenOutflow(token.clone());
+        let within_caps = Self::_within_cap(&env, &chain_key, chain_config.daily_cap, amount)
+            && token_config
+                .map(|config| Self::_within_cap(&env, &token_key, config.daily_cap, amount))
+                .unwrap_or(true);
 
-        let fee_bps: u32 = env.storage().instance().get(&DataKey::BridgeFeesBps).unwrap_or(50);
+        let fee_bps: u32 = match chain_config.fee_bps {
+            Some(bps) => bps,
+            None => env.storage().instance().get(&DataKey::BridgeFeesBps).unwrap_or(50),
+        };
         let bridge_fee = (amount * fee_bps as i128) / 10_000;
         let net_amount = amount - bridge_fee;
 
@@ -108,6 +238,20 @@ impl TokenBridgeContract {
         let counter: u64 = env.storage().instance().get(&DataKey::DepositCounter).unwrap_or(0);
         let deposit_id = counter + 1;
 
+        let now = env.ledger().timestamp();
+        let release_after = if within_caps {
+            Self::_record_outflow(&env, &chain_key, amount);
+            Self::_record_outflow(&env, &token_key, amount);
+            None
+        } else {
+            let delay: u64 = env
+                .storage()
+                .instance()
+                .get(&DataKey::ReleaseDelay)
+                .unwrap_or(DEFAULT_RELEASE_DELAY_SECS);
+            Some(now + delay)
+        };
+
         let deposit = BridgeDeposit {
             deposit_id,
             sender: sender.clone(),
@@ -116,10 +260,11 @@ impl TokenBridgeContract {
             token,
             amount: net_amount,
             bridge_fee,
-            status: BridgeStatus::Pending,
-            created_at: env.ledger().timestamp(),
+            status: if within_caps { BridgeStatus::Pending } else { BridgeStatus::Queued },
+            created_at: now,
             completed_at: None,
             tx_hash: None,
+            release_after,
         };
 
         let _ttl_key = DataKey::Deposit(deposit_id);
@@ -131,10 +276,66 @@ impl TokenBridgeContract {
             (symbol_short!("bridge"), symbol_short!("deposit")),
             (deposit_id, sender, net_amount),
         );
+        if let Some(release_after) = release_after {
+            env.events().publish(
+                (symbol_short!("bridge"), symbol_short!("queued")),
+                (deposit_id, release_after),
+            );
+        }
 
         deposit_id
     }
 
+    /// Move a queued transfer to `Pending` once its delay has passed.
+    /// Anyone may release it if it now fits within the caps; the admin or the
+    /// guardian may release it regardless. The released amount counts toward
+    /// the caps.
+    pub fn release_queued(env: Env, caller: Address, deposit_id: u64) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        caller.require_auth();
+
+        let mut deposit: BridgeDeposit = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Deposit(deposit_id))
+            .expect("deposit not found");
+        if deposit.status != BridgeStatus::Queued {
+            panic!("not queued");
+        }
+        if env.ledger().timestamp() < deposit.release_after.unwrap_or(0) {
+            panic!("release delay active");
+        }
+        Self::_require_unpaused(&env, &deposit.recipient_chain);
+
+        let gross = deposit.amount + deposit.bridge_fee;
+        let chain_config = Self::_chain_config(&env, &deposit.recipient_chain).expect("chain not supported");
+        let token_config: Option<TokenConfig> = env.storage().persistent().get(&DataKey::TokenConfig(deposit.token.clone()));
+        let within_caps = Self::_within_cap(&env, &DataKey::ChainOutflow(deposit.recipient_chain.clone()), chain_config.daily_cap, gross)
+            && token_config
+                .map(|config| Self::_within_cap(&env, &DataKey::TokenOutflow(deposit.token.clone()), config.daily_cap, gross))
+                .unwrap_or(true);
+        if !within_caps {
+            let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+            let guardian: Option<Address> = env.storage().instance().get(&DataKey::Guardian);
+            if caller != admin && guardian != Some(caller.clone()) {
+                panic!("daily cap exceeded");
+            }
+        }
+
+        Self::_record_outflow(&env, &DataKey::ChainOutflow(deposit.recipient_chain.clone()), gross);
+        Self::_record_outflow(&env, &DataKey::TokenOutflow(deposit.token.clone()), gross);
+
+        deposit.status = BridgeStatus::Pending;
+        let _ttl_key = DataKey::Deposit(deposit_id);
+        env.storage().persistent().set(&_ttl_key, &deposit);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("bridge"), symbol_short!("released")),
+            deposit_id,
+        );
+    }
+
     pub fn confirm_bridge(
         env: Env,
         relayer: Address,
@@ -157,6 +358,7 @@ impl TokenBridgeContract {
         if deposit.status != BridgeStatus::Pending {
             panic!("not pending");
         }
+        Self::_require_unpaused(&env, &deposit.recipient_chain);
 
         deposit.status = BridgeStatus::Completed;
         deposit.completed_at = Some(env.ledger().timestamp());
@@ -186,7 +388,10 @@ impl TokenBridgeContract {
             .get(&DataKey::Deposit(deposit_id))
             .expect("deposit not found");
 
-        if deposit.status != BridgeStatus::Pending && deposit.status != BridgeStatus::Failed {
+        if !matches!(
+            deposit.status,
+            BridgeStatus::Pending | BridgeStatus::Failed | BridgeStatus::Queued
+        ) {
             panic!("cannot refund");
         }
 
@@ -204,6 +409,137 @@ impl TokenBridgeContract {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         env.storage().persistent().get(&DataKey::Deposit(deposit_id))
     }
+
+    pub fn get_chain_config(env: Env, chain: String) -> Option<ChainConfig> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_chain_config(&env, &chain)
+    }
+
+    pub fn get_token_config(env: Env, token: Address) -> Option<TokenConfig> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::TokenConfig(token))
+    }
+
+    /// Amount bridged to `chain` over the last 24 hours
+    pub fn get_chain_outflow(env: Env, chain: String) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_window_total(&env, &DataKey::ChainOutflow(chain))
+    }
+
+    /// Amount of `token` bridged over the last 24 hours
+    pub fn get_token_outflow(env: Env, token: Address) -> i128 {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        Self::_window_total(&env, &DataKey::TokenOutflow(token))
+    }
+
+    // ── internal helpers ─────────────────────────────────────────────────────
+
+    fn _require_admin(env: &Env, admin: &Address) {
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if *admin != stored_admin {
+            panic!("unauthorized");
+        }
+    }
+
+    fn _save_chain(env: &Env, chain: &String, config: &ChainConfig) {
+        let _ttl_key = DataKey::SupportedChain(chain.clone());
+        env.storage().persistent().set(&_ttl_key, config);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+
+    /// A chain's config, reading chains added before configs existed (stored
+    /// as a bare daily limit) as an unrestricted config with that cap.
+    fn _chain_config(env: &Env, chain: &String) -> Option<ChainConfig> {
+        let stored: Val = env.storage().persistent().get(&DataKey::SupportedChain(chain.clone()))?;
+        if let Ok(config) = ChainConfig::try_from_val(env, &stored) {
+            return Some(config);
+        }
+        let daily_cap = i128::try_from_val(env, &stored).expect("invalid chain config");
+        Some(Self::_legacy_config(daily_cap))
+    }
+
+    fn _legacy_config(daily_cap: i128) -> ChainConfig {
+        ChainConfig {
+            min_transfer: 0,
+            max_transfer: 0,
+            daily_cap,
+            fee_bps: None,
+            paused: false,
+        }
+    }
+
+    fn _set_paused(env: &Env, chain: String, paused: bool) {
+        let mut config = Self::_chain_config(env, &chain).expect("chain not supported");
+        config.paused = paused;
+        Self::_save_chain(env, &chain, &config);
+        env.events().publish(
+            (symbol_short!("bridge"), symbol_short!("paused")),
+            (chain, paused),
+        );
+    }
+
+    fn _require_unpaused(env: &Env, chain: &String) {
+        let paused = Self::_chain_config(env, chain)
+            .map(|config| config.paused)
+            .unwrap_or(false);
+        if paused {
+            panic!("chain paused");
+        }
+    }
+
+    fn _validate_limits(min_transfer: i128, max_transfer: i128, daily_cap: i128) {
+        if min_transfer < 0 || max_transfer < 0 || daily_cap < 0 {
+            panic!("invalid limits");
+        }
+        if max_transfer > 0 && min_transfer > max_transfer {
+            panic!("invalid limits");
+        }
+    }
+
+    fn _check_transfer(amount: i128, min_transfer: i128, max_transfer: i128) {
+        if amount < min_transfer {
+            panic!("amount below minimum");
+        }
+        if max_transfer > 0 && amount > max_transfer {
+            panic!("amount above maximum");
+        }
+    }
+
+    /// Buckets still inside the rolling window
+    fn _window(env: &Env, key: &DataKey) -> Vec<OutflowBucket> {
+        let current_hour = env.ledger().timestamp() / HOUR_SECS;
+        let buckets: Vec<OutflowBucket> = env.storage().persistent().get(key).unwrap_or(Vec::new(env));
+        let mut live = Vec::new(env);
+        for bucket in buckets.iter() {
+            if bucket.hour + WINDOW_HOURS > current_hour {
+                live.push_back(bucket);
+            }
+        }
+        live
+    }
+
+    fn _window_total(env: &Env, key: &DataKey) -> i128 {
+        Self::_window(env, key).iter().map(|bucket| bucket.amount).sum()
+    }
+
+    fn _within_cap(env: &Env, key: &DataKey, daily_cap: i128, amount: i128) -> bool {
+        daily_cap == 0 || Self::_window_total(env, key) + amount <= daily_cap
+    }
+
+    fn _record_outflow(env: &Env, key: &DataKey, amount: i128) {
+        let current_hour = env.ledger().timestamp() / HOUR_SECS;
+        let mut buckets = Self::_window(env, key);
+        match buckets.last() {
+            Some(mut last) if last.hour == current_hour => {
+                last.amount += amount;
+                buckets.set(buckets.len() - 1, last);
+            }
+            _ => buckets.push_back(OutflowBucket { hour: current_hour, amount }),
+        }
+        env.storage().persistent().set(key, &buckets);
+        env.storage().persistent().extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
 }
 
 mod test;
@@ -1,6 +1,10 @@
 #![cfg(test)]
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env, String,
+};
 
 fn setup(env: &Env) -> (TokenBridgeContractClient, Address) {
     let admin = Address::generate(env);
@@ -55,3 +59,221 @@ fn test_get_deposit_nonexistent() {
     let (c, _) = setup(&env);
     assert!(c.get_deposit(&999u64).is_none());
 }
+
+// ─── limits, caps and pausing ────────────────────────────────────────────────
+
+/// Bridge with "ethereum" capped at 1_000 per 24h and a funded sender
+fn setup_limits(env: &Env) -> (TokenBridgeContractClient<'_>, Address, Address, Address) {
+    let (c, admin) = setup(env);
+    c.add_supported_chain(&admin, &s(env, "ethereum"), &1_000i128);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    let sender = Address::generate(env);
+    StellarAssetClient::new(env, &token).mint(&sender, &100_000);
+    (c, admin, token, sender)
+}
+
+fn bridge(env: &Env, c: &TokenBridgeContractClient, token: &Address, sender: &Address, amount: i128) -> u64 {
+    c.deposit_for_bridge(sender, token, &amount, &s(env, "ethereum"), &s(env, "0xabc"))
+}
+
+#[test]
+fn test_deposit_over_cap_is_queued() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, token, sender) = setup_limits(&env);
+
+    let first = bridge(&env, &c, &token, &sender, 800);
+    assert!(c.get_deposit(&first).unwrap().status == BridgeStatus::Pending);
+    let second = bridge(&env, &c, &token, &sender, 400);
+    let queued = c.get_deposit(&second).unwrap();
+    assert!(queued.status == BridgeStatus::Queued);
+    assert_eq!(queued.release_after, Some(86_400));
+    assert_eq!(c.get_chain_outflow(&s(&env, "ethereum")), 800);
+    // Tokens are still locked while queued
+    assert_eq!(TokenClient::new(&env, &token).balance(&c.address), 1_200);
+}
+
+#[test]
+#[should_panic(expected = "release delay active")]
+fn test_release_queued_before_delay() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, token, sender) = setup_limits(&env);
+    let id = bridge(&env, &c, &token, &sender, 1_500);
+    c.release_queued(&admin, &id);
+}
+
+#[test]
+fn test_release_queued_after_delay() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, token, sender) = setup_limits(&env);
+    let id = bridge(&env, &c, &token, &sender, 1_500);
+
+    env.ledger().with_mut(|li| li.timestamp = 86_400);
+    c.release_queued(&admin, &id);
+    assert!(c.get_deposit(&id).unwrap().status == BridgeStatus::Pending);
+    assert_eq!(c.get_chain_outflow(&s(&env, "ethereum")), 1_500);
+
+    // The window rolls off after 24 hours
+    env.ledger().with_mut(|li| li.timestamp = 2 * 86_400);
+    assert_eq!(c.get_chain_outflow(&s(&env, "ethereum")), 0);
+}
+
+#[test]
+fn test_release_queued_by_anyone_once_within_cap() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, token, sender) = setup_limits(&env);
+    bridge(&env, &c, &token, &sender, 800);
+    let id = bridge(&env, &c, &token, &sender, 400);
+
+    // The first transfer has rolled out of the window by now
+    env.ledger().with_mut(|li| li.timestamp = 86_400);
+    c.release_queued(&Address::generate(&env), &id);
+    assert!(c.get_deposit(&id).unwrap().status == BridgeStatus::Pending);
+}
+
+#[test]
+#[should_panic(expected = "daily cap exceeded")]
+fn test_release_queued_over_cap_needs_approval() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, token, sender) = setup_limits(&env);
+    let id = bridge(&env, &c, &token, &sender, 1_500);
+
+    env.ledger().with_mut(|li| li.timestamp = 86_400);
+    c.release_queued(&Address::generate(&env), &id);
+}
+
+#[test]
+fn test_guardian_approves_release_over_cap() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, token, sender) = setup_limits(&env);
+    let guardian = Address::generate(&env);
+    c.set_guardian(&admin, &guardian);
+    let id = bridge(&env, &c, &token, &sender, 1_500);
+
+    env.ledger().with_mut(|li| li.timestamp = 86_400);
+    c.release_queued(&guardian, &id);
+    assert!(c.get_deposit(&id).unwrap().status == BridgeStatus::Pending);
+}
+
+#[test]
+fn test_readding_chain_keeps_config_and_pause() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, _, _) = setup_limits(&env);
+    let chain = s(&env, "ethereum");
+    c.set_chain_config(&admin, &chain, &ChainConfig { min_transfer: 10, max_transfer: 500, daily_cap: 1_000, fee_bps: Some(20), paused: false });
+    c.pause(&admin, &chain);
+
+    c.add_supported_chain(&admin, &chain, &2_000i128);
+    let config = c.get_chain_config(&chain).unwrap();
+    assert!(config.paused);
+    assert_eq!((config.min_transfer, config.max_transfer, config.daily_cap, config.fee_bps), (10, 500, 2_000, Some(20)));
+}
+
+#[test]
+fn test_legacy_chain_limit_read_as_config() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, token, sender) = setup_limits(&env);
+    let chain = s(&env, "solana");
+    env.as_contract(&c.address, || {
+        env.storage().persistent().set(&DataKey::SupportedChain(chain.clone()), &1_000i128);
+    });
+
+    let config = c.get_chain_config(&chain).unwrap();
+    assert_eq!(config.daily_cap, 1_000);
+    assert!(!config.paused);
+    let id = c.deposit_for_bridge(&sender, &token, &1_500i128, &chain, &s(&env, "0xabc"));
+    assert!(c.get_deposit(&id).unwrap().status == BridgeStatus::Queued);
+
+    // Pausing rewrites it as a full config
+    c.pause(&admin, &chain);
+    assert!(c.get_chain_config(&chain).unwrap().paused);
+}
+
+#[test]
+fn test_token_cap_queues_across_chains() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, token, sender) = setup_limits(&env);
+    c.add_supported_chain(&admin, &s(&env, "polygon"), &0i128);
+    c.set_token_config(&admin, &token, &TokenConfig { min_transfer: 0, max_transfer: 0, daily_cap: 500 });
+
+    let id = c.deposit_for_bridge(&sender, &token, &600i128, &s(&env, "polygon"), &s(&env, "0xabc"));
+    assert!(c.get_deposit(&id).unwrap().status == BridgeStatus::Queued);
+}
+
+#[test]
+#[should_panic(expected = "amount above maximum")]
+fn test_deposit_above_chain_maximum() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, token, sender) = setup_limits(&env);
+    c.set_chain_config(&admin, &s(&env, "ethereum"), &ChainConfig {
+        min_transfer: 10,
+        max_transfer: 500,
+        daily_cap: 1_000,
+        fee_bps: None,
+        paused: false,
+    });
+    bridge(&env, &c, &token, &sender, 501);
+}
+
+#[test]
+#[should_panic(expected = "amount below minimum")]
+fn test_deposit_below_token_minimum() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, token, sender) = setup_limits(&env);
+    c.set_token_config(&admin, &token, &TokenConfig { min_transfer: 100, max_transfer: 0, daily_cap: 0 });
+    bridge(&env, &c, &token, &sender, 99);
+}
+
+#[test]
+fn test_chain_fee_overrides_global() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, token, sender) = setup_limits(&env);
+    c.set_chain_config(&admin, &s(&env, "ethereum"), &ChainConfig {
+        min_transfer: 0,
+        max_transfer: 0,
+        daily_cap: 1_000,
+        fee_bps: Some(200),
+        paused: false,
+    });
+    let deposit = c.get_deposit(&bridge(&env, &c, &token, &sender, 1_000)).unwrap();
+    assert_eq!(deposit.bridge_fee, 20);
+    assert_eq!(deposit.amount, 980);
+}
+
+#[test]
+#[should_panic(expected = "chain paused")]
+fn test_guardian_pause_blocks_deposits() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, token, sender) = setup_limits(&env);
+    let guardian = Address::generate(&env);
+    c.set_guardian(&admin, &guardian);
+    c.pause(&guardian, &s(&env, "ethereum"));
+    bridge(&env, &c, &token, &sender, 100);
+}
+
+#[test]
+fn test_unpause_reopens_chain() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, token, sender) = setup_limits(&env);
+    c.pause(&admin, &s(&env, "ethereum"));
+    c.unpause(&admin, &s(&env, "ethereum"));
+    bridge(&env, &c, &token, &sender, 100);
+    assert!(!c.get_chain_config(&s(&env, "ethereum")).unwrap().paused);
+}
+
+#[test]
+#[should_panic(expected = "unauthorized")]
+fn test_pause_by_stranger() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, _, _) = setup_limits(&env);
+    c.pause(&Address::generate(&env), &s(&env, "ethereum"));
+}
+
+#[test]
+fn test_refund_queued_deposit() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, token, sender) = setup_limits(&env);
+    let id = bridge(&env, &c, &token, &sender, 1_500);
+    c.refund_deposit(&admin, &id);
+    assert!(c.get_deposit(&id).unwrap().status == BridgeStatus::Refunded);
+    assert_eq!(TokenClient::new(&env, &token).balance(&sender), 100_000);
+}
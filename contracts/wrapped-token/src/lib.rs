@@ -1,10 +1,24 @@
 //! PulsarTrack - Wrapped Token Manager (Soroban)
 //! Manages wrapped tokens from other chains for use in PulsarTrack campaigns on Stellar.
+//!
+//! Each wrapped token is a Stellar asset contract whose admin is this
+//! contract. Minting and burning go through that token, so wrapped balances
+//! can be transferred, approved and spent like any other payment token.
+//!
+//! A source-chain transaction can be minted against only once, tracked per
+//! (underlying chain, source tx). When an attester set is configured, every
+//! mint must also be authorized by at least `threshold` distinct attesters.
+//! `burn_wrapped` records a withdrawal request under a sequential nonce,
+//! with a sha256 digest the relayer can prove the release against.
+//!
+//! Events:
+//! - ("wrapped", "minted"): [record_id: u64, recipient: Address, amount: i128]
+//! - ("wrapped", "burned"): [nonce: u64, user: Address, amount: i128, target_address: String, digest: BytesN<32>]
 
 #![no_std]
 use soroban_sdk::{
     contract, contractimpl, contracttype, symbol_short,
-    token, Address, Env, String,
+    token, xdr::ToXdr, Address, BytesN, Env, String, Vec,
 };
 
 #[contracttype]
@@ -31,6 +45,20 @@ pub struct WrapRecord {
     pub wrapped_at: u64,
 }
 
+#[contracttype]
+#[derive(Clone)]
+pub struct WithdrawalRequest {
+    pub nonce: u64,
+    pub user: Address,
+    pub token: String,
+    pub underlying_chain: String,
+    pub underlying_address: String,
+    pub target_address: String,
+    pub amount: i128,
+    pub requested_at: u64,
+    pub digest: BytesN<32>,
+}
+
 #[contracttype]
 pub enum DataKey {
     Admin,
@@ -38,7 +66,11 @@ pub enum DataKey {
     WrapRecordCounter,
     WrappedToken(String),  // symbol
     WrapRecord(u64),
-    UserBalance(String, Address),  // symbol, user
+    ProcessedSourceTx(String, String), // underlying_chain, source_tx -> record_id
+    Attesters,
+    AttestationThreshold,
+    WithdrawalNonce,
+    Withdrawal(u64),
 }
 
 const INSTANCE_LIFETIME_THRESHOLD: u32 = 17_280;
@@ -79,6 +111,12 @@ impl WrappedTokenContract {
             panic!("unauthorized");
         }
 
+        // Minting requires this contract to be the token's admin
+        let asset_admin = token::StellarAssetClient::new(&env, &stellar_token).admin();
+        if asset_admin != env.current_contract_address() {
+            panic!("wrapper is not token admin");
+        }
+
         let wrapped = WrappedToken {
             symbol: symbol.clone(),
             name,
@@ -95,6 +133,27 @@ impl WrappedTokenContract {
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
     }
 
+    /// Admin: require `threshold` of `attesters` to co-authorize each mint.
+    /// A threshold of zero leaves minting to the relayer alone.
+    pub fn set_attesters(env: Env, admin: Address, attesters: Vec<Address>, threshold: u32) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        admin.require_auth();
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if admin != stored_admin {
+            panic!("unauthorized");
+        }
+        if threshold > attesters.len() {
+            panic!("invalid threshold");
+        }
+        for (i, attester) in attesters.iter().enumerate() {
+            if attesters.first_index_of(&attester) != Some(i as u32) {
+                panic!("duplicate attester");
+            }
+        }
+        env.storage().instance().set(&DataKey::Attesters, &attesters);
+        env.storage().instance().set(&DataKey::AttestationThreshold, &threshold);
+    }
+
     pub fn mint_wrapped(
         env: Env,
         relayer: Address,
@@ -102,6 +161,7 @@ impl WrappedTokenContract {
         recipient: Address,
         amount: i128,
         source_tx: String,
+        attesters: Vec<Address>,
     ) -> u64 {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         relayer.require_auth();
@@ -120,13 +180,18 @@ impl WrappedTokenContract {
             panic!("token not active");
         }
 
+        if amount <= 0 {
+            panic!("invalid amount");
+        }
+
+        let processed_key = DataKey::ProcessedSourceTx(wrapped.underlying_chain.clone(), source_tx.clone());
+        if env.storage().persistent().has(&processed_key) {
+            panic!("source tx already processed");
+        }
+        Self::_check_attestations(&env, &attesters);
+
         // Mint stellar-side tokens
-        // NOTE: This would use the token contract's mint function in production
-        // For now, we track the internal balance
-        let key = DataKey::UserBalance(symbol.clone(), recipient.clone());
-        let current: i128 = env.storage().persistent().get(&key).unwrap_or(0);
-        env.storage().persistent().set(&key, &(current + amount));
-        env.storage().persistent().extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        token::StellarAssetClient::new(&env, &wrapped.stellar_token).mint(&recipient, &amount);
 
         wrapped.total_wrapped += amount;
         let _ttl_key = DataKey::WrappedToken(symbol.clone());
@@ -149,6 +214,8 @@ impl WrappedTokenContract {
         env.storage().persistent().set(&_ttl_key, &record);
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
         env.storage().instance().set(&DataKey::WrapRecordCounter, &record_id);
+        env.storage().persistent().set(&processed_key, &record_id);
+        env.storage().persistent().extend_ttl(&processed_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
 
         env.events().publish(
             (symbol_short!("wrapped"), symbol_short!("minted")),
@@ -158,35 +225,67 @@ impl WrappedTokenContract {
         record_id
     }
 
-    pub fn burn_wrapped(env: Env, user: Address, symbol: String, amount: i128, target_address: String) {
+    /// Burn wrapped tokens and record a withdrawal request for the relayer.
+    /// Returns the request nonce.
+    pub fn burn_wrapped(env: Env, user: Address, symbol: String, amount: i128, target_address: String) -> u64 {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         user.require_auth();
 
-        let key = DataKey::UserBalance(symbol.clone(), user.clone());
-        let current: i128 = env.storage().persistent().get(&key).unwrap_or(0);
-
-        if current < amount {
-            panic!("insufficient balance");
-        }
-
-        env.storage().persistent().set(&key, &(current - amount));
-        env.storage().persistent().extend_ttl(&key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
-
         let mut wrapped: WrappedToken = env
             .storage()
             .persistent()
             .get(&DataKey::WrappedToken(symbol.clone()))
             .expect("token not registered");
 
+        if amount <= 0 {
+            panic!("invalid amount");
+        }
+        let token_client = token::Client::new(&env, &wrapped.stellar_token);
+        if token_client.balance(&user) < amount {
+            panic!("insufficient balance");
+        }
+        token_client.burn(&user, &amount);
+
         wrapped.total_wrapped -= amount;
-        let _ttl_key = DataKey::WrappedToken(symbol);
+        let _ttl_key = DataKey::WrappedToken(symbol.clone());
         env.storage().persistent().set(&_ttl_key, &wrapped);
         env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
 
+        let nonce: u64 = env.storage().instance().get(&DataKey::WithdrawalNonce).unwrap_or(0) + 1;
+        let requested_at = env.ledger().timestamp();
+
+        // Digest binds this contract, the nonce and every field of the request
+        let mut data = env.current_contract_address().to_xdr(&env);
+        data.extend_from_array(&nonce.to_be_bytes());
+        data.append(&user.clone().to_xdr(&env));
+        data.append(&wrapped.underlying_chain.clone().to_xdr(&env));
+        data.append(&wrapped.underlying_address.clone().to_xdr(&env));
+        data.append(&target_address.clone().to_xdr(&env));
+        data.extend_from_array(&amount.to_be_bytes());
+        data.extend_from_array(&requested_at.to_be_bytes());
+        let digest: BytesN<32> = env.crypto().sha256(&data).into();
+
+        let request = WithdrawalRequest {
+            nonce,
+            user: user.clone(),
+            token: symbol,
+            underlying_chain: wrapped.underlying_chain,
+            underlying_address: wrapped.underlying_address,
+            target_address: target_address.clone(),
+            amount,
+            requested_at,
+            digest: digest.clone(),
+        };
+        let _ttl_key = DataKey::Withdrawal(nonce);
+        env.storage().persistent().set(&_ttl_key, &request);
+        env.storage().persistent().extend_ttl(&_ttl_key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+        env.storage().instance().set(&DataKey::WithdrawalNonce, &nonce);
+
         env.events().publish(
             (symbol_short!("wrapped"), symbol_short!("burned")),
-            (user, amount, target_address),
+            (nonce, user, amount, target_address, digest),
         );
+        nonce
     }
 
     pub fn get_wrapped_token(env: Env, symbol: String) -> Option<WrappedToken> {
@@ -194,9 +293,53 @@ impl WrappedTokenContract {
         env.storage().persistent().get(&DataKey::WrappedToken(symbol))
     }
 
+    pub fn get_withdrawal(env: Env, nonce: u64) -> Option<WithdrawalRequest> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::Withdrawal(nonce))
+    }
+
+    /// Wrap record minted for a source-chain transaction, if any
+    pub fn get_processed_source_tx(env: Env, underlying_chain: String, source_tx: String) -> Option<u64> {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage().persistent().get(&DataKey::ProcessedSourceTx(underlying_chain, source_tx))
+    }
+
+    pub fn get_attesters(env: Env) -> (Vec<Address>, u32) {
+        env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let attesters = env.storage().instance().get(&DataKey::Attesters).unwrap_or(Vec::new(&env));
+        let threshold = env.storage().instance().get(&DataKey::AttestationThreshold).unwrap_or(0);
+        (attesters, threshold)
+    }
+
     pub fn get_user_balance(env: Env, symbol: String, user: Address) -> i128 {
         env.storage().instance().extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-        env.storage().persistent().get(&DataKey::UserBalance(symbol, user)).unwrap_or(0)
+        match env.storage().persistent().get::<_, WrappedToken>(&DataKey::WrappedToken(symbol)) {
+            Some(wrapped) => token::Client::new(&env, &wrapped.stellar_token).balance(&user),
+            None => 0,
+        }
+    }
+
+    /// Each attester must be configured, distinct and authorize this mint
+    fn _check_attestations(env: &Env, attesters: &Vec<Address>) {
+        let threshold: u32 = env.storage().instance().get(&DataKey::AttestationThreshold).unwrap_or(0);
+        if threshold == 0 {
+            return;
+        }
+        let allowed: Vec<Address> = env.storage().instance().get(&DataKey::Attesters).unwrap();
+        let mut seen: Vec<Address> = Vec::new(env);
+        for attester in attesters.iter() {
+            if !allowed.contains(&attester) {
+                panic!("unknown attester");
+            }
+            if seen.contains(&attester) {
+                panic!("duplicate attester");
+            }
+            attester.require_auth();
+            seen.push_back(attester);
+        }
+        if seen.len() < threshold {
+            panic!("insufficient attestations");
+        }
     }
 }
 
@@ -1,6 +1,10 @@
 #![cfg(test)]
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use soroban_sdk::{
+    testutils::Address as _,
+    token::Client as TokenClient,
+    vec, Address, Env, String,
+};
 
 fn setup(env: &Env) -> (WrappedTokenContractClient, Address, Address) {
     let admin = Address::generate(env);
@@ -12,6 +16,11 @@ fn setup(env: &Env) -> (WrappedTokenContractClient, Address, Address) {
 }
 fn s(env: &Env, v: &str) -> String { String::from_str(env, v) }
 
+/// Stellar asset contract administered by the wrapper
+fn wrapped_asset(env: &Env, c: &WrappedTokenContractClient) -> Address {
+    env.register_stellar_asset_contract_v2(c.address.clone()).address()
+}
+
 #[test]
 fn test_initialize() { let env = Env::default(); env.mock_all_auths(); setup(&env); }
 
@@ -38,7 +47,7 @@ fn test_initialize_non_admin_fails() {
 fn test_register_wrapped_token() {
     let env = Env::default(); env.mock_all_auths();
     let (c, admin, _) = setup(&env);
-    let stellar_token = Address::generate(&env);
+    let stellar_token = wrapped_asset(&env, &c);
     c.register_wrapped_token(&admin, &s(&env, "wETH"), &s(&env, "Wrapped Ether"), &8u32, &s(&env, "ethereum"), &s(&env, "0xAddr"), &stellar_token);
     let token = c.get_wrapped_token(&s(&env, "wETH")).unwrap();
     assert_eq!(token.decimals, 8);
@@ -49,9 +58,9 @@ fn test_mint_wrapped() {
     let env = Env::default(); env.mock_all_auths();
     let (c, admin, relayer) = setup(&env);
     let user = Address::generate(&env);
-    let stellar_token = Address::generate(&env);
+    let stellar_token = wrapped_asset(&env, &c);
     c.register_wrapped_token(&admin, &s(&env, "wETH"), &s(&env, "Wrapped Ether"), &8u32, &s(&env, "ethereum"), &s(&env, "0xAddr"), &stellar_token);
-    c.mint_wrapped(&relayer, &s(&env, "wETH"), &user, &1_000_000i128, &s(&env, "0xTxHash"));
+    c.mint_wrapped(&relayer, &s(&env, "wETH"), &user, &1_000_000i128, &s(&env, "0xTxHash"), &vec![&env]);
     assert_eq!(c.get_user_balance(&s(&env, "wETH"), &user), 1_000_000);
 }
 
@@ -60,9 +69,9 @@ fn test_burn_wrapped() {
     let env = Env::default(); env.mock_all_auths();
     let (c, admin, relayer) = setup(&env);
     let user = Address::generate(&env);
-    let stellar_token = Address::generate(&env);
+    let stellar_token = wrapped_asset(&env, &c);
     c.register_wrapped_token(&admin, &s(&env, "wETH"), &s(&env, "Wrapped Ether"), &8u32, &s(&env, "ethereum"), &s(&env, "0xAddr"), &stellar_token);
-    c.mint_wrapped(&relayer, &s(&env, "wETH"), &user, &1_000_000i128, &s(&env, "0xTxHash"));
+    c.mint_wrapped(&relayer, &s(&env, "wETH"), &user, &1_000_000i128, &s(&env, "0xTxHash"), &vec![&env]);
     c.burn_wrapped(&user, &s(&env, "wETH"), &400_000i128, &s(&env, "0xTargetAddr"));
     assert_eq!(c.get_user_balance(&s(&env, "wETH"), &user), 600_000);
 }
@@ -80,3 +89,140 @@ fn test_get_user_balance_zero() {
     let (c, _, _) = setup(&env);
     assert_eq!(c.get_user_balance(&s(&env, "wETH"), &Address::generate(&env)), 0);
 }
+
+#[test]
+#[should_panic(expected = "wrapper is not token admin")]
+fn test_register_token_not_administered_by_wrapper() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, _) = setup(&env);
+    let stellar_token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    c.register_wrapped_token(&admin, &s(&env, "wETH"), &s(&env, "Wrapped Ether"), &8u32, &s(&env, "ethereum"), &s(&env, "0xAddr"), &stellar_token);
+}
+
+#[test]
+fn test_wrapped_balance_is_transferable() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, relayer) = setup(&env);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let stellar_token = wrapped_asset(&env, &c);
+    c.register_wrapped_token(&admin, &s(&env, "wETH"), &s(&env, "Wrapped Ether"), &8u32, &s(&env, "ethereum"), &s(&env, "0xAddr"), &stellar_token);
+    c.mint_wrapped(&relayer, &s(&env, "wETH"), &user, &1_000_000i128, &s(&env, "0xTxHash"), &vec![&env]);
+
+    let tc = TokenClient::new(&env, &stellar_token);
+    tc.approve(&user, &merchant, &300_000i128, &1_000u32);
+    tc.transfer_from(&merchant, &user, &merchant, &300_000i128);
+    assert_eq!(c.get_user_balance(&s(&env, "wETH"), &merchant), 300_000);
+    assert_eq!(c.get_wrapped_token(&s(&env, "wETH")).unwrap().total_wrapped, 1_000_000);
+}
+
+#[test]
+#[should_panic(expected = "insufficient balance")]
+fn test_burn_more_than_balance() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, relayer) = setup(&env);
+    let user = Address::generate(&env);
+    let stellar_token = wrapped_asset(&env, &c);
+    c.register_wrapped_token(&admin, &s(&env, "wETH"), &s(&env, "Wrapped Ether"), &8u32, &s(&env, "ethereum"), &s(&env, "0xAddr"), &stellar_token);
+    c.mint_wrapped(&relayer, &s(&env, "wETH"), &user, &100i128, &s(&env, "0xTxHash"), &vec![&env]);
+    c.burn_wrapped(&user, &s(&env, "wETH"), &101i128, &s(&env, "0xTargetAddr"));
+}
+
+// ─── replay protection, attestations and withdrawals ─────────────────────────
+
+/// Wrapper with "wETH" registered
+fn setup_weth(env: &Env) -> (WrappedTokenContractClient<'_>, Address, Address) {
+    let (c, admin, relayer) = setup(env);
+    let stellar_token = wrapped_asset(env, &c);
+    c.register_wrapped_token(&admin, &s(env, "wETH"), &s(env, "Wrapped Ether"), &8u32, &s(env, "ethereum"), &s(env, "0xAddr"), &stellar_token);
+    (c, admin, relayer)
+}
+
+#[test]
+#[should_panic(expected = "source tx already processed")]
+fn test_mint_same_source_tx_twice() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, relayer) = setup_weth(&env);
+    let user = Address::generate(&env);
+    c.mint_wrapped(&relayer, &s(&env, "wETH"), &user, &100i128, &s(&env, "0xTx1"), &vec![&env]);
+    c.mint_wrapped(&relayer, &s(&env, "wETH"), &user, &100i128, &s(&env, "0xTx1"), &vec![&env]);
+}
+
+#[test]
+fn test_processed_source_tx_recorded() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, relayer) = setup_weth(&env);
+    let record_id = c.mint_wrapped(&relayer, &s(&env, "wETH"), &Address::generate(&env), &100i128, &s(&env, "0xTx1"), &vec![&env]);
+    assert_eq!(c.get_processed_source_tx(&s(&env, "ethereum"), &s(&env, "0xTx1")), Some(record_id));
+    assert!(c.get_processed_source_tx(&s(&env, "polygon"), &s(&env, "0xTx1")).is_none());
+}
+
+#[test]
+fn test_mint_with_attestation_threshold() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, relayer) = setup_weth(&env);
+    let (a1, a2, a3) = (Address::generate(&env), Address::generate(&env), Address::generate(&env));
+    c.set_attesters(&admin, &vec![&env, a1.clone(), a2.clone(), a3], &2u32);
+
+    let user = Address::generate(&env);
+    c.mint_wrapped(&relayer, &s(&env, "wETH"), &user, &100i128, &s(&env, "0xTx1"), &vec![&env, a1, a2]);
+    assert_eq!(c.get_user_balance(&s(&env, "wETH"), &user), 100);
+}
+
+#[test]
+#[should_panic(expected = "insufficient attestations")]
+fn test_mint_below_attestation_threshold() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, relayer) = setup_weth(&env);
+    let (a1, a2) = (Address::generate(&env), Address::generate(&env));
+    c.set_attesters(&admin, &vec![&env, a1.clone(), a2], &2u32);
+    c.mint_wrapped(&relayer, &s(&env, "wETH"), &Address::generate(&env), &100i128, &s(&env, "0xTx1"), &vec![&env, a1]);
+}
+
+#[test]
+#[should_panic(expected = "duplicate attester")]
+fn test_mint_with_repeated_attester() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, relayer) = setup_weth(&env);
+    let (a1, a2) = (Address::generate(&env), Address::generate(&env));
+    c.set_attesters(&admin, &vec![&env, a1.clone(), a2], &2u32);
+    c.mint_wrapped(&relayer, &s(&env, "wETH"), &Address::generate(&env), &100i128, &s(&env, "0xTx1"), &vec![&env, a1.clone(), a1]);
+}
+
+#[test]
+#[should_panic(expected = "unknown attester")]
+fn test_mint_with_unknown_attester() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, relayer) = setup_weth(&env);
+    let a1 = Address::generate(&env);
+    c.set_attesters(&admin, &vec![&env, a1], &1u32);
+    c.mint_wrapped(&relayer, &s(&env, "wETH"), &Address::generate(&env), &100i128, &s(&env, "0xTx1"), &vec![&env, Address::generate(&env)]);
+}
+
+#[test]
+#[should_panic(expected = "invalid threshold")]
+fn test_set_attesters_threshold_above_set() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, admin, _) = setup(&env);
+    c.set_attesters(&admin, &vec![&env, Address::generate(&env)], &2u32);
+}
+
+#[test]
+fn test_burn_records_withdrawal_request() {
+    let env = Env::default(); env.mock_all_auths();
+    let (c, _, relayer) = setup_weth(&env);
+    let user = Address::generate(&env);
+    c.mint_wrapped(&relayer, &s(&env, "wETH"), &user, &1_000i128, &s(&env, "0xTx1"), &vec![&env]);
+
+    let first = c.burn_wrapped(&user, &s(&env, "wETH"), &300i128, &s(&env, "0xTarget"));
+    let second = c.burn_wrapped(&user, &s(&env, "wETH"), &300i128, &s(&env, "0xTarget"));
+    assert_eq!((first, second), (1, 2));
+
+    let a = c.get_withdrawal(&first).unwrap();
+    let b = c.get_withdrawal(&second).unwrap();
+    assert_eq!(a.amount, 300);
+    assert_eq!(a.underlying_chain, s(&env, "ethereum"));
+    assert_eq!(a.target_address, s(&env, "0xTarget"));
+    // Identical requests still get distinct digests through the nonce
+    assert!(a.digest != b.digest);
+}